@@ -14,7 +14,7 @@ pub mod theme {
     wit_bindgen::generate!({
        path: "wit/plugin.wit",
        world: "theme-runtime",
-       with: { "thought:plugin/types": super::types::thought::plugin::types },
+       with: { "thought:plugin/types@0.2.0": super::types::thought::plugin::types },
        pub_export_macro: true,
     });
 }
@@ -23,7 +23,7 @@ pub mod hook {
     wit_bindgen::generate!({
        path: "wit/plugin.wit",
        world: "hook-runtime",
-       with: { "thought:plugin/types": super::types::thought::plugin::types },
+       with: { "thought:plugin/types@0.2.0": super::types::thought::plugin::types },
     generate_unused_types:true,
        pub_export_macro: true,
     });
@@ -43,7 +43,15 @@ pub struct TranslationLink {
 
 pub trait Theme {
     fn generate_page(article: Article) -> String;
-    fn generate_index(articles: Vec<ArticlePreview>) -> String;
+    /// Index page. `locale` is `None` on the root index and set on
+    /// `<locale>/index.html`; see [`helpers::index_root_prefix`].
+    fn generate_index(articles: Vec<ArticlePreview>, locale: Option<String>) -> String;
+    /// Landing page of a series, emitted at `series/<slug>.html`; `articles`
+    /// are its parts in order.
+    fn generate_series(name: String, articles: Vec<ArticlePreview>) -> String;
+    /// Chronological archive page at `archive/index.html`, `<year>/index.html`
+    /// or `<year>/<month>/index.html`.
+    fn generate_archive(archive: Archive) -> String;
 }
 
 impl<T: Theme> theme::exports::thought::plugin::theme::Guest for T {
@@ -51,8 +59,16 @@ impl<T: Theme> theme::exports::thought::plugin::theme::Guest for T {
         <Self as Theme>::generate_page(article)
     }
 
-    fn generate_index(articles: Vec<ArticlePreview>) -> String {
-        <Self as Theme>::generate_index(articles)
+    fn generate_index(articles: Vec<ArticlePreview>, locale: Option<String>) -> String {
+        <Self as Theme>::generate_index(articles, locale)
+    }
+
+    fn generate_series(name: String, articles: Vec<ArticlePreview>) -> String {
+        <Self as Theme>::generate_series(name, articles)
+    }
+
+    fn generate_archive(archive: Archive) -> String {
+        <Self as Theme>::generate_archive(archive)
     }
 }
 
@@ -118,6 +134,23 @@ impl ArticleMetadata {
         helpers::format_display_date_locale(locale, self.created())
     }
 
+    /// Last revision, when the workspace knows it.
+    #[must_use]
+    pub fn updated(&self) -> Option<OffsetDateTime> {
+        self.updated.as_ref().map(Timestamp::to_offset_datetime)
+    }
+
+    #[must_use]
+    pub fn updated_display(&self) -> Option<String> {
+        self.updated().map(helpers::format_display_date)
+    }
+
+    #[must_use]
+    pub fn updated_display_for(&self, locale: &str) -> Option<String> {
+        self.updated()
+            .map(|updated| helpers::format_display_date_locale(locale, updated))
+    }
+
     #[must_use]
     pub fn description(&self) -> Option<&str> {
         self.description.as_deref()
@@ -137,6 +170,22 @@ impl ArticleMetadata {
     pub fn language(&self) -> Option<&str> {
         self.lang.as_ref().map(|s| s.as_str())
     }
+
+    /// Series the article belongs to, if any.
+    #[must_use]
+    pub fn series(&self) -> Option<&SeriesMembership> {
+        self.series.as_ref()
+    }
+
+    #[must_use]
+    pub const fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+
+    #[must_use]
+    pub const fn weight(&self) -> Option<i32> {
+        self.weight
+    }
 }
 
 impl ArticlePreview {
@@ -155,6 +204,23 @@ impl ArticlePreview {
         self.description.as_str()
     }
 
+    /// Text before a `<!-- more -->` marker, or the description.
+    #[must_use]
+    pub fn excerpt(&self) -> &str {
+        self.excerpt.as_str()
+    }
+
+    #[must_use]
+    pub const fn word_count(&self) -> u32 {
+        self.word_count
+    }
+
+    /// Estimated reading time in minutes.
+    #[must_use]
+    pub const fn reading_minutes(&self) -> u32 {
+        self.reading_minutes
+    }
+
     #[must_use]
     pub fn metadata(&self) -> &ArticleMetadata {
         &self.metadata
@@ -170,6 +236,24 @@ impl ArticlePreview {
         &self.locale
     }
 
+    /// ISO 15924 script of the preview's locale, e.g. `Latn` or `Arab`.
+    #[must_use]
+    pub fn script(&self) -> &str {
+        &self.script
+    }
+
+    /// Whether this is a translation of an older revision of the source.
+    #[must_use]
+    pub const fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Writing direction of the preview's locale.
+    #[must_use]
+    pub const fn direction(&self) -> TextDirection {
+        self.direction
+    }
+
     #[must_use]
     pub fn default_locale(&self) -> &str {
         &self.default_locale
@@ -185,15 +269,10 @@ impl ArticlePreview {
         &self.translations
     }
 
-    /// Prefix to the assets directory relative to this article preview.
+    /// Prefix to the site root relative to this article preview.
     #[must_use]
     pub fn assets_prefix(&self) -> String {
-        let depth = self.category().path().len();
-        if depth == 0 {
-            String::new()
-        } else {
-            "../".repeat(depth)
-        }
+        helpers::root_prefix(&self.output_file)
     }
 
     /// Build an asset path relative to this article preview.
@@ -209,38 +288,29 @@ impl ArticlePreview {
     /// Relative path (without extension) for the rendered article.
     #[must_use]
     pub fn output_path(&self) -> String {
-        let mut path = self.category().path().to_vec();
-        let mut slug = self.slug().to_string();
-        if !self.is_default_locale() && !self.locale().is_empty() {
-            slug.push('.');
-            slug.push_str(self.locale());
-        }
-        path.push(slug);
-        path.join("/")
+        let file = self.output_file.as_str();
+        file.strip_suffix(".html").unwrap_or(file).to_string()
     }
 
-    /// Relative file name (with `.html`) for the rendered article.
+    /// Relative file name (with `.html`) for the rendered article, as decided
+    /// by the site's permalink pattern.
     #[must_use]
     pub fn output_file(&self) -> String {
-        format!("{}.html", self.output_path())
+        self.output_file.clone()
     }
 
     /// Build a relative output file name for a specific locale of this article.
     #[must_use]
     pub fn output_file_for_locale(&self, locale: &str) -> String {
-        if locale.is_empty() || locale == self.default_locale() {
-            return format!("{}.html", {
-                let mut path = self.category().path().to_vec();
-                path.push(self.slug().to_string());
-                path.join("/")
-            });
-        }
-        let mut path = self.category().path().to_vec();
-        let mut slug = self.slug().to_string();
-        slug.push('.');
-        slug.push_str(locale);
-        path.push(slug);
-        format!("{}.html", path.join("/"))
+        let locale = if locale.is_empty() {
+            self.default_locale()
+        } else {
+            locale
+        };
+        self.translations()
+            .iter()
+            .find(|t| t.locale == locale)
+            .map_or_else(|| self.output_file(), |t| t.output_file.clone())
     }
 
     /// Build a permalink given a site base URL.
@@ -281,31 +351,24 @@ impl ArticlePreview {
     /// Search script path relative to this article preview.
     #[must_use]
     pub fn search_script_path(&self) -> String {
-        let depth = self.category().path().len();
-        format!(
-            "{}{}",
-            if depth == 0 {
-                String::new()
-            } else {
-                "../".repeat(depth)
-            },
-            helpers::search_script_path()
-        )
+        format!("{}{}", self.assets_prefix(), helpers::search_script_path())
     }
 
     /// Search wasm path relative to this article preview.
     #[must_use]
     pub fn search_wasm_path(&self) -> String {
-        let depth = self.category().path().len();
-        format!(
-            "{}{}",
-            if depth == 0 {
-                String::new()
-            } else {
-                "../".repeat(depth)
-            },
-            helpers::search_wasm_path()
-        )
+        format!("{}{}", self.assets_prefix(), helpers::search_wasm_path())
+    }
+}
+
+impl TextDirection {
+    /// Value for the HTML `dir` attribute: `ltr` or `rtl`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Ltr => "ltr",
+            Self::Rtl => "rtl",
+        }
     }
 }
 
@@ -335,15 +398,10 @@ impl Article {
         &self.preview
     }
 
-    /// Prefix to the assets directory relative to this article.
+    /// Prefix to the site root relative to this article.
     #[must_use]
     pub fn assets_prefix(&self) -> String {
-        let depth = self.preview().category().path().len();
-        if depth == 0 {
-            String::new()
-        } else {
-            "../".repeat(depth)
-        }
+        self.preview().assets_prefix()
     }
 
     /// Build an asset path relative to this article.
@@ -396,6 +454,18 @@ impl Article {
         self.preview.locale()
     }
 
+    /// Writing direction of the article's locale.
+    #[must_use]
+    pub const fn direction(&self) -> TextDirection {
+        self.preview.direction
+    }
+
+    /// Whether this is a translation of an older revision of the source.
+    #[must_use]
+    pub const fn is_stale(&self) -> bool {
+        self.preview.stale
+    }
+
     #[must_use]
     pub fn default_locale(&self) -> &str {
         self.preview.default_locale()
@@ -406,6 +476,18 @@ impl Article {
         self.preview.translations()
     }
 
+    /// Navigation within the article's series, if it is part of one.
+    #[must_use]
+    pub fn series(&self) -> Option<&Series> {
+        self.series.as_ref()
+    }
+
+    /// Similar articles chosen by the host, best match first.
+    #[must_use]
+    pub fn related(&self) -> &[ArticlePreview] {
+        &self.related
+    }
+
     /// Convenience: article content already rendered to HTML.
     #[must_use]
     pub fn content_html(&self) -> String {
@@ -413,6 +495,22 @@ impl Article {
     }
 }
 
+impl Archive {
+    /// Prefix to the site root relative to this archive page.
+    #[must_use]
+    pub fn assets_prefix(&self) -> String {
+        helpers::root_prefix(&self.output_file)
+    }
+
+    /// Every article on the page, newest first.
+    pub fn articles(&self) -> impl Iterator<Item = &ArticlePreview> {
+        self.years
+            .iter()
+            .flat_map(|year| &year.months)
+            .flat_map(|month| &month.articles)
+    }
+}
+
 impl Category {
     #[must_use]
     pub fn path(&self) -> &[String] {
@@ -494,6 +592,12 @@ pub mod helpers {
         format!("{}/{}", SEARCH_ASSET_DIR, SEARCH_WASM_FILENAME)
     }
 
+    /// Prefix leading from a page emitted at `output_file` back to the site root.
+    #[must_use]
+    pub fn root_prefix(output_file: &str) -> String {
+        "../".repeat(output_file.matches('/').count())
+    }
+
     /// Build an assets prefix for the blog index page.
     #[must_use]
     pub fn index_assets_prefix() -> &'static str {
@@ -512,6 +616,23 @@ pub mod helpers {
         SEARCH_SCRIPT_PATH
     }
 
+    /// Prefix leading from an index page back to the site root: empty for the
+    /// root index, `../` for `<locale>/index.html`.
+    #[must_use]
+    pub fn index_root_prefix(locale: Option<&str>) -> &'static str {
+        if locale.is_some() {
+            "../"
+        } else {
+            ""
+        }
+    }
+
+    /// Prefix leading from a series landing page back to the site root.
+    #[must_use]
+    pub fn series_root_prefix() -> &'static str {
+        "../"
+    }
+
     /// A compact, human readable date like "Mon Nov 24".
     #[must_use]
     pub fn format_display_date(dt: OffsetDateTime) -> String {
@@ -0,0 +1,699 @@
+//! Portable archives of a workspace's articles.
+//!
+//! `thought export` writes every file under `articles/` (category metadata,
+//! article sources in every locale, and co-located assets) and the
+//! workspace's `Thought.toml` and `Glossary.toml`, together with a manifest
+//! listing categories, articles and the [`Article::sha256`] of each variant.
+//! The same data can be written as one JSON document, as NDJSON records, or as
+//! a zip file with the manifest in `thought-archive.json`.
+//!
+//! `thought import thought-archive <file>` writes the files back unchanged,
+//! including their modification times, and then checks that each restored
+//! article hashes to the archived `sha256`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    io::{self, Cursor, Read, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::SystemTime,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use color_eyre::eyre::{self, eyre};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tokio::{fs as async_fs, task};
+use zip::{CompressionMethod, ZipArchive, ZipWriter, write::FileOptions};
+
+use crate::{
+    article::{Article, ArticleLayout},
+    import::{ImportReport, SkippedPost},
+    metadata::{ArticleMetadata, CategoryMetadata},
+    workspace::Workspace,
+};
+
+/// Value of [`ArchiveHeader::format`].
+const ARCHIVE_FORMAT: &str = "thought-archive";
+/// Bumped whenever the layout changes incompatibly. Version 2 added the
+/// workspace settings.
+const ARCHIVE_VERSION: u32 = 2;
+/// Manifest entry of a zip archive.
+const MANIFEST_FILE: &str = "thought-archive.json";
+/// Directory holding the files of a zip archive.
+const FILES_DIR: &str = "articles/";
+/// Files at the workspace root that are archived; a zip archive keeps them at
+/// its own root.
+const SETTINGS_FILES: [&str; 2] = ["Thought.toml", "Glossary.toml"];
+
+/// Encodings `thought export` can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// A single JSON document with file contents inline.
+    Json,
+    /// One JSON record per line: the header, then categories, articles and files.
+    Ndjson,
+    /// A zip file holding the files as-is and the manifest as JSON.
+    Zip,
+}
+
+/// Error returned when an export format name is not recognised.
+#[derive(Debug, thiserror::Error)]
+#[error("unknown export format `{0}` (expected json, ndjson or zip)")]
+pub struct UnknownExportFormat(String);
+
+impl FromStr for ExportFormat {
+    type Err = UnknownExportFormat;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "ndjson" | "jsonl" => Ok(Self::Ndjson),
+            "zip" => Ok(Self::Zip),
+            _ => Err(UnknownExportFormat(value.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Json => "json",
+            Self::Ndjson => "ndjson",
+            Self::Zip => "zip",
+        })
+    }
+}
+
+impl ExportFormat {
+    /// Guess the format from the extension of `path`, if it has a known one.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| extension.parse().ok())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchiveHeader {
+    format: String,
+    version: u32,
+    workspace: String,
+    #[serde(with = "time::serde::rfc3339")]
+    exported: OffsetDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedCategory {
+    /// Segments joined with `/`; empty for the root category.
+    path: String,
+    metadata: CategoryMetadata,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedArticle {
+    path: String,
+    metadata: ArticleMetadata,
+    locales: Vec<ArchivedLocale>,
+    /// Files owned by the article, relative to `articles/`.
+    files: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedLocale {
+    locale: String,
+    title: String,
+    /// [`Article::sha256`] of the variant.
+    sha256: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Encoding {
+    Utf8,
+    Base64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedFile {
+    /// Relative to `articles/`, with `/` separators.
+    path: String,
+    #[serde(
+        default,
+        with = "time::serde::rfc3339::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    modified: Option<OffsetDateTime>,
+    /// Absent in zip manifests, where the bytes are a separate entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encoding: Option<Encoding>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+}
+
+impl ArchivedFile {
+    /// Record `bytes`, read from `source`, at `path` with their contents inline.
+    async fn new(path: String, bytes: &[u8], source: &Path) -> Self {
+        let modified = async_fs::metadata(source)
+            .await
+            .and_then(|meta| meta.modified())
+            .ok()
+            .map(OffsetDateTime::from);
+        let (encoding, content) = match std::str::from_utf8(bytes) {
+            Ok(text) => (Encoding::Utf8, text.to_string()),
+            Err(_) => (Encoding::Base64, BASE64.encode(bytes)),
+        };
+        Self {
+            path,
+            modified,
+            encoding: Some(encoding),
+            content: Some(content),
+        }
+    }
+
+    fn decode(&self) -> eyre::Result<Option<Vec<u8>>> {
+        let Some(content) = &self.content else {
+            return Ok(None);
+        };
+        Ok(Some(match self.encoding.unwrap_or(Encoding::Utf8) {
+            Encoding::Utf8 => content.as_bytes().to_vec(),
+            Encoding::Base64 => BASE64
+                .decode(content)
+                .map_err(|err| eyre!("`{}` is not valid base64: {err}", self.path))?,
+        }))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Archive {
+    #[serde(flatten)]
+    header: ArchiveHeader,
+    categories: Vec<ArchivedCategory>,
+    articles: Vec<ArchivedArticle>,
+    #[serde(default)]
+    files: Vec<ArchivedFile>,
+    /// Files from [`SETTINGS_FILES`], relative to the workspace root.
+    #[serde(default)]
+    settings: Vec<ArchivedFile>,
+}
+
+/// One line of an NDJSON archive.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Record {
+    Header(ArchiveHeader),
+    Category(ArchivedCategory),
+    Article(ArchivedArticle),
+    File(ArchivedFile),
+    Setting(ArchivedFile),
+}
+
+impl Workspace {
+    /// Serialize every category, article and asset under `articles/` in
+    /// `format`.
+    ///
+    /// # Errors
+    /// Returns an error if an article cannot be opened or a file cannot be read.
+    pub async fn export(&self, format: ExportFormat) -> eyre::Result<Vec<u8>> {
+        let (archive, blobs, settings) = collect(self).await?;
+        match format {
+            ExportFormat::Json => Ok(serde_json::to_vec_pretty(&archive)?),
+            ExportFormat::Ndjson => {
+                let Archive {
+                    header,
+                    categories,
+                    articles,
+                    files,
+                    settings,
+                } = archive;
+                let records = std::iter::once(Record::Header(header))
+                    .chain(categories.into_iter().map(Record::Category))
+                    .chain(articles.into_iter().map(Record::Article))
+                    .chain(files.into_iter().map(Record::File))
+                    .chain(settings.into_iter().map(Record::Setting));
+                let mut output = Vec::new();
+                for record in records {
+                    serde_json::to_writer(&mut output, &record)?;
+                    output.push(b'\n');
+                }
+                Ok(output)
+            }
+            ExportFormat::Zip => task::spawn_blocking(move || write_zip(archive, blobs, settings))
+                .await
+                .map_err(|err| eyre!("zip task failed: {err}"))?,
+        }
+    }
+}
+
+/// Bytes of archived files keyed by their path in the archive.
+type Blobs = BTreeMap<String, Vec<u8>>;
+
+async fn collect(workspace: &Workspace) -> eyre::Result<(Archive, Blobs, Blobs)> {
+    let root = workspace.articles_dir();
+    let mut files = Vec::new();
+    let mut blobs = BTreeMap::new();
+    let mut categories = Vec::new();
+
+    let mut stack = vec![root.clone()];
+    while let Some(dir) = stack.pop() {
+        let mut entries = async_fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            if entry.file_type().await?.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            let relative = relative_path(&root, &path)?;
+            let bytes = async_fs::read(&path).await?;
+            if entry.file_name() == "Category.toml" {
+                let metadata = toml::from_str(&String::from_utf8_lossy(&bytes))
+                    .map_err(|err| eyre!("Invalid `{relative}`: {err}"))?;
+                categories.push(ArchivedCategory {
+                    path: relative
+                        .strip_suffix("Category.toml")
+                        .unwrap_or_default()
+                        .trim_end_matches('/')
+                        .to_string(),
+                    metadata,
+                });
+            }
+            files.push(ArchivedFile::new(relative.clone(), &bytes, &path).await);
+            blobs.insert(relative, bytes);
+        }
+    }
+
+    let mut settings = Vec::new();
+    let mut setting_blobs = BTreeMap::new();
+    for name in SETTINGS_FILES {
+        let path = workspace.root().join(name);
+        let bytes = match async_fs::read(&path).await {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err.into()),
+        };
+        settings.push(ArchivedFile::new(name.to_string(), &bytes, &path).await);
+        setting_blobs.insert(name.to_string(), bytes);
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    categories.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut articles = BTreeMap::<String, ArchivedArticle>::new();
+    let mut stream = Box::pin(workspace.articles());
+    while let Some(article) = stream.next().await {
+        let article = article?;
+        let path = article.segments().join("/");
+        let locale = ArchivedLocale {
+            locale: article.locale().to_string(),
+            title: article.title().to_string(),
+            sha256: article.sha256(),
+        };
+        if let Some(entry) = articles.get_mut(&path) {
+            entry.locales.push(locale);
+            continue;
+        }
+        let owned = article_files(&article, &root, &blobs);
+        articles.insert(
+            path.clone(),
+            ArchivedArticle {
+                path,
+                metadata: article.metadata().clone(),
+                locales: vec![locale],
+                files: owned,
+            },
+        );
+    }
+    let mut articles = articles.into_values().collect::<Vec<_>>();
+    for article in &mut articles {
+        article.locales.sort_by(|a, b| a.locale.cmp(&b.locale));
+    }
+
+    let archive = Archive {
+        header: ArchiveHeader {
+            format: ARCHIVE_FORMAT.to_string(),
+            version: ARCHIVE_VERSION,
+            workspace: workspace.manifest().name().to_string(),
+            exported: OffsetDateTime::now_utc(),
+        },
+        categories,
+        articles,
+        files,
+        settings,
+    };
+    Ok((archive, blobs, setting_blobs))
+}
+
+/// Files under `articles/` that belong to `article`: its whole directory, or
+/// its Markdown files in the file layout.
+fn article_files(article: &Article, root: &Path, blobs: &BTreeMap<String, Vec<u8>>) -> Vec<String> {
+    match article.layout() {
+        ArticleLayout::Directory => relative_path(root, &article.dir())
+            .map(|dir| {
+                let prefix = format!("{dir}/");
+                blobs
+                    .keys()
+                    .filter(|path| path.starts_with(&prefix))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default(),
+        ArticleLayout::File => article
+            .translations()
+            .iter()
+            .filter_map(|translation| {
+                relative_path(root, &article.content_path_for_locale(translation.locale())).ok()
+            })
+            .filter(|path| blobs.contains_key(path))
+            .collect(),
+    }
+}
+
+fn relative_path(root: &Path, path: &Path) -> eyre::Result<String> {
+    Ok(path
+        .strip_prefix(root)?
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+fn write_zip(mut archive: Archive, blobs: Blobs, settings: Blobs) -> eyre::Result<Vec<u8>> {
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for file in archive.files.iter_mut().chain(&mut archive.settings) {
+        file.encoding = None;
+        file.content = None;
+    }
+    writer.start_file(MANIFEST_FILE, options)?;
+    writer.write_all(&serde_json::to_vec_pretty(&archive)?)?;
+    for (path, bytes) in blobs {
+        writer.start_file(format!("{FILES_DIR}{path}"), options)?;
+        writer.write_all(&bytes)?;
+    }
+    for (path, bytes) in settings {
+        writer.start_file(path, options)?;
+        writer.write_all(&bytes)?;
+    }
+    Ok(writer.finish()?.into_inner())
+}
+
+/// Bytes of the files and of the settings of a read archive.
+type ReadBlobs = (HashMap<String, Vec<u8>>, HashMap<String, Vec<u8>>);
+
+/// Read an archive in any of the [`ExportFormat`]s, returning its manifest
+/// and the bytes of every file and setting.
+fn read_archive(bytes: Vec<u8>) -> eyre::Result<(Archive, ReadBlobs)> {
+    if bytes.starts_with(b"PK\x03\x04") {
+        return read_zip(bytes);
+    }
+
+    let text = String::from_utf8(bytes).map_err(|_| eyre!("archive is not valid UTF-8"))?;
+    let first = text
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("");
+    let archive = match serde_json::from_str::<Record>(first) {
+        Ok(Record::Header(header)) => {
+            let mut archive = Archive {
+                header,
+                categories: Vec::new(),
+                articles: Vec::new(),
+                files: Vec::new(),
+                settings: Vec::new(),
+            };
+            for (index, line) in text.lines().enumerate().skip(1) {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str(line)
+                    .map_err(|err| eyre!("line {}: {err}", index + 1))?
+                {
+                    Record::Header(_) => return Err(eyre!("line {}: repeated header", index + 1)),
+                    Record::Category(category) => archive.categories.push(category),
+                    Record::Article(article) => archive.articles.push(article),
+                    Record::File(file) => archive.files.push(file),
+                    Record::Setting(file) => archive.settings.push(file),
+                }
+            }
+            archive
+        }
+        _ => serde_json::from_str::<Archive>(&text)?,
+    };
+
+    let decode = |files: &[ArchivedFile]| {
+        files
+            .iter()
+            .map(|file| {
+                let bytes = file
+                    .decode()?
+                    .ok_or_else(|| eyre!("`{}` has no content", file.path))?;
+                Ok((file.path.clone(), bytes))
+            })
+            .collect::<eyre::Result<HashMap<_, _>>>()
+    };
+    let blobs = (decode(&archive.files)?, decode(&archive.settings)?);
+    Ok((archive, blobs))
+}
+
+fn read_zip(bytes: Vec<u8>) -> eyre::Result<(Archive, ReadBlobs)> {
+    let mut zip = ZipArchive::new(Cursor::new(bytes))?;
+    let archive: Archive = {
+        let manifest = zip
+            .by_name(MANIFEST_FILE)
+            .map_err(|_| eyre!("zip file has no `{MANIFEST_FILE}`"))?;
+        serde_json::from_reader(manifest)?
+    };
+    let mut blobs = HashMap::new();
+    let mut settings = HashMap::new();
+    for index in 0..zip.len() {
+        let mut entry = zip.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let target = match name.strip_prefix(FILES_DIR) {
+            Some(path) => blobs.entry(path.to_string()),
+            None if SETTINGS_FILES.contains(&name.as_str()) => settings.entry(name),
+            None => continue,
+        };
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes)?;
+        target.insert_entry(bytes);
+    }
+    Ok((archive, (blobs, settings)))
+}
+
+/// Reject paths that would escape `articles/`.
+fn checked_path(root: &Path, relative: &str) -> eyre::Result<PathBuf> {
+    let valid = !relative.is_empty()
+        && !relative.starts_with('/')
+        && relative
+            .split('/')
+            .all(|segment| !matches!(segment, "" | "." | "..") && !segment.contains('\\'));
+    if !valid {
+        return Err(eyre!("archive contains an invalid path `{relative}`"));
+    }
+    Ok(relative
+        .split('/')
+        .fold(root.to_path_buf(), |path, segment| path.join(segment)))
+}
+
+async fn restore_file(
+    path: &Path,
+    bytes: &[u8],
+    modified: Option<OffsetDateTime>,
+) -> eyre::Result<()> {
+    crate::utils::write(path, bytes).await?;
+    if let Some(modified) = modified {
+        let path = path.to_path_buf();
+        task::spawn_blocking(move || {
+            std::fs::File::options()
+                .write(true)
+                .open(path)?
+                .set_modified(SystemTime::from(modified))
+        })
+        .await??;
+    }
+    Ok(())
+}
+
+/// Write a file that no article owns to `target` unless it already exists,
+/// reporting an existing file whose content differs.
+async fn restore_unowned(
+    target: &Path,
+    path: &str,
+    bytes: &[u8],
+    modified: Option<OffsetDateTime>,
+    report: &mut ImportReport,
+) -> eyre::Result<()> {
+    match async_fs::read(target).await {
+        Ok(existing) if existing == bytes => {}
+        Ok(_) => report.warnings.push(format!(
+            "kept existing `{path}`, which differs from the archive"
+        )),
+        Err(_) => restore_file(target, bytes, modified).await?,
+    }
+    Ok(())
+}
+
+/// Restore the archive at `source` into `workspace`.
+///
+/// Articles whose files already exist are skipped. Files outside any article
+/// (category metadata and the workspace settings) are only written when
+/// missing; an existing file with different content is kept and reported.
+pub(crate) async fn restore(
+    workspace: &Workspace,
+    source: &Path,
+    report: &mut ImportReport,
+) -> eyre::Result<()> {
+    let bytes = async_fs::read(source).await?;
+    let (archive, (mut blobs, settings)) = task::spawn_blocking(move || read_archive(bytes))
+        .await
+        .map_err(|err| eyre!("archive task failed: {err}"))??;
+    if archive.header.format != ARCHIVE_FORMAT {
+        return Err(eyre!("`{}` is not a Thought archive", source.display()));
+    }
+    if archive.header.version > ARCHIVE_VERSION {
+        return Err(eyre!(
+            "archive version {} is newer than supported ({ARCHIVE_VERSION})",
+            archive.header.version
+        ));
+    }
+
+    let root = workspace.articles_dir();
+    let modified = archive
+        .files
+        .iter()
+        .map(|file| (file.path.as_str(), file.modified))
+        .collect::<HashMap<_, _>>();
+    for path in blobs.keys() {
+        checked_path(&root, path)?;
+    }
+    if let Some(name) = settings
+        .keys()
+        .find(|name| !SETTINGS_FILES.contains(&name.as_str()))
+    {
+        return Err(eyre!("archive contains an unexpected setting `{name}`"));
+    }
+
+    for setting in &archive.settings {
+        if let Some(bytes) = settings.get(&setting.path) {
+            let target = workspace.root().join(&setting.path);
+            restore_unowned(&target, &setting.path, bytes, setting.modified, report).await?;
+        }
+    }
+
+    let owned = archive
+        .articles
+        .iter()
+        .flat_map(|article| article.files.iter().map(String::as_str))
+        .collect::<BTreeSet<_>>();
+    let mut leftovers = blobs
+        .keys()
+        .filter(|path| !owned.contains(path.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+    leftovers.sort();
+    for path in leftovers {
+        let target = checked_path(&root, &path)?;
+        let bytes = blobs.remove(&path).unwrap_or_default();
+        let modified = modified.get(path.as_str()).copied().flatten();
+        restore_unowned(&target, &path, &bytes, modified, report).await?;
+    }
+
+    let mut restored = Vec::new();
+    for article in &archive.articles {
+        let mut taken = None;
+        for path in &article.files {
+            let target = checked_path(&root, path)?;
+            if async_fs::try_exists(&target).await? {
+                taken = Some(path);
+                break;
+            }
+            if !blobs.contains_key(path) {
+                taken = Some(path);
+                break;
+            }
+        }
+        if let Some(path) = taken {
+            let reason = if blobs.contains_key(path) {
+                format!("`{path}` already exists")
+            } else {
+                format!("`{path}` is missing from the archive")
+            };
+            report.skipped.push(SkippedPost {
+                source: PathBuf::from(&article.path),
+                reason,
+            });
+            continue;
+        }
+
+        for path in &article.files {
+            let bytes = blobs.remove(path).unwrap_or_default();
+            let target = checked_path(&root, path)?;
+            restore_file(
+                &target,
+                &bytes,
+                modified.get(path.as_str()).copied().flatten(),
+            )
+            .await?;
+            let is_source = path.ends_with(".md") || path.ends_with("/Article.toml");
+            if !is_source {
+                report.assets += 1;
+            }
+        }
+        report.imported.push(article.path.clone());
+        restored.push(article);
+    }
+
+    verify(workspace, &restored).await
+}
+
+/// Check the [`Article::sha256`] of each restored article against the archive.
+///
+/// # Errors
+/// Returns an error listing every variant that differs from the archive or
+/// cannot be opened after restoring.
+async fn verify(workspace: &Workspace, restored: &[&ArchivedArticle]) -> eyre::Result<()> {
+    let mut actual = HashMap::new();
+    let mut stream = Box::pin(workspace.articles());
+    while let Some(article) = stream.next().await {
+        if let Ok(article) = article {
+            actual.insert(
+                (article.segments().join("/"), article.locale().to_string()),
+                article.sha256(),
+            );
+        }
+    }
+
+    let mut problems = Vec::new();
+    for article in restored {
+        for locale in &article.locales {
+            match actual.get(&(article.path.clone(), locale.locale.clone())) {
+                Some(sha256) if *sha256 == locale.sha256 => {}
+                Some(_) => problems.push(format!(
+                    "  `{}` ({}) differs from the archive",
+                    article.path, locale.locale
+                )),
+                None => problems.push(format!(
+                    "  `{}` ({}) could not be opened",
+                    article.path, locale.locale
+                )),
+            }
+        }
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "{} restored article(s) failed verification:\n{}",
+            problems.len(),
+            problems.join("\n")
+        ))
+    }
+}
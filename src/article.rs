@@ -1,24 +1,41 @@
 use std::{
     path::{Path, PathBuf},
     str::FromStr,
+    sync::LazyLock,
 };
 
+use regex::Regex;
 use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
 use whatlang::{Lang, detect};
 
 use crate::{
     category::Category,
+    front_matter::{self, FrontMatter, FrontMatterError},
+    history::FileDates,
+    locale::{self, TextDirection},
     metadata::{ArticleMetadata, FailToOpenMetadata, MetadataExt},
-    slug::ArticleSlug,
+    series::SeriesNavigation,
+    slug::{ArticleSlug, InvalidSlug},
+    stats::ReadingStats,
     utils::read_to_string,
     workspace::Workspace,
 };
 
+/// `<!-- more -->`, the excerpt separator used by WordPress, Hexo and Hugo.
+static MORE_MARKER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\s*<!--\s*more\s*-->\s*$").expect("valid more marker regex")
+});
+
 /// An article with its full content
 #[derive(Debug, Clone)]
 pub struct Article {
     pub(crate) content: String, // markdown content
     pub(crate) preview: ArticlePreview,
+    /// Neighbouring parts, filled in by [`SeriesIndex::attach`](crate::series::SeriesIndex::attach) before rendering.
+    pub(crate) series: Option<SeriesNavigation>,
+    /// Similar articles, filled in by [`RelatedArticles::attach`](crate::related::RelatedArticles::attach).
+    pub(crate) related: Vec<ArticlePreview>,
 }
 
 /// A preview of an article without its content
@@ -29,15 +46,32 @@ pub struct ArticlePreview {
     pub(crate) category: Category,
     pub(crate) metadata: ArticleMetadata,
     pub(crate) description: String,
+    pub(crate) excerpt: String,
+    pub(crate) stats: ReadingStats,
     pub(crate) locale: String,
     pub(crate) default_locale: String,
     pub(crate) translations: Vec<ArticleTranslation>,
+    pub(crate) layout: ArticleLayout,
+    pub(crate) output_file: String,
+    /// `sha256` of the default-locale body, which translations are made from.
+    pub(crate) source_hash: String,
+}
+
+/// How an article's files are laid out inside its category directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArticleLayout {
+    /// `<slug>/article.md` with optional `<slug>/Article.toml`; variants are `<slug>/<locale>.md`.
+    Directory,
+    /// A single `<slug>.md`; variants are `<slug>.<locale>.md` next to it.
+    File,
 }
 
 #[derive(Debug, Clone)]
 pub struct ArticleTranslation {
     pub(crate) locale: String,
     pub(crate) title: String,
+    pub(crate) output_file: String,
+    pub(crate) stale: bool,
 }
 
 impl ArticleTranslation {
@@ -50,6 +84,31 @@ impl ArticleTranslation {
     pub fn title(&self) -> &str {
         &self.title
     }
+
+    /// Output file of this variant, relative to the build directory.
+    #[must_use]
+    pub fn output_file(&self) -> &str {
+        &self.output_file
+    }
+
+    /// Whether the source changed since this variant was translated from it.
+    /// Translations that do not record their source are never stale.
+    #[must_use]
+    pub const fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// ISO 15924 script of this variant's locale.
+    #[must_use]
+    pub fn script(&self) -> String {
+        locale::script(&self.locale)
+    }
+
+    /// Writing direction of this variant's locale.
+    #[must_use]
+    pub fn direction(&self) -> TextDirection {
+        locale::direction(&self.locale)
+    }
 }
 
 impl ArticlePreview {
@@ -77,6 +136,25 @@ impl ArticlePreview {
         &self.description
     }
 
+    /// Plain text before a `<!-- more -->` marker, or the description when
+    /// the article has no marker.
+    #[must_use]
+    pub fn excerpt(&self) -> &str {
+        &self.excerpt
+    }
+
+    /// Words in the body, counting each Chinese or Japanese character as one.
+    #[must_use]
+    pub const fn word_count(&self) -> usize {
+        self.stats.word_count()
+    }
+
+    /// Estimated minutes to read the body.
+    #[must_use]
+    pub fn reading_minutes(&self) -> u32 {
+        self.stats.reading_minutes()
+    }
+
     #[must_use]
     pub fn locale(&self) -> &str {
         &self.locale
@@ -92,26 +170,82 @@ impl ArticlePreview {
         self.locale == self.default_locale
     }
 
+    /// Whether the article is public: neither a draft nor dated in the future.
+    /// Other articles are left out of every listing and of search, and only
+    /// rendered by `thought serve` or `thought generate --drafts`.
+    #[must_use]
+    pub fn is_published(&self) -> bool {
+        !self.metadata.is_draft() && self.metadata.created() <= OffsetDateTime::now_utc()
+    }
+
     #[must_use]
     pub fn translations(&self) -> &[ArticleTranslation] {
         &self.translations
     }
 
+    /// `sha256` of the default-locale body; `thought translate` records it in
+    /// the front matter of each translation as `source_hash`.
+    #[must_use]
+    pub fn source_hash(&self) -> &str {
+        &self.source_hash
+    }
+
+    /// Whether this variant is a translation of an older revision of the
+    /// source; see [`ArticleTranslation::is_stale`].
+    #[must_use]
+    pub fn is_stale(&self) -> bool {
+        self.translations
+            .iter()
+            .any(|translation| translation.locale == self.locale && translation.stale)
+    }
+
+    /// ISO 15924 script of the article's locale.
+    #[must_use]
+    pub fn script(&self) -> String {
+        locale::script(&self.locale)
+    }
+
+    /// Writing direction of the article's locale.
+    #[must_use]
+    pub fn direction(&self) -> TextDirection {
+        locale::direction(&self.locale)
+    }
+
+    /// The variant to show in `locale`, following its fallback chain down to
+    /// the default locale.
+    #[must_use]
+    pub fn translation(&self, locale: &str) -> Option<&ArticleTranslation> {
+        locale::best_match(
+            locale,
+            &self.default_locale,
+            &self.translations,
+            ArticleTranslation::locale,
+        )
+    }
+
+    #[must_use]
+    pub const fn layout(&self) -> ArticleLayout {
+        self.layout
+    }
+
+    /// Slug used in the article's URL: the metadata override, or the file or
+    /// directory name.
+    #[must_use]
+    pub fn url_slug(&self) -> &str {
+        self.metadata.slug().unwrap_or(&self.slug)
+    }
+
     #[must_use]
     pub fn output_path(&self) -> String {
-        let mut path = self.category().segments().to_vec();
-        let mut slug = self.slug().to_string();
-        if !self.is_default_locale() {
-            slug.push('.');
-            slug.push_str(self.locale());
-        }
-        path.push(slug);
-        path.join("/")
+        let file = self.output_file();
+        file.strip_suffix(".html").unwrap_or(&file).to_string()
     }
 
+    /// Output file relative to the build directory, following the workspace
+    /// permalink pattern.
     #[must_use]
     pub fn output_file(&self) -> String {
-        format!("{}.html", self.output_path())
+        self.output_file.clone()
     }
 }
 
@@ -130,10 +264,24 @@ impl Article {
         let title = title.into();
         let slug = slug.into();
         let description = description.into();
+        let (excerpt, stats) = extract(&content).map_or_else(
+            |_| (None, ReadingStats::measure(&content)),
+            |extraction| (extraction.excerpt, extraction.stats),
+        );
+        let excerpt = excerpt.unwrap_or_else(|| description.clone());
+        let output_file = category.workspace().manifest().permalink().output_file(
+            category.segments(),
+            metadata.slug().unwrap_or(&slug),
+            metadata.created(),
+            None,
+        );
         let translations = vec![ArticleTranslation {
             locale: default_locale.clone(),
             title: title.clone(),
+            output_file: output_file.clone(),
+            stale: false,
         }];
+        let source_hash = digest(&content);
         Self {
             content,
             preview: ArticlePreview {
@@ -142,10 +290,17 @@ impl Article {
                 category,
                 metadata,
                 description,
+                excerpt,
+                stats,
                 locale: default_locale.clone(),
                 default_locale,
                 translations,
+                layout: ArticleLayout::Directory,
+                output_file,
+                source_hash,
             },
+            series: None,
+            related: Vec::new(),
         }
     }
 
@@ -184,20 +339,90 @@ impl Article {
             .parent()
             .ok_or(FailToOpenArticle::ArticleNotFound)?;
 
-        // check if the article directory exists
-        if !full_path.exists() || !full_path.is_dir() {
-            return Err(FailToOpenArticle::ArticleNotFound);
+        let (layout, (metadata, explicit), default_locale, available) = if full_path.is_dir() {
+            let metadata = open_metadata(&workspace, &full_path).await?;
+            let default_locale =
+                resolve_default_locale_from_disk(&full_path, metadata.0.lang()).await?;
+            let available =
+                enumerate_locales(&full_path, &default_locale, workspace.manifest().locales())
+                    .await?;
+            (
+                ArticleLayout::Directory,
+                metadata,
+                default_locale,
+                available,
+            )
+        } else {
+            let primary_path = category_path.join(format!("{slug}.md"));
+            let primary = read_to_string(&primary_path)
+                .await
+                .map_err(|_| FailToOpenArticle::ArticleNotFound)?;
+            let (front_matter, body) =
+                front_matter::split(&primary).map_err(FailToOpenArticle::InvalidFrontMatter)?;
+            let mut metadata = default_metadata(&workspace, &primary_path).await;
+            let mut explicit = ExplicitDates::default();
+            if let Some(front_matter) = front_matter {
+                explicit = ExplicitDates::of(&front_matter);
+                front_matter.apply(&mut metadata);
+            }
+            let default_locale = resolve_default_locale(metadata.lang(), body);
+            let available = enumerate_file_locales(
+                category_path,
+                &slug,
+                &default_locale,
+                workspace.manifest().locales(),
+            )
+            .await?;
+            (
+                ArticleLayout::File,
+                (metadata, explicit),
+                default_locale,
+                available,
+            )
+        };
+        let mut metadata = metadata;
+        if let Some(history) = workspace.history_dates().await {
+            let relative = segments.join("/");
+            let dates = match layout {
+                ArticleLayout::Directory => history.dir(&relative),
+                ArticleLayout::File => available
+                    .iter()
+                    .filter_map(|variant| {
+                        history.file(&if variant.locale == default_locale {
+                            format!("{relative}.md")
+                        } else {
+                            format!("{relative}.{}.md", variant.locale)
+                        })
+                    })
+                    .reduce(FileDates::merge),
+            };
+            // Dates written in the front matter or `Article.toml` win.
+            if let Some(dates) = dates {
+                if !explicit.created {
+                    metadata.set_created(dates.created);
+                }
+                if !explicit.updated {
+                    metadata.set_updated(dates.updated);
+                }
+            }
         }
-
-        let metadata_path = full_path.join("Article.toml");
-        let metadata = ArticleMetadata::open(metadata_path)
-            .await
-            .map_err(FailToOpenArticle::FailToOpenMetadata)?;
-        let default_locale = resolve_default_locale_from_disk(&full_path, metadata.lang()).await?;
-
-        let available = enumerate_locales(&full_path, &default_locale).await?;
-        let target_locale = locale.unwrap_or_else(|| default_locale.clone());
-        let content_path = locale_to_path(&full_path, &target_locale, &default_locale);
+        let target_locale = locale.map_or_else(
+            || default_locale.clone(),
+            |locale| {
+                let locale = locale::canonicalize(&locale).unwrap_or(locale);
+                locale::best_match(&locale, &default_locale, &available, |variant| {
+                    &variant.locale
+                })
+                .map_or_else(|| default_locale.clone(), |variant| variant.locale.clone())
+            },
+        );
+        let content_path = content_path(
+            layout,
+            category_path,
+            &slug,
+            &target_locale,
+            &default_locale,
+        );
 
         if !content_path.exists() {
             return Err(FailToOpenArticle::ArticleNotFound);
@@ -211,7 +436,7 @@ impl Article {
             .await
             .map_err(|_| FailToOpenArticle::WorkspaceNotFound)?;
 
-        let extraction = extract(&content);
+        let extraction = extract(&content).map_err(FailToOpenArticle::InvalidFrontMatter)?;
         let title = extraction.title.unwrap_or_else(|| {
             let format =
                 format_description!("[weekday repr:short] [day padding:none] [month repr:short]");
@@ -221,11 +446,35 @@ impl Article {
                 .expect("Failed to format date")
         });
 
+        let permalink = workspace.manifest().permalink();
+        if let Some(url_slug) = metadata.slug() {
+            ArticleSlug::from_str(url_slug).map_err(FailToOpenArticle::InvalidSlug)?;
+        }
+        let url_slug = metadata.slug().unwrap_or(&slug);
+        let output_file_for = |locale: &str| {
+            permalink.output_file(
+                category.segments(),
+                url_slug,
+                metadata.created(),
+                (locale != default_locale).then_some(locale),
+            )
+        };
+        let output_file = output_file_for(&target_locale);
+        let source_hash = available
+            .iter()
+            .find(|variant| variant.locale == default_locale)
+            .map(|variant| variant.digest.clone())
+            .unwrap_or_default();
         let translations = available
             .iter()
             .map(|variant| ArticleTranslation {
                 locale: variant.locale.clone(),
                 title: variant.title.clone().unwrap_or_else(|| title.clone()),
+                output_file: output_file_for(&variant.locale),
+                stale: variant
+                    .source_hash
+                    .as_ref()
+                    .is_some_and(|recorded| *recorded != source_hash),
             })
             .collect::<Vec<_>>();
 
@@ -236,16 +485,41 @@ impl Article {
                 slug,
                 category,
                 metadata,
+                excerpt: extraction
+                    .excerpt
+                    .unwrap_or_else(|| extraction.description.clone()),
                 description: extraction.description,
+                stats: extraction.stats,
                 locale: target_locale,
                 default_locale,
                 translations,
+                layout,
+                output_file,
+                source_hash,
             },
+            series: None,
+            related: Vec::new(),
         })
     }
 
+    /// Directory holding the article's files: its own directory, or the
+    /// category directory for single-file articles.
     pub fn dir(&self) -> PathBuf {
-        self.category().dir().join(self.slug())
+        match self.layout() {
+            ArticleLayout::Directory => self.category().dir().join(self.slug()),
+            ArticleLayout::File => self.category().dir(),
+        }
+    }
+
+    /// Markdown file holding the article's content in `locale`.
+    pub fn content_path_for_locale(&self, locale: &str) -> PathBuf {
+        content_path(
+            self.layout(),
+            &self.category().dir(),
+            self.slug(),
+            locale,
+            self.default_locale(),
+        )
     }
 
     pub fn segments(&self) -> Vec<String> {
@@ -270,6 +544,18 @@ impl Article {
         self.content.as_str()
     }
 
+    /// Navigation within the article's series, once attached.
+    #[must_use]
+    pub const fn series(&self) -> Option<&SeriesNavigation> {
+        self.series.as_ref()
+    }
+
+    /// Articles related to this one, once attached, best match first.
+    #[must_use]
+    pub fn related(&self) -> &[ArticlePreview] {
+        &self.related
+    }
+
     #[must_use]
     pub const fn slug(&self) -> &str {
         self.preview.slug.as_str()
@@ -305,11 +591,21 @@ impl Article {
         self.preview.is_default_locale()
     }
 
+    #[must_use]
+    pub fn is_published(&self) -> bool {
+        self.preview.is_published()
+    }
+
     #[must_use]
     pub fn translations(&self) -> &[ArticleTranslation] {
         self.preview.translations()
     }
 
+    #[must_use]
+    pub const fn layout(&self) -> ArticleLayout {
+        self.preview.layout
+    }
+
     #[must_use]
     pub fn output_path(&self) -> String {
         self.preview.output_path()
@@ -337,10 +633,16 @@ impl Article {
         let created = metadata.created();
         hasher.update(created.unix_timestamp().to_le_bytes());
         hasher.update(created.nanosecond().to_le_bytes());
+        if let Some(updated) = metadata.updated() {
+            hasher.update(updated.unix_timestamp().to_le_bytes());
+            hasher.update(updated.nanosecond().to_le_bytes());
+        }
         hash_strings(&mut hasher, metadata.tags());
         hash_str(&mut hasher, metadata.author());
         hash_optional_str(&mut hasher, metadata.description());
         hash_optional_str(&mut hasher, metadata.lang());
+        hash_optional_str(&mut hasher, metadata.slug());
+        hash_str(&mut hasher, &self.preview.output_file);
 
         hash_str(&mut hasher, self.description());
         hash_str(&mut hasher, self.content());
@@ -350,10 +652,69 @@ impl Article {
         for translation in translations {
             hash_str(&mut hasher, translation.locale());
             hash_str(&mut hasher, translation.title());
+            hash_str(&mut hasher, translation.output_file());
+            hasher.update([u8::from(translation.is_stale())]);
+        }
+
+        // Renaming or reordering another part changes this page's navigation.
+        if let Some(series) = self.series() {
+            hasher.update([1_u8]);
+            hash_str(&mut hasher, series.name());
+            hash_str(&mut hasher, series.output_file());
+            hasher.update(series.part().to_le_bytes());
+            hasher.update((series.parts().len() as u64).to_le_bytes());
+            for entry in series.parts() {
+                hasher.update(entry.part().to_le_bytes());
+                hash_str(&mut hasher, entry.title());
+                hash_str(&mut hasher, entry.output_file());
+            }
+        } else {
+            hasher.update([0_u8]);
+        }
+
+        hasher.update((self.related.len() as u64).to_le_bytes());
+        for related in &self.related {
+            hash_str(&mut hasher, related.title());
+            hash_str(&mut hasher, &related.output_file);
+            hash_str(&mut hasher, related.description());
         }
 
         format!("{:x}", hasher.finalize())
     }
+
+    /// Words in the body; see [`ArticlePreview::word_count`].
+    #[must_use]
+    pub const fn word_count(&self) -> usize {
+        self.preview.word_count()
+    }
+
+    /// When the article was last revised: [`ArticleMetadata::updated`] when
+    /// set, else the latest modification time among its source files.
+    pub async fn updated(&self) -> Option<OffsetDateTime> {
+        if let Some(updated) = self.metadata().updated() {
+            return Some(updated);
+        }
+        let mut paths = self
+            .translations()
+            .iter()
+            .map(|translation| self.content_path_for_locale(translation.locale()))
+            .collect::<Vec<_>>();
+        if self.layout() == ArticleLayout::Directory {
+            paths.push(self.dir().join("Article.toml"));
+        }
+
+        let mut latest: Option<OffsetDateTime> = None;
+        for path in paths {
+            if let Ok(modified) = tokio::fs::metadata(&path)
+                .await
+                .and_then(|meta| meta.modified())
+            {
+                let modified = OffsetDateTime::from(modified);
+                latest = Some(latest.map_or(modified, |latest| latest.max(modified)));
+            }
+        }
+        latest
+    }
 }
 
 fn hash_optional_str(hasher: &mut Sha256, value: Option<&str>) {
@@ -399,7 +760,7 @@ async fn resolve_default_locale_from_disk(
 
     let primary = article_dir.join("article.md");
     if let Ok(content) = read_to_string(&primary).await {
-        if let Some(lang) = detect_locale_from_text(&content) {
+        if let Some(lang) = detect_locale_from_text(front_matter::strip(&content)) {
             return Ok(lang);
         }
     }
@@ -423,7 +784,7 @@ async fn resolve_default_locale_from_disk(
                 continue;
             }
             if let Ok(content) = read_to_string(&path).await {
-                if let Some(lang) = detect_locale_from_text(&content) {
+                if let Some(lang) = detect_locale_from_text(front_matter::strip(&content)) {
                     return Ok(lang);
                 }
             }
@@ -442,14 +803,15 @@ fn detect_locale_from_text(text: &str) -> Option<String> {
 }
 
 fn normalize_lang_tag(lang: Option<&str>) -> Option<String> {
-    lang.and_then(|value| {
-        let trimmed = value.trim();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(trimmed.to_string())
-        }
-    })
+    let value = lang?.trim();
+    if value.is_empty() {
+        return None;
+    }
+    let canonical = locale::canonicalize(value);
+    if canonical.is_none() {
+        tracing::warn!("ignoring `lang = \"{value}\"`: not a BCP 47 language tag");
+    }
+    canonical
 }
 
 fn lang_to_locale(lang: Lang) -> String {
@@ -533,26 +895,190 @@ pub enum FailToOpenArticle {
     ArticleNotFound,
     #[error("Failed to open metadata")]
     FailToOpenMetadata(FailToOpenMetadata),
+    #[error("Invalid front matter: {0}")]
+    InvalidFrontMatter(FrontMatterError),
+    #[error("Invalid slug override: {0}")]
+    InvalidSlug(InvalidSlug),
+}
+
+/// Metadata for an article without `Article.toml`: author and creation time of
+/// the commit that added `path` when `[dates] git = true`, falling back to the
+/// workspace owner and the file's modification time.
+async fn default_metadata(workspace: &Workspace, path: &Path) -> ArticleMetadata {
+    let relative = path
+        .strip_prefix(workspace.articles_dir())
+        .ok()
+        .map(|relative| {
+            relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        });
+    let origin = match relative {
+        Some(relative) => workspace
+            .history_dates()
+            .await
+            .and_then(|history| history.origin(&relative)),
+        None => None,
+    };
+    if let Some(origin) = origin {
+        let author = if origin.author.is_empty() {
+            workspace.manifest().owner().to_string()
+        } else {
+            origin.author
+        };
+        let mut metadata = ArticleMetadata::new(author);
+        metadata.set_created(origin.created);
+        return metadata;
+    }
+
+    let mut metadata = ArticleMetadata::new(workspace.manifest().owner());
+    if let Ok(modified) = tokio::fs::metadata(path)
+        .await
+        .and_then(|meta| meta.modified())
+    {
+        metadata.set_created(modified.into());
+    }
+    metadata
+}
+
+/// Whether `dir` holds an article: it has an `Article.toml`, or its
+/// `article.md` starts with a front matter block.
+pub(crate) async fn is_article_dir(dir: &Path) -> bool {
+    if tokio::fs::metadata(dir.join("Article.toml")).await.is_ok() {
+        return true;
+    }
+    read_to_string(dir.join("article.md"))
+        .await
+        .is_ok_and(|content| front_matter::has_front_matter(&content))
+}
+
+/// Dates an article sets itself, which git history must not replace.
+#[derive(Debug, Clone, Copy, Default)]
+struct ExplicitDates {
+    created: bool,
+    updated: bool,
+}
+
+impl ExplicitDates {
+    const fn of(front_matter: &FrontMatter) -> Self {
+        Self {
+            created: front_matter.created.is_some(),
+            updated: front_matter.updated.is_some(),
+        }
+    }
+
+    const fn or(self, other: Self) -> Self {
+        Self {
+            created: self.created || other.created,
+            updated: self.updated || other.updated,
+        }
+    }
+}
+
+/// Load `Article.toml` (if any) and apply the front matter of `article.md` on top.
+///
+/// Without `Article.toml`, the metadata starts from [`default_metadata`].
+async fn open_metadata(
+    workspace: &Workspace,
+    dir: &Path,
+) -> Result<(ArticleMetadata, ExplicitDates), FailToOpenArticle> {
+    let primary_path = dir.join("article.md");
+    let primary = read_to_string(&primary_path)
+        .await
+        .map_err(|_| FailToOpenArticle::ArticleNotFound)?;
+    let (front_matter, _) =
+        front_matter::split(&primary).map_err(FailToOpenArticle::InvalidFrontMatter)?;
+
+    let metadata_path = dir.join("Article.toml");
+    let (mut metadata, mut explicit) = if tokio::fs::metadata(&metadata_path).await.is_ok() {
+        let metadata = ArticleMetadata::open(metadata_path)
+            .await
+            .map_err(FailToOpenArticle::FailToOpenMetadata)?;
+        // `created` is required in `Article.toml`; `updated` is optional.
+        let explicit = ExplicitDates {
+            created: true,
+            updated: metadata.updated().is_some(),
+        };
+        (metadata, explicit)
+    } else if front_matter.is_some() {
+        (
+            default_metadata(workspace, &primary_path).await,
+            ExplicitDates::default(),
+        )
+    } else {
+        return Err(FailToOpenArticle::ArticleNotFound);
+    };
+
+    if let Some(front_matter) = front_matter {
+        explicit = explicit.or(ExplicitDates::of(&front_matter));
+        front_matter.apply(&mut metadata);
+    }
+    Ok((metadata, explicit))
 }
 
 // extract title,description and content from markdown, but do not render it to html
 struct ExtractionResult<'a> {
     title: Option<String>,
     description: String,
+    /// Body text before a `<!-- more -->` marker.
+    excerpt: Option<String>,
+    stats: ReadingStats,
+    /// `source_hash` recorded in the front matter of a translation.
+    source_hash: Option<String>,
     content: &'a str,
 }
 
-fn extract(input: &str) -> ExtractionResult<'_> {
+fn extract(input: &str) -> Result<ExtractionResult<'_>, FrontMatterError> {
+    let (front_matter, input) = front_matter::split(input)?;
+    let (front_title, front_description, source_hash) = front_matter
+        .map(|front_matter| {
+            (
+                front_matter.title,
+                front_matter.description,
+                front_matter.source_hash,
+            )
+        })
+        .unwrap_or_default();
     let mut title = None;
     let mut description = String::new();
     let mut in_title_heading = false;
     let mut in_description_paragraph = false;
     let mut description_found = false;
+    let mut stats = ReadingStats::default();
+    let mut body_text = String::new();
+    let mut excerpt = None;
 
     // Create a new parser. We need to clone it to iterate multiple times.
     let parser = Parser::new(input);
 
     for event in parser {
+        if !in_title_heading && excerpt.is_none() {
+            match &event {
+                Event::Text(text) | Event::Code(text) => body_text.push_str(text),
+                Event::SoftBreak => body_text.push(' '),
+                Event::HardBreak => body_text.push('\n'),
+                Event::End(
+                    pulldown_cmark::TagEnd::Paragraph
+                    | pulldown_cmark::TagEnd::Heading(_)
+                    | pulldown_cmark::TagEnd::Item
+                    | pulldown_cmark::TagEnd::CodeBlock,
+                ) => {
+                    body_text.truncate(body_text.trim_end().len());
+                    body_text.push_str("\n\n");
+                }
+                Event::Html(html) | Event::InlineHtml(html) if MORE_MARKER.is_match(html) => {
+                    excerpt = Some(body_text.trim().to_string());
+                }
+                _ => {}
+            }
+        }
+        if let Event::Text(text) | Event::Code(text) = &event
+            && !in_title_heading
+        {
+            stats.add(text);
+        }
         match event {
             Event::Start(Tag::Heading { level, .. }) => {
                 if level == pulldown_cmark::HeadingLevel::H1 && title.is_none() {
@@ -565,7 +1091,7 @@ fn extract(input: &str) -> ExtractionResult<'_> {
                 }
             }
             Event::Start(Tag::Paragraph) => {
-                if title.is_some() && !description_found {
+                if (title.is_some() || front_title.is_some()) && !description_found {
                     in_description_paragraph = true;
                 }
             }
@@ -586,33 +1112,108 @@ fn extract(input: &str) -> ExtractionResult<'_> {
         }
     }
 
-    ExtractionResult {
-        title,
-        description,
+    Ok(ExtractionResult {
+        title: front_title.or(title),
+        description: front_description.unwrap_or(description),
+        excerpt: excerpt.filter(|excerpt| !excerpt.is_empty()),
+        stats,
+        source_hash,
         content: input,
-    }
+    })
+}
+
+/// Hex `sha256` of a Markdown body.
+pub(crate) fn digest(body: &str) -> String {
+    format!("{:x}", Sha256::digest(body.as_bytes()))
 }
 
 #[derive(Debug, Clone)]
 struct LocaleVariant {
     locale: String,
     title: Option<String>,
+    path: PathBuf,
+    /// `sha256` of the body.
+    digest: String,
+    /// Source revision the variant was translated from, if recorded.
+    source_hash: Option<String>,
 }
 
-fn parse_locale_from_filename(path: &Path, default_locale: &str) -> Option<String> {
+fn parse_locale_from_filename(
+    path: &Path,
+    default_locale: &str,
+    configured: &[String],
+) -> Option<String> {
     if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
         return None;
     }
     match path.file_stem().and_then(|stem| stem.to_str()) {
         Some("article") => Some(default_locale.to_string()),
-        Some(stem) if !stem.is_empty() => Some(stem.to_string()),
+        Some(stem) if !stem.is_empty() => parse_locale_stem(path, stem, default_locale, configured),
         _ => None,
     }
 }
 
+/// Locale of a variant named `<locale>.md` or `<slug>.<locale>.md`, warning
+/// about files whose name is not a known language tag or that repeat the
+/// default locale.
+fn parse_locale_stem(
+    path: &Path,
+    stem: &str,
+    default_locale: &str,
+    configured: &[String],
+) -> Option<String> {
+    let Some(locale) = locale::from_file_stem(stem, configured) else {
+        tracing::warn!(
+            "ignoring `{}`: `{stem}` is not an ISO 639-1 language tag or listed in `locales`",
+            path.display()
+        );
+        return None;
+    };
+    if locale == default_locale {
+        tracing::warn!(
+            "ignoring `{}`: `{locale}` is the article's default locale",
+            path.display()
+        );
+        return None;
+    }
+    Some(locale)
+}
+
+/// Add `variant` unless another file already provides its locale. Between two
+/// spellings of a locale, the file named after its canonical form wins.
+fn push_variant(variants: &mut Vec<LocaleVariant>, variant: LocaleVariant) {
+    let is_canonical = |variant: &LocaleVariant| {
+        variant
+            .path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| {
+                stem == variant.locale || stem.ends_with(&format!(".{}", variant.locale))
+            })
+    };
+    let Some(existing) = variants
+        .iter_mut()
+        .find(|existing| existing.locale == variant.locale)
+    else {
+        variants.push(variant);
+        return;
+    };
+    let ignored = if is_canonical(&variant) && !is_canonical(existing) {
+        std::mem::replace(existing, variant)
+    } else {
+        variant
+    };
+    tracing::warn!(
+        "ignoring `{}`: another file already provides locale `{}`",
+        ignored.path.display(),
+        ignored.locale
+    );
+}
+
 async fn enumerate_locales(
     dir: &Path,
     default_locale: &str,
+    configured: &[String],
 ) -> Result<Vec<LocaleVariant>, FailToOpenArticle> {
     let mut entries = tokio::fs::read_dir(dir)
         .await
@@ -633,17 +1234,121 @@ async fn enumerate_locales(
             continue;
         }
 
-        let Some(locale) = parse_locale_from_filename(&path, default_locale) else {
+        let Some(locale) = parse_locale_from_filename(&path, default_locale, configured) else {
             continue;
         };
         let content = read_to_string(&path)
             .await
             .map_err(|_| FailToOpenArticle::ArticleNotFound)?;
-        let extraction = extract(&content);
-        variants.push(LocaleVariant {
-            locale,
-            title: extraction.title.map(|s| s.to_string()),
-        });
+        let extraction = extract(&content).map_err(FailToOpenArticle::InvalidFrontMatter)?;
+        push_variant(
+            &mut variants,
+            LocaleVariant {
+                locale,
+                title: extraction.title.map(|s| s.to_string()),
+                path,
+                digest: digest(extraction.content),
+                source_hash: extraction.source_hash,
+            },
+        );
+    }
+
+    if variants.is_empty() {
+        Err(FailToOpenArticle::ArticleNotFound)
+    } else {
+        Ok(variants)
+    }
+}
+
+/// File stems that document a directory rather than being articles, compared
+/// case-insensitively.
+const NON_ARTICLE_STEMS: &[&str] = &[
+    "readme",
+    "notes",
+    "changelog",
+    "license",
+    "contributing",
+    "todo",
+];
+
+/// Whether `path` is the primary file of a single-file article: a `*.md` whose
+/// stem has no `.` (which would make it a locale variant), inside a category
+/// and not shadowed by an article directory of the same name.
+///
+/// Well-known documentation files (`README.md`, `NOTES.md`, …) and stems
+/// starting with `_` (Hugo and Zola `_index.md`) are not articles.
+pub(crate) async fn is_article_file(path: &Path) -> bool {
+    if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
+        return false;
+    }
+    let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+        return false;
+    };
+    let Some(category_dir) = path.parent() else {
+        return false;
+    };
+    !stem.is_empty()
+        && !stem.contains('.')
+        && !stem.starts_with('_')
+        && !NON_ARTICLE_STEMS
+            .iter()
+            .any(|name| stem.eq_ignore_ascii_case(name))
+        && tokio::fs::metadata(category_dir.join("Category.toml"))
+            .await
+            .is_ok()
+        && !category_dir.join(stem).is_dir()
+}
+
+async fn enumerate_file_locales(
+    category_dir: &Path,
+    slug: &str,
+    default_locale: &str,
+    configured: &[String],
+) -> Result<Vec<LocaleVariant>, FailToOpenArticle> {
+    let prefix = format!("{slug}.");
+    let mut entries = tokio::fs::read_dir(category_dir)
+        .await
+        .map_err(|_| FailToOpenArticle::ArticleNotFound)?;
+    let mut variants = Vec::new();
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|_| FailToOpenArticle::ArticleNotFound)?
+    {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let locale = if stem == slug {
+            default_locale.to_string()
+        } else if let Some(locale) = stem.strip_prefix(&prefix) {
+            if locale.is_empty() || locale.contains('.') {
+                continue;
+            }
+            let Some(locale) = parse_locale_stem(&path, locale, default_locale, configured) else {
+                continue;
+            };
+            locale
+        } else {
+            continue;
+        };
+        let content = read_to_string(&path)
+            .await
+            .map_err(|_| FailToOpenArticle::ArticleNotFound)?;
+        let extraction = extract(&content).map_err(FailToOpenArticle::InvalidFrontMatter)?;
+        push_variant(
+            &mut variants,
+            LocaleVariant {
+                locale,
+                title: extraction.title,
+                path,
+                digest: digest(extraction.content),
+                source_hash: extraction.source_hash,
+            },
+        );
     }
 
     if variants.is_empty() {
@@ -653,9 +1358,50 @@ async fn enumerate_locales(
     }
 }
 
+pub(crate) fn content_path(
+    layout: ArticleLayout,
+    category_dir: &Path,
+    slug: &str,
+    locale: &str,
+    default_locale: &str,
+) -> PathBuf {
+    match layout {
+        ArticleLayout::Directory => {
+            locale_to_path(&category_dir.join(slug), locale, default_locale)
+        }
+        ArticleLayout::File if locale == default_locale => category_dir.join(format!("{slug}.md")),
+        ArticleLayout::File => variant_file(category_dir, &format!("{slug}."), locale),
+    }
+}
+
 fn locale_to_path(dir: &Path, locale: &str, default_locale: &str) -> PathBuf {
     if locale == default_locale {
         return dir.join("article.md");
     }
-    dir.join(format!("{locale}.md"))
+    variant_file(dir, "", locale)
+}
+
+/// File of the variant in `locale` named `<prefix><locale>.md`. A file whose
+/// name spells the locale in another case (`zh-cn.md`, `zh_CN.md`) is used
+/// when the canonical one does not exist.
+fn variant_file(dir: &Path, prefix: &str, locale: &str) -> PathBuf {
+    let canonical = dir.join(format!("{prefix}{locale}.md"));
+    if canonical.exists() {
+        return canonical;
+    }
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.extension().and_then(|ext| ext.to_str()) == Some("md")
+                && path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.strip_prefix(prefix))
+                    .and_then(locale::canonicalize)
+                    .is_some_and(|found| found == locale)
+        })
+        .unwrap_or(canonical)
 }
@@ -0,0 +1,203 @@
+//! Files kept next to an article.
+//!
+//! A directory article may hold images and other files beside its Markdown,
+//! as the importers leave them (`diagram.png`, `2024/05/photo.jpg`). They are
+//! published in a directory named after the article's output file, so
+//! `guides/a-helpful-guide.html` gets `guides/a-helpful-guide/diagram.png`
+//! (with pretty URLs they simply sit next to `index.html`), and relative
+//! references to them in the Markdown are rewritten to match.
+
+use std::{
+    collections::BTreeSet,
+    io,
+    path::{Path, PathBuf},
+};
+
+use tokio::task::spawn_blocking;
+
+use crate::{
+    article::{Article, ArticleLayout, ArticlePreview},
+    link::{destinations, relative_href, splice},
+    utils::percent_decode,
+};
+
+/// The files next to an article and where they are published.
+#[derive(Debug, Clone, Default)]
+pub struct Bundle {
+    /// Directory holding the article's files.
+    source: PathBuf,
+    /// Build-relative directory the files are published in, ending with `/`.
+    output_dir: String,
+    /// Paths relative to `source`, with `/` separators.
+    files: BTreeSet<String>,
+}
+
+impl Bundle {
+    /// The files next to `article`, in any of its locales. Single-file
+    /// articles share their directory with the category and have none.
+    ///
+    /// # Errors
+    /// Returns an error if the article directory cannot be read.
+    pub async fn of(article: &ArticlePreview) -> io::Result<Self> {
+        if article.layout() != ArticleLayout::Directory {
+            return Ok(Self::default());
+        }
+        let source = article_dir(article);
+        let files = {
+            let source = source.clone();
+            spawn_blocking(move || list_files(&source)).await??
+        };
+        Ok(Self {
+            source,
+            output_dir: output_dir(&default_output_file(article)),
+            files,
+        })
+    }
+
+    /// Whether the article has no files besides its own.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Build-relative paths the files are published at.
+    pub fn outputs(&self) -> impl Iterator<Item = String> + '_ {
+        self.files
+            .iter()
+            .map(|file| format!("{}{file}", self.output_dir))
+    }
+
+    /// Point relative references to the files in `article`'s Markdown at
+    /// where they are published, as seen from the article's output file.
+    pub fn rewrite(&self, article: &mut Article) {
+        if self.is_empty() {
+            return;
+        }
+        let prefix = relative_href(&article.output_file(), &self.output_dir);
+        let content = article.content();
+        let replacements = destinations(content, true, |dest| self.names(dest).then_some(()))
+            .into_iter()
+            .map(|(range, ())| {
+                let written = &content[range.clone()];
+                let written = written.strip_prefix("./").unwrap_or(written);
+                (range, format!("{prefix}{written}"))
+            })
+            .collect::<Vec<_>>();
+        if !replacements.is_empty() {
+            article.content = splice(content, replacements);
+        }
+    }
+
+    /// Copy the files into the build directory `output`.
+    ///
+    /// # Errors
+    /// Returns an error if a file cannot be copied.
+    pub async fn emit(&self, output: &Path) -> io::Result<()> {
+        for file in &self.files {
+            let target = output.join(format!("{}{file}", self.output_dir));
+            if let Some(parent) = target.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::copy(self.source.join(file), target).await?;
+        }
+        Ok(())
+    }
+
+    /// Whether the link destination `dest` is a relative reference to one of
+    /// the files.
+    fn names(&self, dest: &str) -> bool {
+        let dest = dest.strip_prefix("./").unwrap_or(dest);
+        if dest.is_empty() || dest.starts_with(['/', '#', '@', '?']) || dest.contains(':') {
+            return false;
+        }
+        let path = dest.split(['?', '#']).next().unwrap_or(dest);
+        self.files.contains(&percent_decode(path))
+    }
+}
+
+/// Source of the file published at the build-relative `path` next to one of
+/// `previews`, if there is one.
+#[must_use]
+pub fn source_for(previews: &[ArticlePreview], path: &str) -> Option<PathBuf> {
+    previews
+        .iter()
+        .filter(|preview| {
+            preview.is_default_locale() && preview.layout() == ArticleLayout::Directory
+        })
+        .find_map(|preview| {
+            let relative = path.strip_prefix(&output_dir(&preview.output_file()))?;
+            is_bundled(relative).then(|| article_dir(preview).join(relative))
+        })
+        .filter(|source| source.is_file())
+}
+
+fn article_dir(article: &ArticlePreview) -> PathBuf {
+    article.category().dir().join(article.slug())
+}
+
+/// Output file of the default-locale variant, which names the directory every
+/// variant's files are published in.
+fn default_output_file(article: &ArticlePreview) -> String {
+    article
+        .translations()
+        .iter()
+        .find(|translation| translation.locale() == article.default_locale())
+        .map_or_else(
+            || article.output_file(),
+            |translation| translation.output_file().to_string(),
+        )
+}
+
+/// `guides/post.html` publishes its files in `guides/post/`, and
+/// `guides/post/index.html` next to itself.
+fn output_dir(output_file: &str) -> String {
+    if let Some(dir) = output_file.strip_suffix("index.html")
+        && (dir.is_empty() || dir.ends_with('/'))
+    {
+        return dir.to_string();
+    }
+    let stem = output_file
+        .rsplit_once('.')
+        .filter(|(stem, _)| !stem.ends_with('/'))
+        .map_or(output_file, |(stem, _)| stem);
+    format!("{stem}/")
+}
+
+/// Whether `relative`, inside an article directory, is published with it:
+/// everything but the article's Markdown, its `Article.toml` and hidden files.
+fn is_bundled(relative: &str) -> bool {
+    if relative.is_empty()
+        || relative
+            .split('/')
+            .any(|segment| segment.is_empty() || segment.starts_with('.') || segment == "..")
+    {
+        return false;
+    }
+    relative.contains('/')
+        || !(relative == "Article.toml"
+            || Path::new(relative)
+                .extension()
+                .is_some_and(|extension| extension == "md"))
+}
+
+fn list_files(dir: &Path) -> io::Result<BTreeSet<String>> {
+    let mut files = BTreeSet::new();
+    let mut pending = vec![(dir.to_path_buf(), String::new())];
+    while let Some((current, prefix)) = pending.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let entry = entry?;
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            let relative = format!("{prefix}{name}");
+            if entry.file_type()?.is_dir() {
+                if !name.starts_with('.') {
+                    pending.push((entry.path(), format!("{relative}/")));
+                }
+            } else if is_bundled(&relative) {
+                files.insert(relative);
+            }
+        }
+    }
+    Ok(files)
+}
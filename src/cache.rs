@@ -108,6 +108,23 @@ impl RenderCache {
         .await?
     }
 
+    /// Drop the cached pages stored under `output_paths`, e.g. after an article moved.
+    pub async fn invalidate(&self, output_paths: Vec<String>) -> eyre::Result<()> {
+        let db = Arc::clone(&self.db);
+        spawn_blocking(move || -> eyre::Result<()> {
+            let txn = db.begin_write()?;
+            {
+                let mut table = txn.open_table(CACHE_TABLE)?;
+                for key in &output_paths {
+                    table.remove(key.as_str())?;
+                }
+            }
+            txn.commit()?;
+            Ok(())
+        })
+        .await?
+    }
+
     fn article_key(article: &Article) -> String {
         article.output_path()
     }
@@ -3,7 +3,7 @@ use tokio::{fs, sync::mpsc};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 use crate::{
-    article::{Article, FailToOpenArticle},
+    article::{Article, FailToOpenArticle, is_article_dir, is_article_file},
     metadata::{CategoryMetadata, FailToOpenMetadata, MetadataExt},
     workspace::Workspace,
 };
@@ -36,6 +36,13 @@ impl Category {
         &self.segments
     }
 
+    /// Path of the category relative to `articles/`, e.g. `guides/rust`.
+    /// Empty for the root category.
+    #[must_use]
+    pub fn path(&self) -> String {
+        self.segments.join("/")
+    }
+
     #[must_use]
     pub const fn metadata(&self) -> &CategoryMetadata {
         &self.metadata
@@ -60,6 +67,9 @@ pub enum FailToOpenCategory {
     #[error("Workspace not found")]
     WorkspaceNotFound,
 
+    #[error("Category `{0}` does not exist")]
+    NotFound(String),
+
     #[error("Category path is invalid")]
     InvalidPath,
 
@@ -207,9 +217,14 @@ async fn list_category_articles(
     let mut entries = fs::read_dir(category.dir()).await?;
     while let Some(entry) = entries.next_entry().await? {
         let path = entry.path();
-        if entry.file_type().await?.is_dir()
-            && fs::metadata(path.join("Article.toml")).await.is_ok()
-        {
+        let article_path = if entry.file_type().await?.is_dir() {
+            is_article_dir(&path).await.then_some(path)
+        } else {
+            is_article_file(&path)
+                .await
+                .then(|| path.with_extension(""))
+        };
+        if let Some(path) = article_path {
             let relative = path
                 .strip_prefix(category.workspace().articles_dir())
                 .map_err(std::io::Error::other)?;
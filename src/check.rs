@@ -0,0 +1,327 @@
+//! Broken-link and missing-asset checks over a generated site.
+//!
+//! The checker walks the `build/` tree, collects every `href`, `src` and
+//! `srcset` candidate in the emitted HTML and verifies that internal targets
+//! exist as files (and, with a fragment, as anchors in that file). External
+//! links are only probed when explicitly enabled, only for hosts on the
+//! allowlist, and each for at most ten seconds.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+    time::Duration,
+};
+
+use color_eyre::eyre::{self, eyre};
+use regex::Regex;
+use serde::Serialize;
+use tokio::task::spawn_blocking;
+use url::Url;
+
+use crate::utils::percent_decode;
+
+const SITE_ORIGIN: &str = "http://thought.invalid/";
+
+/// How long a single external link may take to answer.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+static REFERENCE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\s(?:href|src)\s*=\s*(?:"([^"]*)"|'([^']*)')"#)
+        .expect("reference regex should compile")
+});
+
+static SRCSET_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\ssrcset\s*=\s*(?:"([^"]*)"|'([^']*)')"#)
+        .expect("srcset regex should compile")
+});
+
+static ANCHOR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\s(?:id|name)\s*=\s*(?:"([^"]*)"|'([^']*)')"#)
+        .expect("anchor regex should compile")
+});
+
+/// Options controlling which links are checked.
+#[derive(Debug, Clone, Default)]
+pub struct LinkCheckOptions {
+    /// Probe external `http(s)` links whose host is in `allowlist`.
+    pub external: bool,
+    /// Hosts (optionally `host:port`) that may be probed over the network.
+    pub allowlist: Vec<String>,
+}
+
+/// Why a link was reported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum LinkProblem {
+    /// The target file was not emitted.
+    MissingFile {
+        /// Resolved path relative to the build directory.
+        path: String,
+    },
+    /// The target file exists but has no element with the fragment's id.
+    MissingAnchor {
+        /// Resolved path relative to the build directory.
+        path: String,
+        /// The fragment that could not be found.
+        anchor: String,
+    },
+    /// An allowlisted external link failed to respond successfully.
+    External {
+        /// HTTP status or transport error.
+        error: String,
+    },
+}
+
+/// A single broken reference inside a page.
+#[derive(Debug, Clone, Serialize)]
+pub struct BrokenLink {
+    /// The `href`/`src` value, or `srcset` candidate URL, as written in the page.
+    pub href: String,
+    /// What is wrong with it.
+    #[serde(flatten)]
+    pub problem: LinkProblem,
+}
+
+/// Result of a link check, grouped by the page containing the links.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LinkReport {
+    /// Number of pages scanned.
+    pub pages: usize,
+    /// Number of references inspected.
+    pub links: usize,
+    /// Broken references keyed by page path relative to the build directory.
+    pub broken: BTreeMap<String, Vec<BrokenLink>>,
+}
+
+impl LinkReport {
+    /// Whether every checked link resolved.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.broken.is_empty()
+    }
+
+    /// Total number of broken references.
+    #[must_use]
+    pub fn broken_count(&self) -> usize {
+        self.broken.values().map(Vec::len).sum()
+    }
+}
+
+struct Page {
+    path: String,
+    references: Vec<String>,
+}
+
+struct SiteIndex {
+    files: HashSet<String>,
+    anchors: HashMap<String, HashSet<String>>,
+    pages: Vec<Page>,
+}
+
+/// Check every internal link and asset reference under `build_dir`.
+///
+/// # Errors
+/// Returns an error if the build directory cannot be read.
+pub async fn check_links(
+    build_dir: impl AsRef<Path>,
+    options: &LinkCheckOptions,
+) -> eyre::Result<LinkReport> {
+    let build_dir = build_dir.as_ref().to_path_buf();
+    if !build_dir.is_dir() {
+        return Err(eyre!(
+            "{} does not exist; run `thought generate` first",
+            build_dir.display()
+        ));
+    }
+    let site = spawn_blocking(move || index_site(&build_dir)).await??;
+
+    let origin = Url::parse(SITE_ORIGIN).expect("site origin should parse");
+    let mut report = LinkReport {
+        pages: site.pages.len(),
+        ..LinkReport::default()
+    };
+    let mut external: HashMap<String, Vec<(String, String)>> = HashMap::new();
+
+    for page in &site.pages {
+        let Ok(base) = origin.join(&page.path) else {
+            continue;
+        };
+        for reference in &page.references {
+            report.links += 1;
+            let Ok(target) = base.join(reference) else {
+                continue;
+            };
+            if target.host_str() != origin.host_str() || target.scheme() != origin.scheme() {
+                if options.external
+                    && matches!(target.scheme(), "http" | "https")
+                    && is_allowed(&target, &options.allowlist)
+                {
+                    let mut url = target.clone();
+                    url.set_fragment(None);
+                    external
+                        .entry(url.to_string())
+                        .or_default()
+                        .push((page.path.clone(), reference.clone()));
+                }
+                continue;
+            }
+
+            if let Some(problem) = check_internal(&site, &target) {
+                report
+                    .broken
+                    .entry(page.path.clone())
+                    .or_default()
+                    .push(BrokenLink {
+                        href: reference.clone(),
+                        problem,
+                    });
+            }
+        }
+    }
+
+    for (url, sources) in external {
+        if let Err(error) = probe(&url).await {
+            for (page, href) in sources {
+                report.broken.entry(page).or_default().push(BrokenLink {
+                    href,
+                    problem: LinkProblem::External {
+                        error: error.clone(),
+                    },
+                });
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn check_internal(site: &SiteIndex, target: &Url) -> Option<LinkProblem> {
+    let decoded = percent_decode(target.path());
+    let path = decoded.trim_start_matches('/');
+    let candidates = if path.is_empty() || path.ends_with('/') {
+        vec![format!("{path}index.html")]
+    } else {
+        vec![
+            path.to_string(),
+            format!("{path}/index.html"),
+            format!("{path}.html"),
+        ]
+    };
+    let Some(file) = candidates
+        .into_iter()
+        .find(|candidate| site.files.contains(candidate))
+    else {
+        return Some(LinkProblem::MissingFile {
+            path: path.to_string(),
+        });
+    };
+
+    let anchor = target.fragment().map(percent_decode).unwrap_or_default();
+    if anchor.is_empty() || anchor == "top" {
+        return None;
+    }
+    let found = site
+        .anchors
+        .get(&file)
+        .is_some_and(|anchors| anchors.contains(&anchor));
+    if found {
+        None
+    } else {
+        Some(LinkProblem::MissingAnchor { path: file, anchor })
+    }
+}
+
+fn is_allowed(url: &Url, allowlist: &[String]) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let with_port = url
+        .port()
+        .map(|port| format!("{host}:{port}"))
+        .unwrap_or_else(|| host.to_string());
+    allowlist.iter().any(|allowed| {
+        allowed.eq_ignore_ascii_case(host) || allowed.eq_ignore_ascii_case(&with_port)
+    })
+}
+
+async fn probe(url: &str) -> Result<(), String> {
+    let mut client = zenwave::client();
+    let request = client.get(url.to_string()).header("User-Agent", "thought");
+    let response = tokio::time::timeout(PROBE_TIMEOUT, request)
+        .await
+        .map_err(|_| format!("timed out after {}s", PROBE_TIMEOUT.as_secs()))?
+        .map_err(|err| err.to_string())?;
+    let status = response.status();
+    if status.is_success() || status.is_redirection() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {status}"))
+    }
+}
+
+fn index_site(root: &Path) -> io::Result<SiteIndex> {
+    let mut site = SiteIndex {
+        files: HashSet::new(),
+        anchors: HashMap::new(),
+        pages: Vec::new(),
+    };
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            let Some(relative) = relative_path(root, &path) else {
+                continue;
+            };
+            if path.extension().and_then(|ext| ext.to_str()) == Some("html") {
+                let html = std::fs::read_to_string(&path)?;
+                site.anchors.insert(
+                    relative.clone(),
+                    capture_values(&ANCHOR_RE, &html).collect(),
+                );
+                site.pages.push(Page {
+                    path: relative.clone(),
+                    references: capture_values(&REFERENCE_RE, &html)
+                        .chain(capture_values(&SRCSET_RE, &html).flat_map(|srcset| {
+                            srcset_urls(&srcset).map(str::to_string).collect::<Vec<_>>()
+                        }))
+                        .collect(),
+                });
+            }
+            site.files.insert(relative);
+        }
+    }
+    site.pages.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(site)
+}
+
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let relative: PathBuf = path.strip_prefix(root).ok()?.to_path_buf();
+    let segments = relative
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    Some(segments.join("/"))
+}
+
+fn capture_values<'a>(regex: &'a Regex, html: &'a str) -> impl Iterator<Item = String> + 'a {
+    regex.captures_iter(html).filter_map(|captures| {
+        captures
+            .get(1)
+            .or_else(|| captures.get(2))
+            .map(|value| value.as_str().trim().replace("&amp;", "&"))
+    })
+}
+
+/// The image URLs of a `srcset` value such as `a.png 1x, b.png 2x`.
+fn srcset_urls(srcset: &str) -> impl Iterator<Item = &str> {
+    srcset
+        .split(',')
+        .filter_map(|candidate| candidate.split_whitespace().next())
+}
@@ -0,0 +1,191 @@
+use clap::{Args, ValueEnum};
+use color_eyre::eyre::{self, eyre};
+use futures::StreamExt;
+use serde::Serialize;
+use thought::{article::Article, front_matter::parse_date, locale, workspace::Workspace};
+use time::OffsetDateTime;
+
+use crate::path_segments;
+
+#[derive(Args)]
+pub struct ListArgs {
+    /// Only articles in this category or below, e.g. `guides/rust`
+    #[arg(short, long)]
+    category: Option<String>,
+    /// Only articles carrying this tag (repeat to require several)
+    #[arg(short, long = "tag", value_name = "TAG")]
+    tags: Vec<String>,
+    /// Only articles available in this locale
+    #[arg(short, long)]
+    locale: Option<String>,
+    /// Only drafts
+    #[arg(long, conflicts_with = "published")]
+    drafts: bool,
+    /// Only articles that are not drafts
+    #[arg(long)]
+    published: bool,
+    /// Only articles created on or after this date (e.g. 2024-05-01)
+    #[arg(long, value_name = "DATE")]
+    since: Option<String>,
+    /// Only articles created on or before this date
+    #[arg(long, value_name = "DATE")]
+    until: Option<String>,
+    /// Sort order
+    #[arg(short, long, value_enum, default_value_t = SortKey::Created)]
+    sort: SortKey,
+    /// Reverse the sort order
+    #[arg(short, long)]
+    reverse: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SortKey {
+    Created,
+    Updated,
+    Title,
+}
+
+#[derive(Serialize)]
+struct ArticleSummary {
+    slug: String,
+    path: String,
+    title: String,
+    #[serde(with = "time::serde::rfc3339")]
+    created: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    updated: Option<OffsetDateTime>,
+    tags: Vec<String>,
+    locales: Vec<String>,
+    draft: bool,
+    words: usize,
+    sha256: String,
+}
+
+impl ArticleSummary {
+    async fn new(article: &Article) -> Self {
+        Self {
+            slug: article.slug().to_string(),
+            path: article.segments().join("/"),
+            title: article.title().to_string(),
+            created: article.metadata().created(),
+            updated: article.updated().await,
+            tags: article.metadata().tags().to_vec(),
+            locales: article
+                .translations()
+                .iter()
+                .map(|translation| translation.locale().to_string())
+                .collect(),
+            draft: article.metadata().is_draft(),
+            words: article.word_count(),
+            sha256: article.sha256(),
+        }
+    }
+}
+
+pub async fn run_list(workspace: &Workspace, args: ListArgs, emit_json: bool) -> eyre::Result<()> {
+    let category = args.category.as_deref().map(path_segments);
+    // Variants are stored under canonical tags, so `zh-tw` means `zh-TW`.
+    let locale = args
+        .locale
+        .as_deref()
+        .map(|tag| {
+            locale::canonicalize(tag).ok_or_else(|| eyre!("`{tag}` is not a valid language tag"))
+        })
+        .transpose()?;
+    let since = args.since.as_deref().map(parse_bound).transpose()?;
+    let until = args
+        .until
+        .as_deref()
+        .map(parse_bound)
+        .transpose()?
+        // A bare date includes the whole day.
+        .map(|until| {
+            if until.time() == time::Time::MIDNIGHT {
+                until + time::Duration::DAY - time::Duration::NANOSECOND
+            } else {
+                until
+            }
+        });
+
+    let mut summaries = Vec::new();
+    let mut articles = Box::pin(workspace.articles());
+    while let Some(article) = articles.next().await {
+        let article = article?;
+        if !article.is_default_locale() {
+            continue;
+        }
+
+        let metadata = article.metadata();
+        let keep = category
+            .as_ref()
+            .is_none_or(|category| article.category().segments().starts_with(category))
+            && args
+                .tags
+                .iter()
+                .all(|tag| metadata.tags().iter().any(|own| own == tag))
+            && locale.as_ref().is_none_or(|locale| {
+                article
+                    .translations()
+                    .iter()
+                    .any(|translation| translation.locale() == locale)
+            })
+            && (!args.drafts || metadata.is_draft())
+            && (!args.published || !metadata.is_draft())
+            && since.is_none_or(|since| metadata.created() >= since)
+            && until.is_none_or(|until| metadata.created() <= until);
+        if keep {
+            summaries.push(ArticleSummary::new(&article).await);
+        }
+    }
+
+    match args.sort {
+        SortKey::Created => summaries.sort_by_key(|summary| summary.created),
+        SortKey::Updated => summaries.sort_by_key(|summary| summary.updated),
+        SortKey::Title => summaries.sort_by_cached_key(|summary| summary.title.to_lowercase()),
+    }
+    if args.reverse {
+        summaries.reverse();
+    }
+
+    if emit_json {
+        println!("{}", serde_json::to_string_pretty(&summaries)?);
+        return Ok(());
+    }
+
+    if summaries.is_empty() {
+        println!("No articles found");
+        return Ok(());
+    }
+
+    let width = summaries
+        .iter()
+        .map(|summary| summary.path.len())
+        .max()
+        .unwrap_or(0)
+        .max("PATH".len());
+    println!(
+        "{:<width$}  {:<10}  {:>6}  {:<12}  TITLE",
+        "PATH", "CREATED", "WORDS", "LOCALES"
+    );
+    for summary in &summaries {
+        let created = summary.created.date().to_string();
+        let title = if summary.draft {
+            format!("{} (draft)", summary.title)
+        } else {
+            summary.title.clone()
+        };
+        println!(
+            "{:<width$}  {:<10}  {:>6}  {:<12}  {}",
+            summary.path,
+            created,
+            summary.words,
+            summary.locales.join(","),
+            title
+        );
+    }
+    Ok(())
+}
+
+fn parse_bound(input: &str) -> eyre::Result<OffsetDateTime> {
+    parse_date(input).map_err(|_| eyre!("Invalid date `{input}`, expected e.g. 2024-05-01"))
+}
@@ -0,0 +1,199 @@
+use clap::Subcommand;
+use color_eyre::{Section, eyre};
+use futures::StreamExt;
+use serde::Serialize;
+use thought::{category::Category, workspace::Workspace};
+use time::format_description::well_known::Rfc3339;
+use tracing::info;
+
+use crate::path_segments;
+
+#[derive(Subcommand)]
+pub enum CategoryCommands {
+    /// Create a category and any missing parents, e.g. `guides/rust`
+    Create {
+        path: String,
+        #[arg(short, long, default_value = "")]
+        description: String,
+    },
+    /// List every category with its article count
+    List,
+    /// Show a category's metadata, subcategories and articles
+    Describe { path: String },
+    /// Remove a category; refuses non-empty ones unless `--force` is given
+    Remove {
+        path: String,
+        /// Also delete the articles and subcategories inside it
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Serialize)]
+struct CategorySummary {
+    path: String,
+    name: String,
+    description: String,
+    created: String,
+    articles: usize,
+}
+
+#[derive(Serialize)]
+struct CategoryDetails {
+    #[serde(flatten)]
+    summary: CategorySummary,
+    subcategories: Vec<String>,
+    entries: Vec<ArticleEntry>,
+}
+
+#[derive(Serialize)]
+struct ArticleEntry {
+    slug: String,
+    title: String,
+}
+
+pub async fn run_category(
+    workspace: &Workspace,
+    command: CategoryCommands,
+    emit_json: bool,
+) -> eyre::Result<()> {
+    match command {
+        CategoryCommands::Create { path, description } => {
+            workspace
+                .create_category(path_segments(&path), description)
+                .await
+                .note("Failed to create category")?;
+            info!("Category `{path}` created successfully");
+            Ok(())
+        }
+        CategoryCommands::List => list_categories(workspace, emit_json).await,
+        CategoryCommands::Describe { path } => {
+            let category = open_category(workspace, &path).await?;
+            describe_category(&category, emit_json).await
+        }
+        CategoryCommands::Remove { path, force } => {
+            let removed = workspace
+                .remove_category(&path_segments(&path), force)
+                .await
+                .note("Failed to remove category");
+            if force {
+                removed?;
+            } else {
+                removed.suggestion("pass --force to delete its contents as well")?;
+            }
+            info!("Category `{path}` removed");
+            Ok(())
+        }
+    }
+}
+
+/// Resolve a `/`-separated category path, suggesting how to create it when missing.
+pub async fn open_category(workspace: &Workspace, path: &str) -> eyre::Result<Category> {
+    workspace
+        .category(&path_segments(path))
+        .await
+        .with_suggestion(|| format!("create it with `thought category create {path}`"))
+}
+
+async fn list_categories(workspace: &Workspace, emit_json: bool) -> eyre::Result<()> {
+    let mut categories = vec![workspace.category(&[]).await?];
+    let mut stream = Box::pin(workspace.categories());
+    while let Some(category) = stream.next().await {
+        categories.push(category?);
+    }
+    categories.sort_by_key(Category::path);
+
+    let mut summaries = Vec::with_capacity(categories.len());
+    for category in &categories {
+        summaries.push(summarize(category).await?);
+    }
+
+    if emit_json {
+        println!("{}", serde_json::to_string_pretty(&summaries)?);
+        return Ok(());
+    }
+
+    let width = summaries
+        .iter()
+        .map(|summary| display_path(&summary.path).len())
+        .max()
+        .unwrap_or(0);
+    for summary in &summaries {
+        println!(
+            "{:<width$}  {:>3} article(s)  {}",
+            display_path(&summary.path),
+            summary.articles,
+            summary.name
+        );
+    }
+    Ok(())
+}
+
+async fn describe_category(category: &Category, emit_json: bool) -> eyre::Result<()> {
+    let summary = summarize(category).await?;
+
+    let mut subcategories = Vec::new();
+    let mut children = Box::pin(category.list_categories());
+    while let Some(child) = children.next().await {
+        subcategories.push(child?.path());
+    }
+    subcategories.sort();
+
+    let mut entries = Vec::new();
+    let mut articles = Box::pin(category.list_articles());
+    while let Some(article) = articles.next().await {
+        let article = article?;
+        entries.push(ArticleEntry {
+            slug: article.slug().to_string(),
+            title: article.title().to_string(),
+        });
+    }
+    entries.sort_by(|a, b| a.slug.cmp(&b.slug));
+
+    let details = CategoryDetails {
+        summary,
+        subcategories,
+        entries,
+    };
+    if emit_json {
+        println!("{}", serde_json::to_string_pretty(&details)?);
+        return Ok(());
+    }
+
+    println!("{}", details.summary.name);
+    println!("  path:    {}", display_path(&details.summary.path));
+    println!("  created: {}", details.summary.created);
+    if !details.summary.description.is_empty() {
+        println!("  {}", details.summary.description);
+    }
+    if !details.subcategories.is_empty() {
+        println!("Subcategories:");
+        for path in &details.subcategories {
+            println!("  • {path}");
+        }
+    }
+    println!("Articles ({}):", details.entries.len());
+    for entry in &details.entries {
+        println!("  • {} — {}", entry.slug, entry.title);
+    }
+    Ok(())
+}
+
+async fn summarize(category: &Category) -> eyre::Result<CategorySummary> {
+    let articles = category
+        .list_articles()
+        .filter(|article| std::future::ready(article.is_ok()))
+        .count()
+        .await;
+    Ok(CategorySummary {
+        path: category.path(),
+        name: category.metadata().name().to_string(),
+        description: category.metadata().description().to_string(),
+        created: category.metadata().created().format(&Rfc3339)?,
+        articles,
+    })
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() { "/" } else { path }
+}
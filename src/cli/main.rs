@@ -2,10 +2,15 @@ use core::time::Duration;
 use std::{
     env::current_dir,
     io::{self, Write},
+    path::{Path, PathBuf},
     process::exit,
 };
 
-use crate::plugin::{PluginCommands, handle_plugin_command};
+use crate::{
+    article::{ListArgs, run_list},
+    category::{CategoryCommands, open_category, run_category},
+    plugin::{PluginCommands, handle_plugin_command},
+};
 use clap::{Parser, Subcommand};
 use color_eyre::{
     Section,
@@ -13,13 +18,24 @@ use color_eyre::{
     eyre::{self},
 };
 use indicatif::{ProgressBar, ProgressStyle};
-use thought::{search::Searcher, serve, workspace::Workspace};
+use thought::{
+    archive::ExportFormat,
+    article::ArticleLayout,
+    check::{LinkCheckOptions, LinkProblem, check_links},
+    engine::GenerateOptions,
+    import::{ImportFormat, ImportOptions},
+    search::Searcher,
+    serve,
+    workspace::Workspace,
+};
 use tracing::{error, info, level_filters::LevelFilter};
 use tracing_subscriber::{
     EnvFilter, filter::Directive, layer::SubscriberExt, util::SubscriberInitExt,
 };
-use translate::run_translate;
+use translate::{check_translations, run_translate};
 
+mod article;
+mod category;
 mod plugin;
 mod translate;
 
@@ -49,7 +65,15 @@ enum Commands {
     #[command(subcommand)]
     Article(ArticleCommands),
 
-    Generate,
+    /// Manage categories
+    #[command(subcommand)]
+    Category(CategoryCommands),
+
+    Generate {
+        /// Also render drafts and future-dated articles at their URLs
+        #[arg(long)]
+        drafts: bool,
+    },
 
     /// Search indexed articles with fuzzy, multilingual matching.
     Search {
@@ -73,9 +97,55 @@ enum Commands {
     },
 
     /// Translate all articles into the given language code (uses OpenRouter).
+    ///
+    /// Translations whose source article changed since they were made are
+    /// translated again.
     Translate {
         /// Target language code, e.g. zh-CN, ja, fr
         language: Option<String>,
+        /// List outdated translations (in every language unless one is given)
+        /// instead of translating, failing if there are any
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Import posts from another generator (hugo, jekyll, hexo, zola, wordpress)
+    /// or restore a `thought export` archive (thought-archive)
+    Import {
+        format: ImportFormat,
+        /// Root of the site to import, or the export file
+        path: PathBuf,
+        /// Local copy of `wp-content/uploads` to read attachments from instead
+        /// of downloading them
+        #[arg(long, value_name = "DIR")]
+        uploads: Option<PathBuf>,
+    },
+
+    /// Export every category, article and asset as JSON, NDJSON or zip
+    Export {
+        /// json, ndjson or zip (default: from the output extension, else json)
+        #[arg(short, long)]
+        format: Option<ExportFormat>,
+        /// File to write; JSON and NDJSON go to stdout when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Verify the generated site
+    #[command(subcommand)]
+    Check(CheckCommands),
+}
+
+#[derive(Subcommand)]
+enum CheckCommands {
+    /// Report broken internal links, missing assets and anchors in `build/`.
+    Links {
+        /// Also probe external links whose host is allowlisted
+        #[arg(long)]
+        external: bool,
+        /// Extra host (or host:port) to allow for external probing
+        #[arg(long = "allow", value_name = "HOST")]
+        allow: Vec<String>,
     },
 }
 
@@ -84,7 +154,28 @@ enum ArticleCommands {
     // create a new article
     Create {
         title: String,
+        /// Category path, e.g. `guides/rust`
+        #[arg(short, long)]
         category: Option<String>,
+        /// Write a single `<slug>.md` instead of a `<slug>/` directory
+        #[arg(long)]
+        single_file: bool,
+    },
+    /// List articles, optionally filtered and sorted
+    List(ListArgs),
+    /// Move an article into another category, leaving a redirect behind.
+    Move {
+        /// Article path, e.g. `guides/hello-world`
+        path: String,
+        /// Destination category, e.g. `notes/rust` (`/` for the root)
+        category: String,
+    },
+    /// Change an article's slug, leaving a redirect behind.
+    Rename {
+        /// Article path, e.g. `guides/hello-world`
+        path: String,
+        /// New slug
+        slug: String,
     },
 }
 
@@ -153,19 +244,52 @@ async fn entry(cli: Cli) -> eyre::Result<()> {
                 .note("Can't open workspace")?;
             match command {
                 Commands::Article(article_cmd) => match article_cmd {
-                    ArticleCommands::Create { title, category: _ } => {
+                    ArticleCommands::Create {
+                        title,
+                        category,
+                        single_file,
+                    } => {
+                        let category = match category {
+                            Some(path) => Some(open_category(&workspace, &path).await?),
+                            None => None,
+                        };
+                        let layout = if single_file {
+                            ArticleLayout::File
+                        } else {
+                            ArticleLayout::Directory
+                        };
                         workspace
-                            .create_article(title, None)
+                            .create_article(title, category, layout)
                             .await
                             .note("Failed to create article")?;
                         info!("Article created successfully");
                         Ok(())
                     }
+                    ArticleCommands::List(args) => run_list(&workspace, args, cli.json).await,
+                    ArticleCommands::Move { path, category } => {
+                        let article = workspace
+                            .move_article(&path_segments(&path), &path_segments(&category))
+                            .await
+                            .note("Failed to move article")?;
+                        info!("Article moved to {}", article.segments().join("/"));
+                        Ok(())
+                    }
+                    ArticleCommands::Rename { path, slug } => {
+                        let article = workspace
+                            .rename_article(&path_segments(&path), &slug)
+                            .await
+                            .note("Failed to rename article")?;
+                        info!("Article renamed to {}", article.segments().join("/"));
+                        Ok(())
+                    }
                 },
-                Commands::Generate => {
+                Commands::Category(category_cmd) => {
+                    run_category(&workspace, category_cmd, cli.json).await
+                }
+                Commands::Generate { drafts } => {
                     long_task(
                         "Generating site...",
-                        workspace.generate(workspace.build_dir()),
+                        workspace.generate(workspace.build_dir(), GenerateOptions { drafts }),
                         "Site generated successfully",
                     )
                     .await?;
@@ -183,10 +307,28 @@ async fn entry(cli: Cli) -> eyre::Result<()> {
                     serve::serve(workspace.clone(), host, port, allow_fallback).await?;
                     Ok(())
                 }
-                Commands::Translate { language } => {
-                    run_translate(workspace.clone(), language).await?;
+                Commands::Translate { language, check } => {
+                    if check {
+                        check_translations(workspace.clone(), language).await?;
+                    } else {
+                        run_translate(workspace.clone(), language).await?;
+                    }
                     Ok(())
                 }
+                Commands::Import {
+                    format,
+                    path,
+                    uploads,
+                } => {
+                    let options = ImportOptions { uploads };
+                    run_import(&workspace, format, &path, &options, cli.json).await
+                }
+                Commands::Export { format, output } => {
+                    run_export(&workspace, format, output.as_deref()).await
+                }
+                Commands::Check(CheckCommands::Links { external, allow }) => {
+                    run_check_links(&workspace, external, allow, cli.json).await
+                }
                 _ => unreachable!(),
             }
         }
@@ -230,6 +372,15 @@ fn prompt_blog_name() -> eyre::Result<String> {
     }
 }
 
+/// Split a `/`-separated article or category path into segments.
+pub(crate) fn path_segments(path: &str) -> Vec<String> {
+    path.split('/')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 async fn run_search(workspace: &Workspace, query: &str, emit_json: bool) -> eyre::Result<()> {
     let searcher = Searcher::open(workspace.clone())
         .await
@@ -266,3 +417,118 @@ async fn run_search(workspace: &Workspace, query: &str, emit_json: bool) -> eyre
     }
     Ok(())
 }
+
+async fn run_import(
+    workspace: &Workspace,
+    format: ImportFormat,
+    path: &Path,
+    options: &ImportOptions,
+    emit_json: bool,
+) -> eyre::Result<()> {
+    let report = long_task(
+        "Importing posts...",
+        workspace.import(format, path, options),
+        "Import finished",
+    )
+    .await
+    .note("Failed to import")?;
+
+    if emit_json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!(
+        "Imported {} article(s) and {} asset(s)",
+        report.imported.len(),
+        report.assets
+    );
+    for skipped in &report.skipped {
+        println!("  skipped {}: {}", skipped.source.display(), skipped.reason);
+    }
+    for warning in &report.warnings {
+        println!("  warning: {warning}");
+    }
+    Ok(())
+}
+
+async fn run_export(
+    workspace: &Workspace,
+    format: Option<ExportFormat>,
+    output: Option<&Path>,
+) -> eyre::Result<()> {
+    let format = format
+        .or_else(|| output.and_then(ExportFormat::from_path))
+        .unwrap_or(ExportFormat::Json);
+    if format == ExportFormat::Zip && output.is_none() {
+        return Err(
+            eyre::eyre!("Zip archives need an output file").suggestion("Pass `--output site.zip`")
+        );
+    }
+
+    let bytes = long_task("Exporting...", workspace.export(format), "Export finished")
+        .await
+        .note("Failed to export")?;
+    match output {
+        Some(path) => {
+            tokio::fs::write(path, &bytes).await?;
+            info!("Wrote {format} archive to {}", path.display());
+        }
+        None => io::stdout().write_all(&bytes)?,
+    }
+    Ok(())
+}
+
+async fn run_check_links(
+    workspace: &Workspace,
+    external: bool,
+    allow: Vec<String>,
+    emit_json: bool,
+) -> eyre::Result<()> {
+    let mut allowlist = workspace
+        .manifest()
+        .link_config()
+        .external_allowlist
+        .clone();
+    allowlist.extend(allow);
+    let options = LinkCheckOptions {
+        external,
+        allowlist,
+    };
+    let report = check_links(workspace.build_dir(), &options)
+        .await
+        .note("Failed to check links")?;
+
+    if emit_json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if report.is_clean() {
+        println!(
+            "Checked {} link(s) across {} page(s); no problems found",
+            report.links, report.pages
+        );
+    } else {
+        for (page, links) in &report.broken {
+            println!("{page}");
+            for link in links {
+                let reason = match &link.problem {
+                    LinkProblem::MissingFile { path } => format!("missing file `{path}`"),
+                    LinkProblem::MissingAnchor { path, anchor } => {
+                        format!("no anchor `#{anchor}` in `{path}`")
+                    }
+                    LinkProblem::External { error } => format!("external link failed: {error}"),
+                };
+                println!("  {} -> {reason}", link.href);
+            }
+        }
+    }
+
+    if report.is_clean() {
+        Ok(())
+    } else {
+        Err(eyre::eyre!(
+            "{} broken link(s) in {} page(s)",
+            report.broken_count(),
+            report.broken.len()
+        ))
+    }
+}
@@ -2,9 +2,10 @@ use thought_plugin::{
     askama::Template,
     export_theme,
     helpers::{
-        format_rfc3339, index_assets_prefix, index_search_script_path, markdown_to_html,
+        format_rfc3339, index_root_prefix, index_search_script_path, markdown_to_html,
+        series_root_prefix,
     },
-    Article, ArticlePreview, Theme,
+    Archive, Article, ArticlePreview, Theme,
 };
 
 pub struct Plugin;
@@ -12,6 +13,8 @@ pub struct Plugin;
 #[derive(Template)]
 #[template(path = "article.html")]
 struct ArticleTemplate<'a> {
+    lang: &'a str,
+    dir: &'a str,
     title: &'a str,
     created: &'a str,
     body: &'a str,
@@ -19,6 +22,7 @@ struct ArticleTemplate<'a> {
     search_js: &'a str,
     asset_prefix: &'a str,
     translations: &'a [LangOption],
+    stale: bool,
 }
 
 #[derive(Template)]
@@ -53,6 +57,8 @@ impl Theme for Plugin {
             })
             .collect::<Vec<_>>();
         ArticleTemplate {
+            lang: article.locale(),
+            dir: article.direction().as_str(),
             title: article.title(),
             created: &created,
             body: article.content_html().as_str(),
@@ -60,27 +66,65 @@ impl Theme for Plugin {
             search_js: &article.search_script_path(),
             asset_prefix: &article.assets_prefix(),
             translations: translations.as_slice(),
+            stale: article.is_stale(),
         }
         .render()
         .expect("failed to render article template")
     }
 
-    fn generate_index(articles: Vec<ArticlePreview>) -> String {
+    fn generate_index(articles: Vec<ArticlePreview>, locale: Option<String>) -> String {
+        let prefix = index_root_prefix(locale.as_deref());
         let entries = articles
             .into_iter()
             .map(|article| IndexEntry {
                 title: article.title().to_string(),
-                href: article.output_file(),
+                href: format!("{prefix}{}", article.output_file()),
             })
             .collect::<Vec<_>>();
         IndexTemplate {
             entries: &entries,
-            search_js: index_search_script_path(),
-            asset_prefix: index_assets_prefix(),
+            search_js: &format!("{prefix}{}", index_search_script_path()),
+            asset_prefix: prefix,
         }
         .render()
         .expect("failed to render index template")
     }
+
+    fn generate_series(_name: String, articles: Vec<ArticlePreview>) -> String {
+        let prefix = series_root_prefix();
+        let entries = articles
+            .into_iter()
+            .map(|article| IndexEntry {
+                title: article.title().to_string(),
+                href: format!("{prefix}{}", article.output_file()),
+            })
+            .collect::<Vec<_>>();
+        IndexTemplate {
+            entries: &entries,
+            search_js: &format!("{prefix}{}", index_search_script_path()),
+            asset_prefix: prefix,
+        }
+        .render()
+        .expect("failed to render series template")
+    }
+
+    fn generate_archive(archive: Archive) -> String {
+        let prefix = archive.assets_prefix();
+        let entries = archive
+            .articles()
+            .map(|article| IndexEntry {
+                title: article.title().to_string(),
+                href: format!("{prefix}{}", article.output_file()),
+            })
+            .collect::<Vec<_>>();
+        IndexTemplate {
+            entries: &entries,
+            search_js: &format!("{prefix}{}", index_search_script_path()),
+            asset_prefix: &prefix,
+        }
+        .render()
+        .expect("failed to render archive template")
+    }
 }
 
 export_theme!(Plugin);
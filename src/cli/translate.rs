@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     path::Path,
     sync::{Arc, LazyLock},
     time::Duration,
@@ -17,7 +18,16 @@ use regex::Regex;
 use tokio::time::sleep;
 use tracing::{info, warn};
 
-use thought::{article::Article, workspace::Workspace};
+use thought::{
+    article::Article,
+    front_matter,
+    glossary::Glossary,
+    locale,
+    metadata::{TranslationConfig, TranslationProvider},
+    segment::{SegmentError, Segments},
+    translation_memory::TranslationMemory,
+    workspace::Workspace,
+};
 
 const LANGUAGE_EXAMPLES: [(&str, &str); 11] = [
     ("🇨🇳", "zh-CN"),
@@ -38,17 +48,19 @@ pub async fn run_translate(workspace: Workspace, language: Option<String>) -> ey
 
     let config = workspace.manifest().translation_config();
     let models = config.effective_models();
-    if models.is_empty() {
+    let backend = Backend::from_config(&config)?;
+    if models.is_empty() && matches!(backend, Backend::Remote { .. }) {
         return Err(eyre!("No translation models configured"));
     }
-    let api_key = std::env::var("OPENROUTER_API_KEY")
-        .wrap_err("Set OPENROUTER_API_KEY in your environment to enable translation")?;
 
     let jobs = collect_jobs(&workspace, &target).await?;
     if jobs.is_empty() {
-        info!("All articles already have a {target} translation");
+        info!("All articles already have an up-to-date {target} translation");
         return Ok(());
     }
+    let glossary = Arc::new(Glossary::load(workspace.glossary_path()).await?);
+    let memory =
+        TranslationMemory::load(workspace.cache_dir().join("translation_memory.redb")).await?;
 
     let pb = Arc::new(ProgressBar::new(jobs.len() as u64));
     pb.set_style(
@@ -69,10 +81,16 @@ pub async fn run_translate(workspace: Workspace, language: Option<String>) -> ey
         let target = target.clone();
         let pb = pb.clone();
         let models = models.clone();
-        let api_key = api_key.clone();
+        let backend = backend.clone();
+        let glossary = glossary.clone();
+        let memory = memory.clone();
         async move {
             pb.set_message(format!("{} → {target}", article.title()));
-            match translate_article(&article, &target, &models, &api_key, retries).await {
+            match translate_article(
+                &article, &target, &models, &backend, &glossary, &memory, retries,
+            )
+            .await
+            {
                 Ok(_) => {
                     pb.inc(1);
                     Ok(())
@@ -105,6 +123,53 @@ pub async fn run_translate(workspace: Workspace, language: Option<String>) -> ey
     }
 }
 
+/// List translations made from an older revision of their source, in
+/// `language` or in every locale.
+///
+/// # Errors
+/// Returns an error if any translation is outdated.
+pub async fn check_translations(
+    workspace: Workspace,
+    language: Option<String>,
+) -> eyre::Result<()> {
+    let target = match language {
+        Some(raw) if !raw.trim().is_empty() => Some(parse_language_code(Some(raw))?),
+        _ => None,
+    };
+    let mut stale = 0_usize;
+    let mut stream = workspace.articles();
+    while let Some(article) = stream.try_next().await? {
+        if !article.is_default_locale() {
+            continue;
+        }
+        for translation in article.translations() {
+            if !translation.is_stale()
+                || target
+                    .as_deref()
+                    .is_some_and(|target| translation.locale() != target)
+            {
+                continue;
+            }
+            stale += 1;
+            let path = article.content_path_for_locale(translation.locale());
+            let path = path
+                .strip_prefix(workspace.root())
+                .unwrap_or(&path)
+                .display()
+                .to_string();
+            println!("{path}: outdated {} translation", translation.locale());
+        }
+    }
+    if stale == 0 {
+        info!("All translations are up to date");
+        Ok(())
+    } else {
+        Err(eyre!(
+            "{stale} translation(s) were made from an older revision of their source"
+        ))
+    }
+}
+
 async fn collect_jobs(workspace: &Workspace, target: &str) -> eyre::Result<Vec<Article>> {
     let mut jobs = Vec::new();
     let mut stream = workspace.articles();
@@ -118,7 +183,7 @@ async fn collect_jobs(workspace: &Workspace, target: &str) -> eyre::Result<Vec<A
         if article
             .translations()
             .iter()
-            .any(|t| t.locale().eq_ignore_ascii_case(target))
+            .any(|t| t.locale().eq_ignore_ascii_case(target) && !t.is_stale())
         {
             continue;
         }
@@ -127,25 +192,239 @@ async fn collect_jobs(workspace: &Workspace, target: &str) -> eyre::Result<Vec<A
     Ok(jobs)
 }
 
+/// Where translations come from, resolved from `[translation]` in `Thought.toml`.
+#[derive(Clone)]
+enum Backend {
+    /// An OpenAI-compatible chat API: OpenRouter, OpenAI or a local server.
+    Remote {
+        provider: TranslationProvider,
+        api_key: String,
+        base_url: Option<String>,
+    },
+    /// Word-for-word replacement that needs no network.
+    Dictionary(Arc<BTreeMap<String, String>>),
+}
+
+impl Backend {
+    fn from_config(config: &TranslationConfig) -> eyre::Result<Self> {
+        let key_from = |default: &str| {
+            let name = config.api_key_env.as_deref().unwrap_or(default);
+            std::env::var(name)
+                .ok()
+                .filter(|key| !key.is_empty())
+                .ok_or_else(|| {
+                    eyre!("Set {name} in your environment to enable translation via {default}")
+                })
+        };
+        match config.provider {
+            TranslationProvider::OpenRouter => Ok(Self::Remote {
+                provider: config.provider,
+                api_key: key_from("OPENROUTER_API_KEY")?,
+                base_url: config.base_url.clone(),
+            }),
+            TranslationProvider::OpenAi => {
+                // Local servers usually accept any key, so one is only
+                // required for OpenAI itself.
+                let api_key = match (&config.base_url, key_from("OPENAI_API_KEY")) {
+                    (_, Ok(key)) => key,
+                    (Some(_), Err(_)) => String::new(),
+                    (None, Err(err)) => return Err(err),
+                };
+                Ok(Self::Remote {
+                    provider: config.provider,
+                    api_key,
+                    base_url: config.base_url.clone(),
+                })
+            }
+            TranslationProvider::Dictionary => {
+                Ok(Self::Dictionary(Arc::new(config.dictionary.clone())))
+            }
+        }
+    }
+
+    fn model(
+        provider: TranslationProvider,
+        api_key: &str,
+        base_url: Option<&str>,
+        name: &str,
+    ) -> OpenAI {
+        let client = match provider {
+            TranslationProvider::OpenRouter => OpenAI::openrouter(api_key.to_string()),
+            _ => OpenAI::new(api_key.to_string()),
+        };
+        let client = match base_url {
+            Some(base_url) => client.with_base_url(base_url.to_string()),
+            None => client,
+        };
+        client.with_model(name.to_string())
+    }
+}
+
 async fn translate_article(
     article: &Article,
     target: &str,
     models: &[String],
-    api_key: &str,
+    backend: &Backend,
+    glossary: &Glossary,
+    memory: &TranslationMemory,
     max_retries: usize,
 ) -> eyre::Result<()> {
-    let prompt = build_prompt(article, target);
+    let segments = Segments::new(article.content());
+    let body_count = segments.len();
+    // The title and description in front matter are translated after the body.
+    let header = front_matter_fields(article).await?;
+    let texts = segments
+        .texts()
+        .map(str::to_string)
+        .chain(header.iter().map(|(_, value)| value.clone()))
+        .collect::<Vec<_>>();
+    let source = article.default_locale();
+    let translated = match backend {
+        // Dictionary output is not remembered, so that it never stands in for
+        // a real translation later.
+        Backend::Dictionary(dictionary) => {
+            let translated = texts
+                .iter()
+                .map(|text| {
+                    glossary.replace(text, target, |rest| {
+                        translate_with_dictionary(rest, dictionary)
+                    })
+                })
+                .collect::<Vec<_>>();
+            segments.reassemble(&translated[..body_count])?;
+            translated
+        }
+        Backend::Remote {
+            provider,
+            api_key,
+            base_url,
+        } => {
+            // Remembered translations are reused while they follow the glossary.
+            let remembered = memory
+                .lookup(source, target, &texts)
+                .await?
+                .into_iter()
+                .zip(&texts)
+                .map(|(hit, text)| {
+                    hit.filter(|translation| glossary.verify(text, translation, target).is_ok())
+                })
+                .collect::<Vec<_>>();
+            let pending = (0..texts.len())
+                .filter(|&index| remembered[index].is_none())
+                .collect::<Vec<_>>();
+            if pending.is_empty() {
+                let translated = remembered.into_iter().flatten().collect::<Vec<_>>();
+                segments.reassemble(&translated[..body_count])?;
+                translated
+            } else {
+                let requested = pending
+                    .iter()
+                    .map(|&index| texts[index].as_str())
+                    .collect::<Vec<_>>();
+                let prompt = build_prompt(article, target, &requested, glossary);
+                let (translated, fresh) = request_with_fallback(
+                    article,
+                    &prompt,
+                    models,
+                    |name| Backend::model(*provider, api_key, base_url.as_deref(), name),
+                    |output| {
+                        let fresh = split_segments(output, pending.len())?;
+                        let mut translated = remembered.clone();
+                        for (&index, translation) in pending.iter().zip(&fresh) {
+                            glossary
+                                .verify(&texts[index], translation, target)
+                                .wrap_err_with(|| format!("segment {}", index + 1))?;
+                            translated[index] = Some(translation.clone());
+                        }
+                        let translated = translated.into_iter().flatten().collect::<Vec<_>>();
+                        segments.reassemble(&translated[..body_count])?;
+                        Ok((translated, fresh))
+                    },
+                    max_retries,
+                )
+                .await?;
+                let learned = pending
+                    .iter()
+                    .map(|&index| texts[index].clone())
+                    .zip(fresh)
+                    .collect();
+                memory.store(source, target, learned).await?;
+                translated
+            }
+        }
+    };
+
+    let output = segments.reassemble(&translated[..body_count])?;
+    let header = header
+        .iter()
+        .map(|&(key, _)| key)
+        .zip(translated[body_count..].iter().cloned())
+        .collect::<Vec<_>>();
+    let path = article.content_path_for_locale(target);
+    write_file(&path, &translation_file(article, &header, &output)).await
+}
+
+/// The `title` and `description` of `article`'s source front matter, keyed
+/// as they are written in a translation.
+async fn front_matter_fields(article: &Article) -> eyre::Result<Vec<(&'static str, String)>> {
+    let path = article.content_path_for_locale(article.default_locale());
+    let source = tokio::fs::read_to_string(&path)
+        .await
+        .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+    let (front_matter, _) = front_matter::split(&source)?;
+    let front_matter = front_matter.unwrap_or_default();
+    let title = front_matter.title.map(|title| ("title", title));
+    // A description kept in `Article.toml` is shared by every locale, so it
+    // is carried into the translation's front matter as well.
+    let description = front_matter
+        .description
+        .or_else(|| article.metadata().description().map(str::to_string))
+        .map(|description| ("description", description));
+    Ok(title
+        .into_iter()
+        .chain(description)
+        .filter(|(_, value)| !value.trim().is_empty())
+        .collect())
+}
+
+/// A translation file: the translated `header` fields and the source hash in
+/// front matter, then the translated body.
+fn translation_file(article: &Article, header: &[(&str, String)], body: &str) -> String {
+    let mut contents = String::from("+++\n");
+    for (key, value) in header {
+        contents.push_str(&format!("{key} = {}\n", toml::Value::String(value.clone())));
+    }
+    contents.push_str(&format!(
+        "source_hash = \"{}\"\n+++\n\n{}",
+        article.preview().source_hash(),
+        body.trim_start()
+    ));
+    contents
+}
+
+/// Ask each model in turn, retrying with backoff, until one answers with a
+/// translation that `accept` turns into the final document.
+async fn request_with_fallback<T>(
+    article: &Article,
+    prompt: &str,
+    models: &[String],
+    model_for: impl Fn(&str) -> OpenAI,
+    accept: impl Fn(&str) -> eyre::Result<T>,
+    max_retries: usize,
+) -> eyre::Result<T> {
     let mut last_error = None;
 
     for model_name in models {
-        let model = OpenAI::openrouter(api_key.to_string()).with_model(model_name.clone());
+        let model = model_for(model_name);
         for attempt in 0..=max_retries {
-            match request_translation(model.clone(), &prompt).await {
-                Ok(output) => {
-                    let path = article.dir().join(format!("{target}.md"));
-                    write_file(&path, &output).await?;
-                    return Ok(());
-                }
+            let result = request_translation(model.clone(), prompt)
+                .await
+                .and_then(|output| {
+                    accept(&output)
+                        .wrap_err_with(|| format!("Rejected translation from {model_name}"))
+                });
+            match result {
+                Ok(output) => return Ok(output),
                 Err(err) => {
                     last_error = Some(err);
                     if attempt < max_retries {
@@ -174,8 +453,9 @@ async fn translate_article(
 async fn request_translation(model: OpenAI, prompt: &str) -> eyre::Result<String> {
     let stream = model.respond(LLMRequest::new([
         Message::system(
-            "You are a professional technical translator. Preserve Markdown structure, \
-             keep code fences unchanged, and do not add commentary.",
+            "You are a professional technical translator. Translate every numbered \
+             segment, keep each `@@@ n @@@` marker line and every placeholder such as \
+             ⟦0⟧ exactly as given, and do not add commentary.",
         ),
         Message::user(prompt),
     ]));
@@ -188,15 +468,82 @@ async fn request_translation(model: OpenAI, prompt: &str) -> eyre::Result<String
     Ok(output)
 }
 
-fn build_prompt(article: &Article, target: &str) -> String {
-    format!(
-        "Translate the following Markdown from language `{src}` into `{target}`. \
-         Keep headings, links, and formatting intact. Only return translated Markdown, \
-         no explanations.\n\n{body}",
+/// Replace each word of `text` listed in `dictionary` (exactly, or in
+/// lowercase).
+fn translate_with_dictionary(text: &str, dictionary: &BTreeMap<String, String>) -> String {
+    static WORD: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"[\p{L}\p{N}_'-]+").expect("word regex should compile"));
+    WORD.replace_all(text, |caps: &regex::Captures<'_>| {
+        let word = &caps[0];
+        dictionary
+            .get(word)
+            .or_else(|| dictionary.get(&word.to_lowercase()))
+            .map_or_else(|| word.to_string(), Clone::clone)
+    })
+    .into_owned()
+}
+
+static SEGMENT_MARKER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^[ \t]*@@@[ \t]*(\d+)[ \t]*@@@[ \t]*\r?$")
+        .expect("segment marker regex should compile")
+});
+
+fn build_prompt(article: &Article, target: &str, segments: &[&str], glossary: &Glossary) -> String {
+    let mut prompt = format!(
+        "Translate each numbered Markdown segment below from language `{src}` into `{target}`. \
+         Answer with the same `@@@ n @@@` marker lines, each followed by its translation. \
+         Keep inline formatting and every placeholder such as ⟦0⟧ unchanged, exactly once. \
+         No explanations.\n",
         src = article.default_locale(),
-        target = target,
-        body = article.content()
-    )
+    );
+    let mut terms = Vec::new();
+    for (term, rendering) in segments
+        .iter()
+        .flat_map(|segment| glossary.terms_in(segment, target))
+    {
+        if !terms.contains(&(term, rendering)) {
+            terms.push((term, rendering));
+        }
+    }
+    if !terms.is_empty() {
+        prompt.push_str("\nAlways use these renderings:\n");
+        for (term, rendering) in terms {
+            prompt.push_str(&format!("- {term} → {rendering}\n"));
+        }
+    }
+    for (index, text) in segments.iter().enumerate() {
+        prompt.push_str(&format!("\n@@@ {} @@@\n{text}\n", index + 1));
+    }
+    prompt
+}
+
+/// Split a model answer into the `expected` segments requested by
+/// [`build_prompt`].
+fn split_segments(output: &str, expected: usize) -> eyre::Result<Vec<String>> {
+    let markers = SEGMENT_MARKER.captures_iter(output).collect::<Vec<_>>();
+    if markers.len() != expected {
+        return Err(SegmentError::SegmentCount {
+            expected,
+            found: markers.len(),
+        }
+        .into());
+    }
+    let mut segments = Vec::with_capacity(expected);
+    for (index, captures) in markers.iter().enumerate() {
+        if captures[1].parse::<usize>().ok() != Some(index + 1) {
+            return Err(eyre!(
+                "expected segment marker {} but found `{}`",
+                index + 1,
+                &captures[0].trim()
+            ));
+        }
+        let start = captures.get(0).expect("whole match").end();
+        let end = markers.get(index + 1).map_or(output.len(), |next| {
+            next.get(0).expect("whole match").start()
+        });
+        segments.push(output[start..end].trim().to_string());
+    }
+    Ok(segments)
 }
 
 async fn write_file(path: &Path, contents: &str) -> eyre::Result<()> {
@@ -225,10 +572,7 @@ fn parse_language_code(language: Option<String>) -> eyre::Result<String> {
     if trimmed.is_empty() {
         return Err(missing_language_error());
     }
-    if !is_valid_language_code(trimmed) {
-        return Err(invalid_language_error(trimmed));
-    }
-    Ok(trimmed.to_string())
+    locale::canonicalize(trimmed).ok_or_else(|| invalid_language_error(trimmed))
 }
 
 fn missing_language_error() -> eyre::Report {
@@ -240,7 +584,7 @@ fn missing_language_error() -> eyre::Report {
 
 fn invalid_language_error(code: &str) -> eyre::Report {
     eyre!(
-        "Invalid language code `{code}`. Use a BCP-47 style code with letters and optional region (e.g. en-US).\nExamples: {}",
+        "Invalid language code `{code}`. Use a BCP-47 language tag such as en-US or zh-Hant-TW.\nExamples: {}",
         format_language_examples()
     )
 }
@@ -266,11 +610,3 @@ fn prompt_language_selection() -> eyre::Result<String> {
         .wrap_err("Failed to read language selection")?;
     Ok(LANGUAGE_EXAMPLES[choice].1.to_string())
 }
-
-fn is_valid_language_code(code: &str) -> bool {
-    static LANGUAGE_CODE_RE: LazyLock<Regex> = LazyLock::new(|| {
-        Regex::new(r"^[A-Za-z]{2,8}(?:-[A-Za-z0-9]{2,8})*$")
-            .expect("language code regex should compile")
-    });
-    LANGUAGE_CODE_RE.is_match(code)
-}
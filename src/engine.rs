@@ -1,14 +1,36 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 
-use color_eyre::eyre;
+use color_eyre::eyre::{self, eyre};
 use futures::TryStreamExt;
 use sha2::{Digest, Sha256};
 use tokio::{fs as async_fs, spawn, task::JoinHandle};
 
 use crate::{
-    cache::RenderCache, plugin::PluginManager, search, utils::write, workspace::Workspace,
+    article::Article,
+    bundle::Bundle,
+    cache::RenderCache,
+    feed,
+    link::{self, LinkResolver},
+    locale::LocaleIndex,
+    order,
+    plugin::PluginManager,
+    redirect::RedirectTable,
+    related::RelatedArticles,
+    search::{self, Searcher},
+    series::SeriesIndex,
+    timeline::Timeline,
+    utils::write,
+    workspace::Workspace,
 };
 
+/// Options for [`Engine::generate`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GenerateOptions {
+    /// Also render drafts and future-dated articles at their URLs. They stay
+    /// out of listings, feeds and search either way.
+    pub drafts: bool,
+}
+
 pub struct Engine {
     workspace: Workspace,
     plugins: Arc<PluginManager>,
@@ -23,7 +45,11 @@ impl Engine {
         })
     }
 
-    pub async fn generate(&self, output: impl AsRef<Path>) -> eyre::Result<()> {
+    pub async fn generate(
+        &self,
+        output: impl AsRef<Path>,
+        options: GenerateOptions,
+    ) -> eyre::Result<()> {
         let output = output.as_ref();
         if async_fs::metadata(output).await.is_ok() {
             async_fs::remove_dir_all(output).await?;
@@ -37,21 +63,104 @@ impl Engine {
 
         let stream = self.workspace.articles();
         futures::pin_mut!(stream);
+        let mut articles = Vec::new();
+        while let Some(article) = stream.try_next().await? {
+            if options.drafts || article.is_published() {
+                articles.push(article);
+            }
+        }
+
+        // With a custom permalink pattern, two articles may map to the same file.
+        let mut outputs = HashMap::new();
+        for article in &articles {
+            let source = article.segments().join("/");
+            if let Some(other) = outputs.insert(article.output_file(), source.clone())
+                && other != source
+            {
+                return Err(eyre!(
+                    "articles `{other}` and `{source}` both map to `{}`; \
+                     set a distinct `slug` or adjust `permalink` in Thought.toml",
+                    article.output_file()
+                ));
+            }
+        }
+
+        // Files next to an article are published beside its output file.
+        let mut bundles = HashMap::new();
+        for article in articles
+            .iter()
+            .filter(|article| article.is_default_locale())
+        {
+            let source = article.segments().join("/");
+            let bundle = Bundle::of(article.preview()).await?;
+            for file in bundle.outputs() {
+                if let Some(other) = outputs.insert(file.clone(), source.clone()) {
+                    return Err(eyre!(
+                        "a file next to `{source}` would be published at `{file}`, \
+                         which `{other}` already uses"
+                    ));
+                }
+            }
+            bundles.insert(source, bundle);
+        }
+
+        let redirects = RedirectTable::new(articles.iter().map(Article::preview))?;
+        let series = SeriesIndex::new(articles.iter().map(Article::preview))?;
+        let timeline = Timeline::new(articles.iter().map(Article::preview))?;
+        let locales = LocaleIndex::new(articles.iter().map(Article::preview))?;
+        redirects.check_listings(&locales, &series, &timeline)?;
+
+        // Cross-references need the full set of output paths before anything renders.
+        let resolver = LinkResolver::new(articles.iter().map(Article::preview));
+        let mut unresolved = Vec::new();
+        for article in &mut articles {
+            unresolved.extend(resolver.rewrite(article));
+            if let Some(bundle) = bundles.get(&article.segments().join("/")) {
+                bundle.rewrite(article);
+            }
+            series.attach(article);
+        }
+        link::enforce(
+            self.workspace.manifest().link_config().unresolved,
+            unresolved,
+        )?;
+
+        let mut fingerprint = Sha256::new();
+        for article in &articles {
+            fingerprint.update(article.sha256().as_bytes());
+        }
+        let fingerprint = format!("{:x}", fingerprint.finalize());
+
+        // Related articles are ranked with the search index, so build it first.
+        let related_count = self.workspace.manifest().related_config().count;
+        if related_count > 0 {
+            let searcher = Searcher::open(self.workspace.clone()).await?;
+            searcher.ensure_index(Some(&fingerprint)).await?;
+            let related =
+                RelatedArticles::new(articles.iter().map(Article::preview), related_count);
+            for article in &mut articles {
+                related.attach(&searcher, article)?;
+            }
+        }
+
+        // The feed and sitemap list every locale, not just the index previews.
+        let every_locale = articles
+            .iter()
+            .map(|article| article.preview().clone())
+            .collect::<Vec<_>>();
 
         let mut tasks: Vec<JoinHandle<eyre::Result<()>>> = Vec::new();
 
         let mut previews = Vec::new();
-        let mut fingerprint = Sha256::new();
         let theme_fp = self.plugins.theme_fingerprint().to_string();
 
-        while let Some(article) = stream.try_next().await? {
+        for article in articles {
             let plugins = self.plugins.clone();
             let cache = cache.clone();
             let theme_fp = theme_fp.clone();
-            if article.is_default_locale() {
+            if article.is_default_locale() && article.is_published() {
                 previews.push(article.preview().clone());
             }
-            fingerprint.update(article.sha256().as_bytes());
             let article_output = output.join(article.output_file());
 
             tasks.push(spawn(async move {
@@ -69,21 +178,60 @@ impl Engine {
             }));
         }
 
+        order::sort_previews(&mut previews);
         let plugins = self.plugins.clone();
         let index_file_path = output.join("index.html");
         tasks.push(spawn(async move {
-            let index_html = plugins.render_index(previews)?;
+            let index_html = plugins.render_index(previews, None)?;
             write(index_file_path, index_html.as_bytes()).await?;
             Ok(())
         }));
 
+        for (locale, output_file, listing) in locales.pages() {
+            let plugins = self.plugins.clone();
+            let locale = locale.to_string();
+            let listing = listing.to_vec();
+            let index_path = output.join(output_file);
+            tasks.push(spawn(async move {
+                let index_html = plugins.render_index(listing, Some(&locale))?;
+                write(index_path, index_html.as_bytes()).await?;
+                Ok(())
+            }));
+        }
+
+        for series in series.iter().cloned() {
+            let plugins = self.plugins.clone();
+            let series_path = output.join(series.output_file());
+            tasks.push(spawn(async move {
+                let series_html = plugins.render_series(series)?;
+                write(series_path, series_html.as_bytes()).await?;
+                Ok(())
+            }));
+        }
+
+        for page in timeline.pages().iter().cloned() {
+            let plugins = self.plugins.clone();
+            let page_path = output.join(page.output_file());
+            tasks.push(spawn(async move {
+                let page_html = plugins.render_archive(page)?;
+                write(page_path, page_html.as_bytes()).await?;
+                Ok(())
+            }));
+        }
+
         // Wait for all tasks to complete
         for task in tasks {
             task.await??;
         }
 
-        // No persist needed - writes are incremental
-        let fingerprint = format!("{:x}", fingerprint.finalize());
+        for bundle in bundles.values() {
+            bundle.emit(output).await?;
+        }
+        redirects
+            .emit(output, self.workspace.manifest().redirect_config())
+            .await?;
+        feed::emit(self.workspace.manifest(), output, &every_locale).await?;
+
         search::emit_search_bundle(&self.workspace, output, Some(&fingerprint)).await?;
 
         Ok(())
@@ -0,0 +1,203 @@
+//! Atom feed and sitemap.
+//!
+//! When `base_url` is set in `Thought.toml`, `thought generate` writes
+//! `atom.xml` with the latest articles and `sitemap.xml` listing every article
+//! in every locale. Both report an article's `updated` date when it has one,
+//! falling back to `created`. Drafts are left out of both. Feed entries carry
+//! their locale as `xml:lang`, and sitemap URLs of translated articles list
+//! every variant as an `hreflang` alternate.
+
+use std::{fmt::Write as _, path::Path};
+
+use color_eyre::eyre;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+use crate::{article::ArticlePreview, metadata::WorkspaceManifest, utils::write};
+
+/// Number of articles listed in `atom.xml`.
+const FEED_ENTRIES: usize = 20;
+
+/// Write `atom.xml` and `sitemap.xml` into `output`, if a base URL is configured.
+///
+/// # Errors
+/// Returns an error if a file cannot be written.
+pub async fn emit(
+    manifest: &WorkspaceManifest,
+    output: &Path,
+    articles: &[ArticlePreview],
+) -> eyre::Result<()> {
+    let Some(base_url) = manifest.base_url() else {
+        return Ok(());
+    };
+    let base_url = base_url.trim_end_matches('/');
+    let published = articles
+        .iter()
+        .filter(|article| article.is_published())
+        .collect::<Vec<_>>();
+
+    write(
+        output.join("atom.xml"),
+        atom(manifest, base_url, &published).as_bytes(),
+    )
+    .await?;
+    write(
+        output.join("sitemap.xml"),
+        sitemap(base_url, &published).as_bytes(),
+    )
+    .await?;
+    Ok(())
+}
+
+fn atom(manifest: &WorkspaceManifest, base_url: &str, articles: &[&ArticlePreview]) -> String {
+    let mut entries = articles
+        .iter()
+        .filter(|article| article.is_default_locale())
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|article| std::cmp::Reverse(article.metadata().created()));
+    entries.truncate(FEED_ENTRIES);
+
+    let updated = entries
+        .iter()
+        .map(|article| last_modified(article))
+        .max()
+        .unwrap_or_else(OffsetDateTime::now_utc);
+
+    let mut feed = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    feed.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    let _ = writeln!(feed, "  <title>{}</title>", escape(manifest.name()));
+    if !manifest.description().is_empty() {
+        let _ = writeln!(
+            feed,
+            "  <subtitle>{}</subtitle>",
+            escape(manifest.description())
+        );
+    }
+    let _ = writeln!(feed, "  <link href=\"{}/\"/>", escape(base_url));
+    let _ = writeln!(
+        feed,
+        "  <link rel=\"self\" href=\"{}/atom.xml\"/>",
+        escape(base_url)
+    );
+    let _ = writeln!(feed, "  <id>{}/</id>", escape(base_url));
+    let _ = writeln!(feed, "  <updated>{}</updated>", timestamp(updated));
+    let _ = writeln!(
+        feed,
+        "  <author><name>{}</name></author>",
+        escape(manifest.owner())
+    );
+
+    for article in entries {
+        let url = escape(&page_url(base_url, &article.output_file()));
+        let metadata = article.metadata();
+        let _ = writeln!(feed, "  <entry xml:lang=\"{}\">", escape(article.locale()));
+        let _ = writeln!(feed, "    <title>{}</title>", escape(article.title()));
+        let _ = writeln!(feed, "    <link href=\"{url}\"/>");
+        let _ = writeln!(feed, "    <id>{url}</id>");
+        let _ = writeln!(
+            feed,
+            "    <published>{}</published>",
+            timestamp(metadata.created())
+        );
+        let _ = writeln!(
+            feed,
+            "    <updated>{}</updated>",
+            timestamp(last_modified(article))
+        );
+        let _ = writeln!(
+            feed,
+            "    <author><name>{}</name></author>",
+            escape(metadata.author())
+        );
+        if !article.description().is_empty() {
+            let _ = writeln!(
+                feed,
+                "    <summary>{}</summary>",
+                escape(article.description())
+            );
+        }
+        for tag in metadata.tags() {
+            let _ = writeln!(feed, "    <category term=\"{}\"/>", escape(tag));
+        }
+        feed.push_str("  </entry>\n");
+    }
+    feed.push_str("</feed>\n");
+    feed
+}
+
+fn sitemap(base_url: &str, articles: &[&ArticlePreview]) -> String {
+    let mut pages = articles
+        .iter()
+        .map(|article| {
+            let alternates = if article.translations().len() > 1 {
+                article
+                    .translations()
+                    .iter()
+                    .map(|translation| {
+                        (
+                            translation.locale().to_string(),
+                            page_url(base_url, translation.output_file()),
+                        )
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            (
+                page_url(base_url, &article.output_file()),
+                Some(last_modified(article)),
+                alternates,
+            )
+        })
+        .collect::<Vec<_>>();
+    pages.sort_by(|a, b| a.0.cmp(&b.0));
+    pages.insert(0, (format!("{base_url}/"), None, Vec::new()));
+
+    let mut sitemap = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    sitemap.push_str(
+        "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\" \
+         xmlns:xhtml=\"http://www.w3.org/1999/xhtml\">\n",
+    );
+    for (url, modified, alternates) in pages {
+        let _ = write!(sitemap, "  <url><loc>{}</loc>", escape(&url));
+        if let Some(modified) = modified {
+            let _ = write!(sitemap, "<lastmod>{}</lastmod>", timestamp(modified));
+        }
+        for (locale, href) in alternates {
+            let _ = write!(
+                sitemap,
+                "<xhtml:link rel=\"alternate\" hreflang=\"{}\" href=\"{}\"/>",
+                escape(&locale),
+                escape(&href)
+            );
+        }
+        sitemap.push_str("</url>\n");
+    }
+    sitemap.push_str("</urlset>\n");
+    sitemap
+}
+
+fn last_modified(article: &ArticlePreview) -> OffsetDateTime {
+    let metadata = article.metadata();
+    metadata.updated().unwrap_or_else(|| metadata.created())
+}
+
+/// Public URL of an output file; `…/index.html` is addressed as its directory.
+fn page_url(base_url: &str, output_file: &str) -> String {
+    let path = output_file
+        .strip_suffix("index.html")
+        .unwrap_or(output_file);
+    format!("{base_url}/{path}")
+}
+
+fn timestamp(datetime: OffsetDateTime) -> String {
+    datetime.format(&Rfc3339).unwrap_or_default()
+}
+
+/// Escape `value` for XML text and attribute values.
+pub(crate) fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
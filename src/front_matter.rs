@@ -0,0 +1,592 @@
+//! Front matter blocks at the top of article Markdown files.
+//!
+//! Both TOML (`+++`) and YAML (`---`) blocks are accepted and map onto
+//! [`ArticleMetadata`] fields, so an article can be written without a sibling
+//! `Article.toml`, or override individual fields of it:
+//!
+//! ```markdown
+//! ---
+//! title: Hello
+//! date: 2024-05-01 09:30:00 +0800
+//! tags: [rust, notes]
+//! ---
+//!
+//! Body starts here.
+//! ```
+
+use serde::Deserialize;
+use time::{
+    Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset,
+    format_description::well_known::Rfc3339, macros::format_description,
+};
+
+use crate::metadata::{ArticleMetadata, SeriesMembership};
+
+/// Fields recognised in an article's front matter. Unknown keys are ignored so
+/// posts carried over from other generators keep working.
+#[derive(Debug, Clone, Default)]
+pub struct FrontMatter {
+    /// Overrides the title taken from the first heading.
+    pub title: Option<String>,
+    /// Publication date (`created` or `date`).
+    pub created: Option<OffsetDateTime>,
+    /// Last revision (`updated`, `lastmod` or `last_modified_at`).
+    pub updated: Option<OffsetDateTime>,
+    /// Tags (`tags`); a single string is accepted as one tag.
+    pub tags: Option<Vec<String>>,
+    /// Author of the article.
+    pub author: Option<String>,
+    /// Description; also used as the preview description.
+    pub description: Option<String>,
+    /// Language of the content (`lang`, `language` or `locale`).
+    pub lang: Option<String>,
+    /// Slug used in the article's URL.
+    pub slug: Option<String>,
+    /// Former URLs that should redirect to the article.
+    pub aliases: Option<Vec<String>>,
+    /// Whether the article is a draft (`draft`, or Jekyll's `published: false`).
+    pub draft: Option<bool>,
+    /// Series the article is part of (`series = { name, part }`).
+    pub series: Option<SeriesMembership>,
+    /// Whether the article is pinned to the top of listings (`pinned` or `sticky`).
+    pub pinned: Option<bool>,
+    /// Position among weighted articles; lower comes first.
+    pub weight: Option<i32>,
+    /// Categories as written by other generators (`categories` or `category`).
+    /// Thought places articles by directory, so this is only read by importers.
+    pub categories: Option<Vec<String>>,
+    /// On a translation, the `sha256` of the source body it was produced from.
+    pub source_hash: Option<String>,
+}
+
+/// Errors raised while reading a front matter block.
+#[derive(Debug, thiserror::Error)]
+pub enum FrontMatterError {
+    /// The TOML block could not be parsed.
+    #[error("invalid TOML front matter: {0}")]
+    Toml(#[from] toml::de::Error),
+    /// The YAML block could not be parsed.
+    #[error("invalid YAML front matter: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    /// The TOML block could not be written back.
+    #[error("failed to write TOML front matter: {0}")]
+    TomlWrite(#[from] toml::ser::Error),
+    /// A `+++` block was opened but never closed.
+    #[error("front matter block is not terminated")]
+    Unterminated,
+    /// The date field is not in a recognised format.
+    #[error("invalid date `{0}` in front matter")]
+    InvalidDate(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawDate {
+    Toml(toml::value::Datetime),
+    Text(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Self::One(value) => vec![value],
+            Self::Many(values) => values,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawFrontMatter {
+    title: Option<String>,
+    #[serde(alias = "date")]
+    created: Option<RawDate>,
+    #[serde(alias = "lastmod", alias = "last_modified_at")]
+    updated: Option<RawDate>,
+    tags: Option<OneOrMany>,
+    author: Option<String>,
+    description: Option<String>,
+    #[serde(alias = "language", alias = "locale")]
+    lang: Option<String>,
+    slug: Option<String>,
+    aliases: Option<OneOrMany>,
+    draft: Option<bool>,
+    published: Option<bool>,
+    series: Option<SeriesMembership>,
+    #[serde(alias = "sticky")]
+    pinned: Option<bool>,
+    weight: Option<i32>,
+    #[serde(alias = "category")]
+    categories: Option<OneOrMany>,
+    taxonomies: Option<Taxonomies>,
+    source_hash: Option<String>,
+}
+
+/// Zola keeps tags and categories under `[taxonomies]`.
+#[derive(Debug, Default, Deserialize)]
+struct Taxonomies {
+    tags: Option<OneOrMany>,
+    categories: Option<OneOrMany>,
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    const fn delimiter(self) -> &'static str {
+        match self {
+            Self::Toml => "+++",
+            Self::Yaml => "---",
+        }
+    }
+}
+
+/// Whether `input` opens with a front matter delimiter.
+#[must_use]
+pub fn has_front_matter(input: &str) -> bool {
+    opening(input).is_some()
+}
+
+/// Split `input` into its front matter and the Markdown body that follows it.
+///
+/// Inputs without a front matter block are returned unchanged. Blank lines
+/// between the block and the body are dropped, so moving fields from
+/// `Article.toml` into front matter leaves the body byte-identical.
+///
+/// # Errors
+/// Returns [`FrontMatterError`] if the block is malformed.
+pub fn split(input: &str) -> Result<(Option<FrontMatter>, &str), FrontMatterError> {
+    let Some(Block {
+        format,
+        content: block,
+        body_start,
+        ..
+    }) = locate(input)?
+    else {
+        return Ok((None, input));
+    };
+
+    let raw: RawFrontMatter = if block.trim().is_empty() {
+        RawFrontMatter::default()
+    } else {
+        match format {
+            Format::Toml => toml::from_str(block)?,
+            Format::Yaml => serde_yaml::from_str(block)?,
+        }
+    };
+
+    let date = |raw: Option<RawDate>| match raw {
+        Some(RawDate::Toml(datetime)) => parse_date(&datetime.to_string()).map(Some),
+        Some(RawDate::Text(text)) => parse_date(&text).map(Some),
+        None => Ok(None),
+    };
+    let created = date(raw.created)?;
+    let updated = date(raw.updated)?;
+    let taxonomies = raw.taxonomies.unwrap_or_default();
+    let front_matter = FrontMatter {
+        title: raw.title,
+        created,
+        updated,
+        tags: raw.tags.or(taxonomies.tags).map(OneOrMany::into_vec),
+        author: raw.author,
+        description: raw.description,
+        lang: raw.lang,
+        slug: raw.slug,
+        aliases: raw.aliases.map(OneOrMany::into_vec),
+        draft: raw.draft.or(raw.published.map(|published| !published)),
+        series: raw.series,
+        pinned: raw.pinned,
+        weight: raw.weight,
+        categories: raw
+            .categories
+            .or(taxonomies.categories)
+            .map(OneOrMany::into_vec),
+        source_hash: raw.source_hash,
+    };
+
+    let body = input[body_start..].trim_start_matches(['\n', '\r']);
+    Ok((Some(front_matter), body))
+}
+
+/// The Markdown body of `input`, ignoring a malformed front matter block.
+#[must_use]
+pub fn strip(input: &str) -> &str {
+    split(input).map_or(input, |(_, body)| body)
+}
+
+impl FrontMatter {
+    /// Override the fields of `metadata` that this block sets.
+    pub fn apply(&self, metadata: &mut ArticleMetadata) {
+        if let Some(created) = self.created {
+            metadata.set_created(created);
+        }
+        if let Some(updated) = self.updated {
+            metadata.set_updated(updated);
+        }
+        if let Some(tags) = &self.tags {
+            metadata.tags.clone_from(tags);
+        }
+        if let Some(author) = &self.author {
+            metadata.set_author(author.clone());
+        }
+        if let Some(description) = &self.description {
+            metadata.set_description(description.clone());
+        }
+        if let Some(lang) = &self.lang {
+            metadata.set_lang(lang.clone());
+        }
+        if let Some(slug) = &self.slug {
+            metadata.set_slug(slug.clone());
+        }
+        if let Some(aliases) = &self.aliases {
+            metadata.aliases.clone_from(aliases);
+        }
+        if let Some(draft) = self.draft {
+            metadata.set_draft(draft);
+        }
+        if let Some(series) = &self.series {
+            metadata.set_series(series.clone());
+        }
+        if let Some(pinned) = self.pinned {
+            metadata.set_pinned(pinned);
+        }
+        if let Some(weight) = self.weight {
+            metadata.set_weight(weight);
+        }
+    }
+}
+
+/// Replace the `aliases` list in `input`'s front matter with the result of
+/// `update`, adding a YAML block when the file has none.
+///
+/// The block is re-serialized, so comments and key order inside it are not
+/// preserved.
+///
+/// # Errors
+/// Returns [`FrontMatterError`] if the existing block is malformed.
+pub fn update_aliases(
+    input: &str,
+    update: impl FnOnce(&mut Vec<String>),
+) -> Result<String, FrontMatterError> {
+    let Some(block) = locate(input)? else {
+        let mut aliases = Vec::new();
+        update(&mut aliases);
+        if aliases.is_empty() {
+            return Ok(input.to_string());
+        }
+        let mut mapping = serde_yaml::Mapping::new();
+        mapping.insert("aliases".into(), aliases.into());
+        return Ok(format!(
+            "---\n{}---\n\n{input}",
+            serde_yaml::to_string(&mapping)?
+        ));
+    };
+
+    let serialized = match block.format {
+        Format::Toml => {
+            let mut table: toml::Table = toml::from_str(block.content)?;
+            let mut aliases = match table.remove("aliases") {
+                Some(toml::Value::String(alias)) => vec![alias],
+                Some(toml::Value::Array(values)) => values
+                    .into_iter()
+                    .filter_map(|value| value.as_str().map(str::to_string))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            update(&mut aliases);
+            if !aliases.is_empty() {
+                table.insert("aliases".to_string(), aliases.into());
+            }
+            toml::to_string(&table)?
+        }
+        Format::Yaml => {
+            let mut mapping: serde_yaml::Mapping = if block.content.trim().is_empty() {
+                serde_yaml::Mapping::new()
+            } else {
+                serde_yaml::from_str(block.content)?
+            };
+            let mut aliases = match mapping.remove("aliases") {
+                Some(value) => serde_yaml::from_value::<OneOrMany>(value)?.into_vec(),
+                None => Vec::new(),
+            };
+            update(&mut aliases);
+            if !aliases.is_empty() {
+                mapping.insert("aliases".into(), aliases.into());
+            }
+            if mapping.is_empty() {
+                String::new()
+            } else {
+                serde_yaml::to_string(&mapping)?
+            }
+        }
+    };
+
+    Ok(format!(
+        "{}{serialized}{}\n{}",
+        &input[..block.content_start],
+        block.format.delimiter(),
+        &input[block.body_start..]
+    ))
+}
+
+struct Block<'a> {
+    format: Format,
+    content: &'a str,
+    content_start: usize,
+    body_start: usize,
+}
+
+/// Find the front matter block at the top of `input`, if any.
+fn locate(input: &str) -> Result<Option<Block<'_>>, FrontMatterError> {
+    let Some((format, content_start)) = opening(input) else {
+        return Ok(None);
+    };
+
+    let mut offset = content_start;
+    for line in input[content_start..].split_inclusive('\n') {
+        if line.trim_end() == format.delimiter() {
+            return Ok(Some(Block {
+                format,
+                content: &input[content_start..offset],
+                content_start,
+                body_start: offset + line.len(),
+            }));
+        }
+        offset += line.len();
+    }
+    match format {
+        Format::Toml => Err(FrontMatterError::Unterminated),
+        // A lone `---` is a thematic break, not front matter.
+        Format::Yaml => Ok(None),
+    }
+}
+
+fn opening(input: &str) -> Option<(Format, usize)> {
+    let input_start = input.len() - input.trim_start_matches('\u{feff}').len();
+    let first = input[input_start..].split_inclusive('\n').next()?;
+    if !first.ends_with('\n') {
+        return None;
+    }
+    let format = match first.trim_end() {
+        "+++" => Format::Toml,
+        "---" => Format::Yaml,
+        _ => return None,
+    };
+    Some((format, input_start + first.len()))
+}
+
+/// Parse the date formats commonly found in front matter: RFC 3339, a bare
+/// date, or a date and time separated by a space with an optional offset
+/// (`2024-05-01 09:30:00 +0800`). Dates without an offset are taken as UTC.
+///
+/// # Errors
+/// Returns [`FrontMatterError::InvalidDate`] if `input` matches none of them.
+pub fn parse_date(input: &str) -> Result<OffsetDateTime, FrontMatterError> {
+    let text = input.trim();
+    let invalid = || FrontMatterError::InvalidDate(text.to_string());
+
+    if let Ok(datetime) = OffsetDateTime::parse(text, &Rfc3339) {
+        return Ok(datetime);
+    }
+
+    let (date_part, rest) = text
+        .split_once(['T', ' '])
+        .map_or((text, ""), |(date, rest)| (date, rest.trim()));
+    let date = Date::parse(date_part, format_description!("[year]-[month]-[day]"))
+        .map_err(|_| invalid())?;
+    if rest.is_empty() {
+        return Ok(date.midnight().assume_utc());
+    }
+
+    let split_at = rest.find(['Z', 'z', '+', '-', ' ']).unwrap_or(rest.len());
+    let (time_part, offset_part) = rest.split_at(split_at);
+    let time = Time::parse(time_part, format_description!("[hour]:[minute]:[second]"))
+        .or_else(|_| {
+            Time::parse(
+                time_part,
+                format_description!("[hour]:[minute]:[second].[subsecond]"),
+            )
+        })
+        .or_else(|_| Time::parse(time_part, format_description!("[hour]:[minute]")))
+        .map_err(|_| invalid())?;
+    let datetime = PrimitiveDateTime::new(date, time);
+
+    let offset_part = offset_part.trim();
+    let offset = match offset_part {
+        "" | "Z" | "z" | "UTC" => UtcOffset::UTC,
+        _ => UtcOffset::parse(
+            offset_part,
+            format_description!("[offset_hour sign:mandatory]:[offset_minute]"),
+        )
+        .or_else(|_| {
+            UtcOffset::parse(
+                offset_part,
+                format_description!("[offset_hour sign:mandatory][offset_minute]"),
+            )
+        })
+        .map_err(|_| invalid())?,
+    };
+    Ok(datetime.assume_offset(offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn parses_rfc3339_dates() {
+        assert_eq!(
+            parse_date("2024-05-01T09:30:00+08:00").unwrap(),
+            datetime!(2024-05-01 09:30:00 +8)
+        );
+        assert_eq!(
+            parse_date("2024-05-01T09:30:00.250Z").unwrap(),
+            datetime!(2024-05-01 09:30:00.25 UTC)
+        );
+    }
+
+    #[test]
+    fn takes_bare_dates_as_utc_midnight() {
+        assert_eq!(
+            parse_date(" 2024-05-01 ").unwrap(),
+            datetime!(2024-05-01 00:00:00 UTC)
+        );
+    }
+
+    #[test]
+    fn parses_dates_with_a_space_separated_time_and_offset() {
+        assert_eq!(
+            parse_date("2024-05-01 09:30:00 +0800").unwrap(),
+            datetime!(2024-05-01 09:30:00 +8)
+        );
+        assert_eq!(
+            parse_date("2024-05-01 09:30 -05:00").unwrap(),
+            datetime!(2024-05-01 09:30:00 -5)
+        );
+        assert_eq!(
+            parse_date("2024-05-01 09:30:00").unwrap(),
+            datetime!(2024-05-01 09:30:00 UTC)
+        );
+        assert_eq!(
+            parse_date("2024-05-01T09:30:00 UTC").unwrap(),
+            datetime!(2024-05-01 09:30:00 UTC)
+        );
+    }
+
+    #[test]
+    fn rejects_unrecognised_dates() {
+        for input in ["May 1st, 2024", "2024-05-01 9am", "2024-05-01 09:30 +8h"] {
+            assert!(
+                matches!(parse_date(input), Err(FrontMatterError::InvalidDate(_))),
+                "{input}"
+            );
+        }
+        assert!(matches!(
+            split("---\ndate: yesterday\n---\n"),
+            Err(FrontMatterError::InvalidDate(_))
+        ));
+    }
+
+    #[test]
+    fn splits_toml_and_yaml_blocks() {
+        let (front_matter, body) =
+            split("+++\ntitle = \"Hello\"\ndate = 2024-05-01\ntags = \"rust\"\n+++\n\nBody\n")
+                .unwrap();
+        let front_matter = front_matter.unwrap();
+        assert_eq!(front_matter.title.as_deref(), Some("Hello"));
+        assert_eq!(
+            front_matter.created,
+            Some(datetime!(2024-05-01 00:00:00 UTC))
+        );
+        assert_eq!(front_matter.tags, Some(vec!["rust".to_string()]));
+        assert_eq!(body, "Body\n");
+
+        let (front_matter, body) = split(
+            "---\npublished: false\nlastmod: 2024-05-02 10:00:00 +0800\n\
+             taxonomies:\n  tags: [a, b]\n---\nBody\n",
+        )
+        .unwrap();
+        let front_matter = front_matter.unwrap();
+        assert_eq!(front_matter.draft, Some(true));
+        assert_eq!(
+            front_matter.updated,
+            Some(datetime!(2024-05-02 10:00:00 +8))
+        );
+        assert_eq!(
+            front_matter.tags,
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(body, "Body\n");
+    }
+
+    #[test]
+    fn splits_blocks_after_a_bom_and_with_crlf_line_endings() {
+        let input = "\u{feff}---\r\ntitle: Hello\r\n---\r\n\r\nBody\r\n";
+        assert!(has_front_matter(input));
+        let (front_matter, body) = split(input).unwrap();
+        assert_eq!(front_matter.unwrap().title.as_deref(), Some("Hello"));
+        assert_eq!(body, "Body\r\n");
+    }
+
+    #[test]
+    fn leaves_input_without_a_block_unchanged() {
+        for input in [
+            "# Title\n\nBody\n",
+            "---",
+            "---\nA thematic break, not a block\n",
+        ] {
+            let (front_matter, body) = split(input).unwrap();
+            assert!(front_matter.is_none(), "{input}");
+            assert_eq!(body, input);
+        }
+    }
+
+    #[test]
+    fn rejects_an_unterminated_toml_block() {
+        assert!(matches!(
+            split("+++\ntitle = \"Hello\"\n\nBody\n"),
+            Err(FrontMatterError::Unterminated)
+        ));
+    }
+
+    #[test]
+    fn adds_a_yaml_block_for_new_aliases() {
+        let updated =
+            update_aliases("# Title\n", |aliases| aliases.push("/old/".to_string())).unwrap();
+        assert_eq!(updated, "---\naliases:\n- /old/\n---\n\n# Title\n");
+        assert_eq!(
+            update_aliases("# Title\n", |_| {}).unwrap(),
+            "# Title\n",
+            "no block is added without aliases"
+        );
+    }
+
+    #[test]
+    fn updates_aliases_in_an_existing_block() {
+        let input = "+++\ntitle = \"Hello\"\naliases = \"/a/\"\n+++\n\nBody\n";
+        let updated = update_aliases(input, |aliases| aliases.push("/b/".to_string())).unwrap();
+        let (front_matter, body) = split(&updated).unwrap();
+        let front_matter = front_matter.unwrap();
+        assert_eq!(front_matter.title.as_deref(), Some("Hello"));
+        assert_eq!(
+            front_matter.aliases,
+            Some(vec!["/a/".to_string(), "/b/".to_string()])
+        );
+        assert_eq!(body, "Body\n");
+
+        let input = "---\ntitle: Hello\naliases: [/a/]\n---\n\nBody\n";
+        let updated = update_aliases(input, Vec::clear).unwrap();
+        assert_eq!(updated, "---\ntitle: Hello\n---\n\nBody\n");
+    }
+}
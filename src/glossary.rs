@@ -0,0 +1,293 @@
+//! Workspace glossary for consistent translation of terms.
+//!
+//! `Glossary.toml` at the workspace root maps each term to its rendering per
+//! locale, or marks it as never translated:
+//!
+//! ```toml
+//! [Thought]
+//! translate = false
+//!
+//! ["pull request"]
+//! zh-CN = "拉取请求"
+//! ja = "プルリクエスト"
+//! ```
+//!
+//! Terms are matched case-insensitively, on word boundaries where the term
+//! starts or ends with an ASCII letter or digit. Renderings are looked up
+//! along the locale's fallback chain, so a `zh` rendering also serves `zh-TW`.
+
+use std::{collections::BTreeMap, ops::Range, path::Path};
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::locale;
+
+/// Errors that can occur while loading or applying a glossary.
+#[derive(Debug, thiserror::Error)]
+pub enum GlossaryError {
+    /// Failed to read `Glossary.toml`.
+    #[error("failed to read Glossary.toml: {0}")]
+    Io(#[from] std::io::Error),
+    /// Failed to parse `Glossary.toml`.
+    #[error("failed to parse Glossary.toml: {0}")]
+    Parse(#[from] toml::de::Error),
+    /// A translation does not use the glossary rendering of a term.
+    #[error("`{term}` must be rendered as `{rendering}`")]
+    Rendering { term: String, rendering: String },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawEntry {
+    #[serde(default = "default_translate")]
+    translate: bool,
+    #[serde(flatten)]
+    renderings: BTreeMap<String, String>,
+}
+
+const fn default_translate() -> bool {
+    true
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    term: String,
+    /// The term, case-insensitively, anchored at the start of the haystack.
+    pattern: Regex,
+    translate: bool,
+    /// Renderings keyed by canonical locale.
+    renderings: Vec<(String, String)>,
+}
+
+/// Terms with a fixed rendering per locale.
+#[derive(Debug, Clone, Default)]
+pub struct Glossary {
+    entries: Vec<Entry>,
+    /// Alternation of all terms, used to find where a term may start; `None`
+    /// when empty.
+    pattern: Option<Regex>,
+}
+
+impl Glossary {
+    /// Load the glossary at `path`; a missing file is an empty glossary.
+    ///
+    /// # Errors
+    /// Returns [`GlossaryError`] if the file cannot be read or parsed.
+    pub async fn load(path: impl AsRef<Path>) -> Result<Self, GlossaryError> {
+        match tokio::fs::read_to_string(path).await {
+            Ok(data) => Self::parse(&data),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Parse the contents of a `Glossary.toml`.
+    ///
+    /// # Errors
+    /// Returns [`GlossaryError::Parse`] for malformed TOML.
+    pub fn parse(data: &str) -> Result<Self, GlossaryError> {
+        let raw: BTreeMap<String, RawEntry> = toml::from_str(data)?;
+        let mut entries = raw
+            .into_iter()
+            .filter(|(term, _)| !term.trim().is_empty())
+            .map(|(term, raw)| {
+                let renderings = raw
+                    .renderings
+                    .into_iter()
+                    .filter_map(|(tag, rendering)| {
+                        let canonical = locale::canonicalize(&tag);
+                        if canonical.is_none() {
+                            tracing::warn!(
+                                "ignoring `{tag}` for glossary term `{term}`: not a BCP 47 language tag"
+                            );
+                        }
+                        canonical.map(|tag| (tag, rendering))
+                    })
+                    .collect();
+                let term = term.trim();
+                Entry {
+                    term: term.to_string(),
+                    pattern: Regex::new(&format!("(?i)^(?:{})", regex::escape(term)))
+                        .expect("an escaped term forms a valid regex"),
+                    translate: raw.translate,
+                    renderings,
+                }
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.term.len()));
+
+        let pattern = (!entries.is_empty()).then(|| {
+            let alternation = entries
+                .iter()
+                .map(|entry| regex::escape(&entry.term))
+                .collect::<Vec<_>>()
+                .join("|");
+            Regex::new(&format!("(?i){alternation}")).expect("escaped terms form a valid regex")
+        });
+        Ok(Self { entries, pattern })
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Terms occurring in `text` with their rendering in `locale`, without
+    /// duplicates. Terms with no rendering for `locale` are left out.
+    #[must_use]
+    pub fn terms_in<'a>(&'a self, text: &str, locale: &str) -> Vec<(&'a str, &'a str)> {
+        let mut terms = Vec::new();
+        for (entry, _) in self.matches(text) {
+            if let Some(rendering) = entry.rendering(locale)
+                && !terms.iter().any(|(term, _)| *term == entry.term)
+            {
+                terms.push((entry.term.as_str(), rendering));
+            }
+        }
+        terms
+    }
+
+    /// Check that `translation` of `source` into `locale` uses the rendering
+    /// of every glossary term found in `source`.
+    ///
+    /// # Errors
+    /// Returns [`GlossaryError::Rendering`] for the first term whose rendering
+    /// is missing.
+    pub fn verify(
+        &self,
+        source: &str,
+        translation: &str,
+        locale: &str,
+    ) -> Result<(), GlossaryError> {
+        let translation = translation.to_lowercase();
+        for (term, rendering) in self.terms_in(source, locale) {
+            if !translation.contains(&rendering.to_lowercase()) {
+                return Err(GlossaryError::Rendering {
+                    term: term.to_string(),
+                    rendering: rendering.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Render the glossary terms of `text` in `locale`, passing the text
+    /// between them through `translate`.
+    pub fn replace(
+        &self,
+        text: &str,
+        locale: &str,
+        mut translate: impl FnMut(&str) -> String,
+    ) -> String {
+        let mut output = String::with_capacity(text.len());
+        let mut last = 0;
+        for (entry, range) in self.matches(text) {
+            let Some(rendering) = entry.rendering(locale) else {
+                continue;
+            };
+            output.push_str(&translate(&text[last..range.start]));
+            // Keep the source spelling of a term that is never translated.
+            output.push_str(if entry.translate {
+                rendering
+            } else {
+                &text[range.clone()]
+            });
+            last = range.end;
+        }
+        output.push_str(&translate(&text[last..]));
+        output
+    }
+
+    /// Occurrences of terms in `text`, left to right. At each position the
+    /// longest term on word boundaries wins, so `pull request` inside
+    /// `pull requests` still leaves a shorter `pull` to be found.
+    fn matches<'a>(&'a self, text: &str) -> impl Iterator<Item = (&'a Entry, Range<usize>)> {
+        let mut position = 0;
+        std::iter::from_fn(move || {
+            let pattern = self.pattern.as_ref()?;
+            while let Some(candidate) = pattern.find_at(text, position) {
+                let start = candidate.start();
+                let found = self.entries.iter().find_map(|entry| {
+                    let end = start + entry.pattern.find(&text[start..])?.end();
+                    on_boundaries(text, start..end).then_some((entry, start..end))
+                });
+                if let Some((entry, range)) = found {
+                    position = range.end;
+                    return Some((entry, range));
+                }
+                position = start + text[start..].chars().next().map_or(1, char::len_utf8);
+            }
+            position = text.len();
+            None
+        })
+    }
+}
+
+impl Entry {
+    fn rendering(&self, locale: &str) -> Option<&str> {
+        if !self.translate {
+            return Some(&self.term);
+        }
+        locale::fallback_chain(locale, None)
+            .iter()
+            .find_map(|wanted| {
+                self.renderings
+                    .iter()
+                    .find(|(tag, _)| tag == wanted)
+                    .map(|(_, rendering)| rendering.as_str())
+            })
+    }
+}
+
+/// Whether `range` of `text` is not part of a longer word. Edges that are not ASCII
+/// letters or digits, such as those of scripts written without spaces, always
+/// qualify.
+fn on_boundaries(text: &str, range: Range<usize>) -> bool {
+    let is_word = |c: char| c.is_ascii_alphanumeric();
+    let inner_start = text[range.clone()].chars().next();
+    let inner_end = text[range.clone()].chars().next_back();
+    let before = text[..range.start].chars().next_back();
+    let after = text[range.end..].chars().next();
+    let joined_before = inner_start.is_some_and(is_word) && before.is_some_and(is_word);
+    let joined_after = inner_end.is_some_and(is_word) && after.is_some_and(is_word);
+    !joined_before && !joined_after
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLOSSARY: &str = r#"
+[Thought]
+translate = false
+
+[pull]
+es = "tirar"
+
+["pull request"]
+es = "solicitud de cambios"
+"#;
+
+    #[test]
+    fn tries_shorter_terms_where_a_longer_one_is_inside_a_word() {
+        let glossary = Glossary::parse(GLOSSARY).unwrap();
+        assert_eq!(
+            glossary.terms_in("Open pull requests", "es"),
+            [("pull", "tirar")]
+        );
+        assert_eq!(
+            glossary.terms_in("Open a pull request", "es"),
+            [("pull request", "solicitud de cambios")]
+        );
+    }
+
+    #[test]
+    fn skips_terms_that_are_part_of_a_word() {
+        let glossary = Glossary::parse(GLOSSARY).unwrap();
+        assert!(glossary.terms_in("A Thoughtful pullover", "es").is_empty());
+        assert_eq!(
+            glossary.replace("Thoughtful Thought", "es", str::to_uppercase),
+            "THOUGHTFUL Thought"
+        );
+    }
+}
@@ -0,0 +1,269 @@
+//! Git history lookups for article sources.
+//!
+//! Articles without an `Article.toml` take their creation date and author from
+//! the commit that first added their Markdown file, when the workspace is a git
+//! repository.
+//!
+//! With `[dates] git = true`, every article takes `created` and `updated` from
+//! the first and last commits touching its files. Both come from a single walk
+//! over the history, cached in `.thought/history.redb` and extended
+//! incrementally when `HEAD` moves forward.
+
+use std::{collections::BTreeMap, path::Path};
+
+use git2::{Commit, Delta, DiffFindOptions, DiffOptions, Oid, Repository, Sort};
+use redb::{Database, ReadableDatabase, TableDefinition};
+use serde::{Deserialize, Serialize};
+use time::{OffsetDateTime, UtcOffset};
+
+const HISTORY_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("history_dates");
+
+/// Where a file came from in the repository history.
+#[derive(Debug, Clone)]
+pub struct FileOrigin {
+    /// Author time of the commit that added the file (following renames).
+    pub created: OffsetDateTime,
+    /// Author name of that commit.
+    pub author: String,
+}
+
+fn author_time(commit: &Commit<'_>) -> Option<OffsetDateTime> {
+    let when = commit.author().when();
+    let offset = UtcOffset::from_whole_seconds(when.offset_minutes() * 60).ok()?;
+    Some(
+        OffsetDateTime::from_unix_timestamp(when.seconds())
+            .ok()?
+            .to_offset(offset),
+    )
+}
+
+/// First and last commit touching a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileDates {
+    /// Author time of the commit that added the file (following renames).
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    /// Author time of the latest commit that changed it.
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated: OffsetDateTime,
+}
+
+impl FileDates {
+    /// The earliest `created` and latest `updated` of both.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            created: self.created.min(other.created),
+            updated: self.updated.max(other.updated),
+        }
+    }
+}
+
+/// Commit dates for every file below a directory, as of one `HEAD`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryDates {
+    head: String,
+    /// Keyed by path relative to the directory, with `/` separators.
+    files: BTreeMap<String, FileDates>,
+    /// Author of the commit that added each file, keyed like `files`.
+    authors: BTreeMap<String, String>,
+}
+
+impl HistoryDates {
+    /// Commit the dates were read at.
+    #[must_use]
+    pub fn head(&self) -> &str {
+        &self.head
+    }
+
+    /// Dates of the file at `path`.
+    #[must_use]
+    pub fn file(&self, path: &str) -> Option<FileDates> {
+        self.files.get(path).copied()
+    }
+
+    /// When and by whom the file at `path` was added.
+    #[must_use]
+    pub fn origin(&self, path: &str) -> Option<FileOrigin> {
+        Some(FileOrigin {
+            created: self.files.get(path)?.created,
+            author: self.authors.get(path).cloned().unwrap_or_default(),
+        })
+    }
+
+    /// Combined dates of every file inside the directory `dir`.
+    #[must_use]
+    pub fn dir(&self, dir: &str) -> Option<FileDates> {
+        let prefix = format!("{}/", dir.trim_end_matches('/'));
+        self.files
+            .range(prefix.clone()..)
+            .take_while(|(path, _)| path.starts_with(&prefix))
+            .map(|(_, dates)| *dates)
+            .reduce(FileDates::merge)
+    }
+}
+
+/// Commit dates for files under `root`, reusing the walk cached in `cache`.
+///
+/// Returns `None` when `root` is not inside a git repository with commits.
+/// Cache failures only cost a full walk. Call from a blocking context.
+#[must_use]
+pub fn history_dates(root: &Path, cache: &Path) -> Option<HistoryDates> {
+    let database = open_cache(cache)
+        .inspect_err(|err| tracing::warn!("history cache unavailable: {err}"))
+        .ok();
+    let previous = database.as_ref().and_then(|database| {
+        let txn = database.begin_read().ok()?;
+        let table = txn.open_table(HISTORY_TABLE).ok()?;
+        let value = table.get("articles").ok()??;
+        bincode::deserialize::<HistoryDates>(value.value()).ok()
+    });
+
+    let dates = walk(root, previous.clone())?;
+    let changed = previous.is_none_or(|previous| previous.head != dates.head);
+    if changed && let Some(database) = &database {
+        let stored = bincode::serialize(&dates)
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| store(database, &bytes).map_err(|err| err.to_string()));
+        if let Err(err) = stored {
+            tracing::warn!("failed to cache history dates: {err}");
+        }
+    }
+    Some(dates)
+}
+
+fn open_cache(path: &Path) -> Result<Database, redb::Error> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(redb::StorageError::from)?;
+    }
+    Ok(Database::create(path)?)
+}
+
+fn store(database: &Database, bytes: &[u8]) -> Result<(), redb::Error> {
+    let txn = database.begin_write()?;
+    {
+        let mut table = txn.open_table(HISTORY_TABLE)?;
+        table.insert("articles", bytes)?;
+    }
+    txn.commit()?;
+    Ok(())
+}
+
+/// Walk the commits reachable from `HEAD`, oldest first, recording when each
+/// file under `root` was added and last changed. When `previous` was taken at
+/// an ancestor of `HEAD`, only the newer commits are visited.
+fn walk(root: &Path, previous: Option<HistoryDates>) -> Option<HistoryDates> {
+    let repo = Repository::discover(root).ok()?;
+    let workdir = repo.workdir()?.canonicalize().ok()?;
+    let root = root.canonicalize().ok()?;
+    let prefix = root
+        .strip_prefix(&workdir)
+        .ok()?
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    let head = repo.head().ok()?.peel_to_commit().ok()?.id();
+
+    let mut walk = repo.revwalk().ok()?;
+    walk.push(head).ok()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE).ok()?;
+
+    let mut files = BTreeMap::new();
+    let mut authors = BTreeMap::new();
+    if let Some(previous) = previous
+        && let Ok(base) = Oid::from_str(&previous.head)
+    {
+        if base == head {
+            return Some(previous);
+        }
+        if repo.graph_descendant_of(head, base).unwrap_or(false) && walk.hide(base).is_ok() {
+            files = previous.files;
+            authors = previous.authors;
+        }
+    }
+
+    let strip = |path: &Path| -> Option<String> {
+        let path = path.to_string_lossy().replace('\\', "/");
+        if prefix.is_empty() {
+            return Some(path);
+        }
+        path.strip_prefix(&prefix)?
+            .strip_prefix('/')
+            .map(str::to_string)
+    };
+
+    for oid in walk {
+        let Ok(commit) = oid.and_then(|oid| repo.find_commit(oid)) else {
+            continue;
+        };
+        let Some(when) = author_time(&commit) else {
+            continue;
+        };
+        let author = commit.author().name().unwrap_or_default().to_string();
+        let Ok(tree) = commit.tree() else {
+            continue;
+        };
+        let parent = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+
+        let mut options = DiffOptions::new();
+        if !prefix.is_empty() {
+            options.pathspec(&prefix);
+        }
+        let Ok(mut diff) = repo.diff_tree_to_tree(parent.as_ref(), Some(&tree), Some(&mut options))
+        else {
+            continue;
+        };
+        let _ = diff.find_similar(Some(DiffFindOptions::new().renames(true)));
+
+        for delta in diff.deltas() {
+            let old = delta.old_file().path().and_then(strip);
+            let new = delta.new_file().path().and_then(strip);
+            match delta.status() {
+                Delta::Deleted => {
+                    if let Some(old) = old {
+                        files.remove(&old);
+                        authors.remove(&old);
+                    }
+                }
+                Delta::Renamed => {
+                    let created = old
+                        .as_ref()
+                        .and_then(|old| files.remove(old))
+                        .map_or(when, |dates: FileDates| dates.created);
+                    let added_by = old
+                        .and_then(|old| authors.remove(&old))
+                        .unwrap_or_else(|| author.clone());
+                    if let Some(new) = new {
+                        authors.insert(new.clone(), added_by);
+                        files.insert(
+                            new,
+                            FileDates {
+                                created,
+                                updated: when,
+                            },
+                        );
+                    }
+                }
+                _ => {
+                    if let Some(new) = new {
+                        authors.entry(new.clone()).or_insert_with(|| author.clone());
+                        files
+                            .entry(new)
+                            .and_modify(|dates: &mut FileDates| dates.updated = when)
+                            .or_insert(FileDates {
+                                created: when,
+                                updated: when,
+                            });
+                    }
+                }
+            }
+        }
+    }
+
+    Some(HistoryDates {
+        head: head.to_string(),
+        files,
+        authors,
+    })
+}
@@ -0,0 +1,497 @@
+//! Importing content from other static site generators and blog engines.
+//!
+//! `thought import <format> <path>` reads a Hugo, Jekyll, Hexo or Zola content
+//! tree, or a WordPress export file, and writes each post as
+//! `articles/<category>/<slug>/` with an `Article.toml`, an `article.md` and
+//! one `<locale>.md` per translation. Images and other files that live next
+//! to a post (page bundles, Hexo asset folders, WordPress uploads) are copied
+//! into the article directory.
+
+mod html;
+mod markup;
+mod wordpress;
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::LazyLock,
+};
+
+use color_eyre::eyre::{self, eyre};
+use regex::Regex;
+use serde::Serialize;
+use slug::slugify;
+use time::OffsetDateTime;
+use tokio::fs as async_fs;
+
+use crate::{
+    archive,
+    front_matter::{self, parse_date},
+    locale,
+    metadata::{ArticleMetadata, MetadataExt},
+    slug::ArticleSlug,
+    utils::{read_to_string, write},
+    workspace::Workspace,
+};
+
+/// `2024-05-01-hello.md` (Jekyll, Zola) carries the date in the file name.
+static DATED_STEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d{4}-\d{2}-\d{2})[-_](.+)$").expect("valid dated stem regex"));
+
+/// Hexo's `{% asset_img file.png [title] %}` tag.
+static HEXO_ASSET_IMG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{%\s*asset_img\s+(\S+)(?:\s+([^%]*?))?\s*%\}").expect("valid asset_img regex")
+});
+
+/// Content trees Thought can import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// `content/` with sections and page bundles.
+    Hugo,
+    /// `_posts/YYYY-MM-DD-slug.md` and `_drafts/`.
+    Jekyll,
+    /// `source/_posts/` with optional asset folders, and `source/_drafts/`.
+    Hexo,
+    /// `content/` with sections and page bundles, TOML front matter.
+    Zola,
+    /// A WordPress eXtended RSS (WXR) export file.
+    WordPress,
+    /// An archive written by `thought export`.
+    ThoughtArchive,
+}
+
+/// Error returned when an import format name is not recognised.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "unknown import format `{0}` (expected hugo, jekyll, hexo, zola, wordpress or thought-archive)"
+)]
+pub struct UnknownImportFormat(String);
+
+impl FromStr for ImportFormat {
+    type Err = UnknownImportFormat;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "hugo" => Ok(Self::Hugo),
+            "jekyll" => Ok(Self::Jekyll),
+            "hexo" => Ok(Self::Hexo),
+            "zola" => Ok(Self::Zola),
+            "wordpress" | "wxr" => Ok(Self::WordPress),
+            "thought-archive" | "thought" => Ok(Self::ThoughtArchive),
+            _ => Err(UnknownImportFormat(value.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Hugo => "hugo",
+            Self::Jekyll => "jekyll",
+            Self::Hexo => "hexo",
+            Self::Zola => "zola",
+            Self::WordPress => "wordpress",
+            Self::ThoughtArchive => "thought-archive",
+        })
+    }
+}
+
+impl ImportFormat {
+    /// Directories holding posts under `source`, and whether they hold drafts.
+    fn roots(self, source: &Path) -> Vec<(PathBuf, bool)> {
+        let existing = |candidates: &[(&str, bool)]| {
+            candidates
+                .iter()
+                .map(|(dir, draft)| (source.join(dir), *draft))
+                .filter(|(dir, _)| dir.is_dir())
+                .collect::<Vec<_>>()
+        };
+        let roots = match self {
+            Self::Hugo | Self::Zola => existing(&[("content", false)]),
+            Self::Jekyll => existing(&[("_posts", false), ("_drafts", true)]),
+            Self::Hexo => existing(&[("source/_posts", false), ("source/_drafts", true)]),
+            Self::WordPress | Self::ThoughtArchive => Vec::new(),
+        };
+        if roots.is_empty() {
+            // Pointed directly at the posts directory.
+            vec![(source.to_path_buf(), false)]
+        } else {
+            roots
+        }
+    }
+
+    /// Whether `categories` in front matter is a hierarchy (Hexo) rather than
+    /// a set of labels, of which only the first is used.
+    const fn nested_categories(self) -> bool {
+        matches!(self, Self::Hexo)
+    }
+
+    /// Whether the category comes from front matter before the directory.
+    const fn categories_from_front_matter(self) -> bool {
+        matches!(self, Self::Jekyll | Self::Hexo)
+    }
+}
+
+/// Settings that only apply to some formats.
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Local copy of WordPress' `wp-content/uploads`, used instead of
+    /// downloading attachments.
+    pub uploads: Option<PathBuf>,
+}
+
+/// Outcome of an import.
+#[derive(Debug, Default, Serialize)]
+pub struct ImportReport {
+    /// Article paths (`category/slug`) that were written.
+    pub imported: Vec<String>,
+    /// Number of co-located files copied alongside the articles.
+    pub assets: usize,
+    /// Posts that were left out, with the reason.
+    pub skipped: Vec<SkippedPost>,
+    /// Problems that did not stop a post from being imported, such as an
+    /// attachment that could not be fetched.
+    pub warnings: Vec<String>,
+}
+
+/// A post that could not be imported.
+#[derive(Debug, Serialize)]
+pub struct SkippedPost {
+    /// Source file of the post.
+    pub source: PathBuf,
+    /// Why it was skipped.
+    pub reason: String,
+}
+
+/// One post found in the source tree, with its translations.
+#[derive(Debug, Default)]
+struct Post {
+    directory: Vec<String>,
+    stem: String,
+    primary: Option<PathBuf>,
+    variants: BTreeMap<String, PathBuf>,
+    assets: Option<PathBuf>,
+    draft: bool,
+}
+
+impl Workspace {
+    /// Import a content tree written for another generator.
+    ///
+    /// Posts that cannot be read, or whose destination already exists, are
+    /// reported in [`ImportReport::skipped`] rather than failing the import.
+    ///
+    /// # Errors
+    /// Returns an error if `source` cannot be walked or a file cannot be written.
+    pub async fn import(
+        &self,
+        format: ImportFormat,
+        source: &Path,
+        options: &ImportOptions,
+    ) -> eyre::Result<ImportReport> {
+        if matches!(
+            format,
+            ImportFormat::WordPress | ImportFormat::ThoughtArchive
+        ) {
+            if !source.is_file() {
+                return Err(eyre!("`{}` is not a file", source.display()));
+            }
+            let mut report = ImportReport::default();
+            if format == ImportFormat::WordPress {
+                self.category(&[]).await?;
+                wordpress::import(self, source, options, &mut report).await?;
+            } else {
+                // The archive carries its own root `Category.toml`.
+                archive::restore(self, source, &mut report).await?;
+                self.category(&[]).await?;
+            }
+            return Ok(report);
+        }
+        if !source.is_dir() {
+            return Err(eyre!("`{}` is not a directory", source.display()));
+        }
+        self.category(&[]).await?;
+
+        let mut posts = BTreeMap::<(Vec<String>, String), Post>::new();
+        for (root, draft) in format.roots(source) {
+            collect_posts(format, &root, draft, self.manifest().locales(), &mut posts).await?;
+        }
+
+        let mut report = ImportReport::default();
+        for post in posts.into_values() {
+            let source = post
+                .primary
+                .clone()
+                .or_else(|| post.variants.values().next().cloned())
+                .unwrap_or_default();
+            match import_post(self, format, post).await {
+                Ok((path, assets)) => {
+                    report.imported.push(path);
+                    report.assets += assets;
+                }
+                Err(err) => report.skipped.push(SkippedPost {
+                    source,
+                    reason: err.to_string(),
+                }),
+            }
+        }
+        Ok(report)
+    }
+}
+
+async fn collect_posts(
+    format: ImportFormat,
+    root: &Path,
+    draft: bool,
+    locales: &[String],
+    posts: &mut BTreeMap<(Vec<String>, String), Post>,
+) -> eyre::Result<()> {
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let mut entries = async_fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+            if entry.file_type().await?.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            let Some(stem) = name
+                .strip_suffix(".md")
+                .or_else(|| name.strip_suffix(".markdown"))
+            else {
+                continue;
+            };
+
+            // Locale suffix in `post.ja.md` or `index.zh_cn.md`, canonicalized.
+            let (stem, locale) = match stem
+                .rsplit_once('.')
+                .and_then(|(base, suffix)| Some((base, locale::from_file_stem(suffix, locales)?)))
+            {
+                Some((base, locale)) => (base.to_string(), Some(locale)),
+                None => (stem.to_string(), None),
+            };
+            // Section pages (`_index.md`) describe a listing, not a post.
+            if stem == "_index" {
+                continue;
+            }
+
+            let relative = dir
+                .strip_prefix(root)
+                .unwrap_or(Path::new(""))
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+
+            // Page bundles: `posts/hello/index.md` is the post `hello`, and
+            // everything else in `posts/hello/` belongs to it.
+            let (directory, stem, assets) = if stem == "index" && !relative.is_empty() {
+                let mut directory = relative;
+                let stem = directory.pop().unwrap_or_default();
+                (directory, stem, Some(dir.clone()))
+            } else {
+                let assets = (format == ImportFormat::Hexo)
+                    .then(|| dir.join(&stem))
+                    .filter(|folder| folder.is_dir());
+                (relative, stem, assets)
+            };
+
+            let post = posts
+                .entry((directory.clone(), stem.clone()))
+                .or_insert_with(|| Post {
+                    directory,
+                    stem,
+                    draft,
+                    ..Post::default()
+                });
+            post.assets = post.assets.take().or(assets);
+            match locale {
+                Some(locale) => {
+                    post.variants.insert(locale, path);
+                }
+                None => post.primary = Some(path),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write one post into the workspace, returning its article path and the
+/// number of assets copied.
+async fn import_post(
+    workspace: &Workspace,
+    format: ImportFormat,
+    mut post: Post,
+) -> eyre::Result<(String, usize)> {
+    // A tree where every file carries a language suffix has no unsuffixed
+    // primary; promote the first translation.
+    let (primary, primary_locale) = match post.primary.take() {
+        Some(primary) => (primary, None),
+        None => {
+            let locale = post
+                .variants
+                .keys()
+                .next()
+                .cloned()
+                .ok_or_else(|| eyre!("post has no content"))?;
+            let primary = post.variants.remove(&locale).unwrap_or_default();
+            (primary, Some(locale))
+        }
+    };
+
+    let raw = read_to_string(&primary).await?;
+    let (front_matter, body) = front_matter::split(&raw)?;
+    let front_matter = front_matter.unwrap_or_default();
+
+    let (date_hint, stem) = match DATED_STEM.captures(&post.stem) {
+        Some(captures) => (parse_date(&captures[1]).ok(), captures[2].to_string()),
+        None => (None, post.stem.clone()),
+    };
+    let slug = ArticleSlug::from_title(&stem)
+        .map_err(|err| eyre!("cannot derive a slug from `{}`: {err}", post.stem))?
+        .into_string();
+
+    let category: Vec<String> = match front_matter.categories.as_deref() {
+        Some([first, ..]) if format.categories_from_front_matter() => {
+            let labels = if format.nested_categories() {
+                front_matter.categories.clone().unwrap_or_default()
+            } else {
+                vec![first.clone()]
+            };
+            labels
+                .iter()
+                .map(slugify)
+                .filter(|segment| !segment.is_empty())
+                .collect()
+        }
+        // Section directories become category directories, named like slugs.
+        _ => post
+            .directory
+            .iter()
+            .map(slugify)
+            .filter(|segment| !segment.is_empty())
+            .collect(),
+    };
+
+    let mut metadata = ArticleMetadata::new(workspace.manifest().owner());
+    match date_hint {
+        Some(date) => metadata.set_created(date),
+        None => {
+            if let Ok(modified) = async_fs::metadata(&primary)
+                .await
+                .and_then(|meta| meta.modified())
+            {
+                metadata.set_created(OffsetDateTime::from(modified));
+            }
+        }
+    }
+    front_matter.apply(&mut metadata);
+    if post.draft {
+        metadata.set_draft(true);
+    }
+    if let Some(locale) = primary_locale
+        && metadata.lang().is_none()
+    {
+        metadata.set_lang(locale);
+    }
+
+    let (path, article_dir) = article_destination(workspace, &category, &slug).await?;
+    metadata
+        .save_to_file(article_dir.join("Article.toml"))
+        .await?;
+    let content = convert_body(format, front_matter.title.as_deref(), body);
+    write(article_dir.join("article.md"), content.as_bytes()).await?;
+
+    for (locale, variant) in &post.variants {
+        let raw = read_to_string(variant).await?;
+        let (front_matter, body) = front_matter::split(&raw)?;
+        let title = front_matter.and_then(|front_matter| front_matter.title);
+        let content = convert_body(format, title.as_deref(), body);
+        write(article_dir.join(format!("{locale}.md")), content.as_bytes()).await?;
+    }
+
+    let assets = match &post.assets {
+        Some(dir) => copy_assets(dir, &article_dir).await?,
+        None => 0,
+    };
+    Ok((path, assets))
+}
+
+/// Create `category` if needed and return the path and directory a new
+/// article `slug` will be written to.
+///
+/// # Errors
+/// Returns an error if the category cannot be created or an article already
+/// exists there.
+async fn article_destination(
+    workspace: &Workspace,
+    category: &[String],
+    slug: &str,
+) -> eyre::Result<(String, PathBuf)> {
+    let mut path = category.to_vec();
+    path.push(slug.to_string());
+    let path = path.join("/");
+
+    if !category.is_empty() {
+        workspace.create_category(category.to_vec(), "").await?;
+    }
+    let article_dir = category
+        .iter()
+        .fold(workspace.articles_dir(), |dir, segment| dir.join(segment))
+        .join(slug);
+    if article_dir.exists() || article_dir.with_extension("md").exists() {
+        return Err(eyre!("an article already exists at `{path}`"));
+    }
+    Ok((path, article_dir))
+}
+
+/// Rewrite generator-specific syntax in `body`.
+///
+/// Thought takes the title from the first heading, so a front matter title
+/// becomes one when the body does not start with a heading already.
+fn convert_body(format: ImportFormat, title: Option<&str>, body: &str) -> String {
+    let body = if format == ImportFormat::Hexo {
+        HEXO_ASSET_IMG.replace_all(body, "![$2]($1)")
+    } else {
+        body.into()
+    };
+    match title {
+        Some(title) if !body.trim_start().starts_with("# ") => {
+            format!("# {}\n\n{body}", title.trim())
+        }
+        _ => body.into_owned(),
+    }
+}
+
+/// Copy every non-Markdown file under `from` into `to`, keeping the layout.
+async fn copy_assets(from: &Path, to: &Path) -> eyre::Result<usize> {
+    let mut copied = 0;
+    let mut stack = vec![from.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let mut entries = async_fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_type().await?.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            let is_markdown = path
+                .extension()
+                .is_some_and(|extension| extension == "md" || extension == "markdown");
+            if is_markdown {
+                continue;
+            }
+            let target = to.join(path.strip_prefix(from)?);
+            if let Some(parent) = target.parent() {
+                async_fs::create_dir_all(parent).await?;
+            }
+            async_fs::copy(&path, &target).await?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
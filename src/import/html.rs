@@ -0,0 +1,501 @@
+//! HTML to Markdown conversion for imported posts.
+//!
+//! Covers what blog editors produce: paragraphs, headings, emphasis, links,
+//! images, lists, quotes, code blocks and simple tables. Anything else is
+//! reduced to its text.
+
+use super::markup::{Token, decode_entities, tokenize};
+
+const VOID_ELEMENTS: [&str; 9] = [
+    "br", "hr", "img", "input", "meta", "link", "source", "wbr", "col",
+];
+
+const BLOCK_ELEMENTS: [&str; 27] = [
+    "p",
+    "div",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "aside",
+    "nav",
+    "main",
+    "figure",
+    "figcaption",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "ul",
+    "ol",
+    "li",
+    "blockquote",
+    "pre",
+    "hr",
+    "table",
+    "dl",
+    "dt",
+    "dd",
+];
+
+#[derive(Debug)]
+enum Node {
+    Element {
+        name: String,
+        attrs: Vec<(String, String)>,
+        children: Vec<Node>,
+    },
+    Text(String),
+}
+
+impl Node {
+    fn is_block(&self) -> bool {
+        matches!(self, Self::Element { name, .. } if BLOCK_ELEMENTS.contains(&name.as_str()))
+    }
+
+    fn attr(&self, key: &str) -> Option<&str> {
+        match self {
+            Self::Element { attrs, .. } => attrs
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str()),
+            Self::Text(_) => None,
+        }
+    }
+
+    /// Decoded text of the node and its descendants.
+    fn text(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Element { name, .. } if name == "br" => "\n".to_string(),
+            Self::Element { children, .. } => children.iter().map(Self::text).collect(),
+        }
+    }
+}
+
+/// Convert an HTML fragment to Markdown.
+///
+/// Fragments without any `<p>` are treated like WordPress's `wpautop`
+/// output: blank lines separate paragraphs and single newlines are breaks.
+pub(crate) fn to_markdown(html: &str) -> String {
+    let has_paragraphs = tokenize(html)
+        .iter()
+        .any(|token| matches!(token, Token::Start { name, .. } if name == "p"));
+    let html = if has_paragraphs {
+        html.to_string()
+    } else {
+        autop(html)
+    };
+    let tree = parse(&html);
+    blocks(&tree).join("\n\n")
+}
+
+/// Plain text of an HTML fragment, with whitespace collapsed.
+pub(crate) fn to_text(html: &str) -> String {
+    let tree = parse(html);
+    let text = tree.iter().map(Node::text).collect::<String>();
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn autop(html: &str) -> String {
+    html.replace("\r\n", "\n")
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|chunk| !chunk.is_empty())
+        .map(|chunk| {
+            if chunk.starts_with('<') && tokenize(chunk).first().is_some_and(|token| {
+                matches!(token, Token::Start { name, .. } if BLOCK_ELEMENTS.contains(&name.as_str()))
+            }) {
+                chunk.to_string()
+            } else {
+                format!("<p>{}</p>", chunk.replace('\n', "<br>\n"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// An element being parsed: name, attributes and the children so far.
+type OpenElement = (String, Vec<(String, String)>, Vec<Node>);
+
+fn parse(html: &str) -> Vec<Node> {
+    let mut stack: Vec<OpenElement> = vec![(String::new(), Vec::new(), Vec::new())];
+
+    fn close(stack: &mut Vec<OpenElement>) {
+        if stack.len() > 1 {
+            let (name, attrs, children) = stack.pop().expect("stack has an open element");
+            stack
+                .last_mut()
+                .expect("root is never popped")
+                .2
+                .push(Node::Element {
+                    name,
+                    attrs,
+                    children,
+                });
+        }
+    }
+
+    for token in tokenize(html) {
+        match token {
+            Token::Start {
+                name,
+                attrs,
+                self_closing,
+            } => {
+                // Unclosed `<p>` and `<li>` end where the next one starts.
+                let top = stack.last().map(|(open, ..)| open.as_str());
+                if (name == "p" && top == Some("p")) || (name == "li" && top == Some("li")) {
+                    close(&mut stack);
+                }
+                if self_closing || VOID_ELEMENTS.contains(&name.as_str()) {
+                    stack
+                        .last_mut()
+                        .expect("root is never popped")
+                        .2
+                        .push(Node::Element {
+                            name,
+                            attrs,
+                            children: Vec::new(),
+                        });
+                } else {
+                    stack.push((name, attrs, Vec::new()));
+                }
+            }
+            Token::End { name } => {
+                if let Some(position) = stack.iter().rposition(|(open, ..)| *open == name)
+                    && position > 0
+                {
+                    while stack.len() > position {
+                        close(&mut stack);
+                    }
+                }
+            }
+            Token::Text(text) => stack
+                .last_mut()
+                .expect("root is never popped")
+                .2
+                .push(Node::Text(decode_entities(text).into_owned())),
+            Token::CData(text) => stack
+                .last_mut()
+                .expect("root is never popped")
+                .2
+                .push(Node::Text(text.to_string())),
+        }
+    }
+    while stack.len() > 1 {
+        close(&mut stack);
+    }
+    stack
+        .pop()
+        .map(|(.., children)| children)
+        .unwrap_or_default()
+}
+
+/// Render `nodes` as a list of Markdown blocks.
+fn blocks(nodes: &[Node]) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut inline_run: Vec<&Node> = Vec::new();
+
+    let flush = |run: &mut Vec<&Node>, out: &mut Vec<String>| {
+        let paragraph = paragraph(run);
+        if !paragraph.is_empty() {
+            out.push(paragraph);
+        }
+        run.clear();
+    };
+
+    for node in nodes {
+        if !node.is_block() {
+            inline_run.push(node);
+            continue;
+        }
+        flush(&mut inline_run, &mut out);
+        let Node::Element { name, children, .. } = node else {
+            continue;
+        };
+        match name.as_str() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = usize::from(name.as_bytes()[1] - b'0');
+                let text = paragraph(&children.iter().collect::<Vec<_>>());
+                if !text.is_empty() {
+                    out.push(format!("{} {}", "#".repeat(level), text.replace('\n', " ")));
+                }
+            }
+            "ul" | "ol" => {
+                let list = list(node, name == "ol");
+                if !list.is_empty() {
+                    out.push(list);
+                }
+            }
+            "blockquote" => {
+                let inner = blocks(children).join("\n\n");
+                if !inner.is_empty() {
+                    out.push(prefix_lines(&inner, "> ", "> "));
+                }
+            }
+            "pre" => out.push(code_block(node)),
+            "hr" => out.push("---".to_string()),
+            "table" => {
+                let table = table(node);
+                if !table.is_empty() {
+                    out.push(table);
+                }
+            }
+            "figcaption" => {
+                let text = paragraph(&children.iter().collect::<Vec<_>>());
+                if !text.is_empty() {
+                    out.push(format!("*{text}*"));
+                }
+            }
+            _ => out.extend(blocks(children)),
+        }
+    }
+    flush(&mut inline_run, &mut out);
+    out
+}
+
+/// Render a run of inline nodes as one paragraph.
+fn paragraph(nodes: &[&Node]) -> String {
+    let text = nodes.iter().map(|node| inline(node)).collect::<String>();
+    text.split('\n')
+        .map(|line| collapse_spaces(line.trim()))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .trim_end_matches('\\')
+        .trim()
+        .to_string()
+}
+
+fn inline(node: &Node) -> String {
+    match node {
+        Node::Text(text) => escape(&collapse_whitespace(text)),
+        Node::Element { name, children, .. } => {
+            let inner = || children.iter().map(inline).collect::<String>();
+            match name.as_str() {
+                "br" => "\\\n".to_string(),
+                "strong" | "b" => wrap(&inner(), "**"),
+                "em" | "i" | "cite" => wrap(&inner(), "*"),
+                "del" | "s" | "strike" => wrap(&inner(), "~~"),
+                "code" | "kbd" | "samp" | "tt" => code_span(&node.text()),
+                "a" => {
+                    let text = inner();
+                    match node.attr("href").filter(|href| !href.is_empty()) {
+                        Some(href) if !text.trim().is_empty() => {
+                            format!("[{}]({})", text.trim(), destination(href))
+                        }
+                        _ => text,
+                    }
+                }
+                "img" => {
+                    let Some(src) = node.attr("src").filter(|src| !src.is_empty()) else {
+                        return String::new();
+                    };
+                    let alt = escape(node.attr("alt").unwrap_or_default());
+                    format!("![{alt}]({})", destination(src))
+                }
+                "script" | "style" => String::new(),
+                _ if node.is_block() => format!(" {} ", blocks(children).join(" ")),
+                _ => inner(),
+            }
+        }
+    }
+}
+
+fn list(node: &Node, ordered: bool) -> String {
+    let Node::Element { children, .. } = node else {
+        return String::new();
+    };
+    let mut items = Vec::new();
+    let mut number = node
+        .attr("start")
+        .and_then(|start| start.parse::<usize>().ok())
+        .unwrap_or(1);
+    for child in children {
+        let Node::Element { name, children, .. } = child else {
+            continue;
+        };
+        if name != "li" {
+            continue;
+        }
+        let marker = if ordered {
+            let marker = format!("{number}. ");
+            number += 1;
+            marker
+        } else {
+            "- ".to_string()
+        };
+        let body = blocks(children).join("\n");
+        let indent = " ".repeat(marker.len());
+        items.push(prefix_lines(&body, &marker, &indent));
+    }
+    items.join("\n")
+}
+
+fn code_block(node: &Node) -> String {
+    let Node::Element { children, .. } = node else {
+        return String::new();
+    };
+    let language_of = |node: &Node| {
+        node.attr("class").and_then(|class| {
+            class.split_whitespace().find_map(|class| {
+                class
+                    .strip_prefix("language-")
+                    .or_else(|| class.strip_prefix("lang-"))
+                    .map(str::to_string)
+            })
+        })
+    };
+    let language = language_of(node)
+        .or_else(|| children.iter().find_map(language_of))
+        .unwrap_or_default();
+    let code = node.text();
+    let code = code.trim_matches('\n');
+    let fence = if code.contains("```") { "~~~" } else { "```" };
+    format!("{fence}{language}\n{code}\n{fence}")
+}
+
+fn table(node: &Node) -> String {
+    fn rows<'a>(node: &'a Node, out: &mut Vec<&'a [Node]>) {
+        if let Node::Element { name, children, .. } = node {
+            if name == "tr" {
+                out.push(children);
+            } else {
+                for child in children {
+                    rows(child, out);
+                }
+            }
+        }
+    }
+
+    let mut found = Vec::new();
+    rows(node, &mut found);
+    let rows = found
+        .into_iter()
+        .map(|cells| {
+            cells
+                .iter()
+                .filter(|cell| matches!(cell, Node::Element { name, .. } if name == "td" || name == "th"))
+                .map(|cell| match cell {
+                    Node::Element { children, .. } => {
+                        paragraph(&children.iter().collect::<Vec<_>>())
+                            .replace('\n', " ")
+                            .replace('|', "\\|")
+                    }
+                    Node::Text(_) => String::new(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|row| !row.is_empty())
+        .collect::<Vec<_>>();
+
+    let Some(columns) = rows.iter().map(Vec::len).max() else {
+        return String::new();
+    };
+    let line = |row: &[String]| {
+        let mut cells = row.to_vec();
+        cells.resize(columns, String::new());
+        format!("| {} |", cells.join(" | "))
+    };
+    let mut out = vec![line(&rows[0]), format!("|{}", " --- |".repeat(columns))];
+    out.extend(rows[1..].iter().map(|row| line(row)));
+    out.join("\n")
+}
+
+/// Put `first` before the first line of `text` and `rest` before the others.
+fn prefix_lines(text: &str, first: &str, rest: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let prefix = if index == 0 { first } else { rest };
+            if line.is_empty() {
+                prefix.trim_end().to_string()
+            } else {
+                format!("{prefix}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Surround `text` with `marker`, keeping surrounding spaces outside so the
+/// emphasis stays valid (`** x **` is not bold).
+fn wrap(text: &str, marker: &str) -> String {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return text.to_string();
+    }
+    let leading = if text.starts_with(char::is_whitespace) {
+        " "
+    } else {
+        ""
+    };
+    let trailing = if text.ends_with(char::is_whitespace) {
+        " "
+    } else {
+        ""
+    };
+    format!("{leading}{marker}{trimmed}{marker}{trailing}")
+}
+
+fn code_span(code: &str) -> String {
+    let code = collapse_whitespace(code);
+    let fence = if code.contains('`') { "``" } else { "`" };
+    let padding = if code.starts_with('`') || code.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    format!("{fence}{padding}{code}{padding}{fence}")
+}
+
+fn destination(url: &str) -> String {
+    if url.contains([' ', '(', ')']) {
+        format!("<{url}>")
+    } else {
+        url.to_string()
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_space = false;
+    for character in text.chars() {
+        if character.is_whitespace() && character != '\u{a0}' {
+            if !in_space {
+                out.push(' ');
+            }
+            in_space = true;
+        } else {
+            out.push(character);
+            in_space = false;
+        }
+    }
+    out
+}
+
+fn collapse_spaces(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    for character in line.chars() {
+        if character == ' ' && out.ends_with(' ') {
+            continue;
+        }
+        out.push(character);
+    }
+    out
+}
+
+/// Escape characters that would otherwise start Markdown syntax.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for character in text.chars() {
+        if matches!(character, '\\' | '`' | '*' | '_' | '[' | ']' | '<') {
+            out.push('\\');
+        }
+        out.push(character);
+    }
+    out
+}
@@ -0,0 +1,227 @@
+//! A forgiving tokenizer for the XML and HTML found in exports.
+//!
+//! Markup is read with quick-xml in its most lenient configuration. Nothing
+//! is validated: mismatched end tags pass through, and wherever quick-xml
+//! still gives up the offending `<` is kept as text and reading resumes after
+//! it, which is what imported content needs.
+
+use std::{borrow::Cow, ops::Range};
+
+use quick_xml::{Reader, events::Event};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    /// An opening tag; `name` is lowercased.
+    Start {
+        name: String,
+        attrs: Vec<(String, String)>,
+        self_closing: bool,
+    },
+    /// A closing tag; `name` is lowercased.
+    End { name: String },
+    /// Character data with entities still encoded.
+    Text(&'a str),
+    /// The contents of a `<![CDATA[…]]>` section.
+    CData(&'a str),
+}
+
+impl Token<'_> {
+    /// Value of attribute `name` on a start tag.
+    pub(crate) fn attr(&self, name: &str) -> Option<&str> {
+        match self {
+            Self::Start { attrs, .. } => attrs
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str()),
+            _ => None,
+        }
+    }
+}
+
+/// Split `input` into tags and text. Comments, doctypes and processing
+/// instructions are dropped; `<script>` and `<style>` bodies are returned as
+/// a single text token.
+pub(crate) fn tokenize(input: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    while offset < input.len() {
+        offset = read_from(input, offset, &mut tokens);
+    }
+    tokens
+}
+
+/// Tokenize `input` from `start` until its end, a raw-text element, or
+/// markup quick-xml rejects. Returns the offset to resume reading at.
+fn read_from<'a>(input: &'a str, start: usize, tokens: &mut Vec<Token<'a>>) -> usize {
+    let rest = &input[start..];
+    let mut reader = Reader::from_str(rest);
+    let config = reader.config_mut();
+    config.check_end_names = false;
+    config.allow_unmatched_ends = true;
+    config.allow_dangling_amp = true;
+
+    loop {
+        let before = position(&reader);
+        let event = reader.read_event();
+        let raw = &rest[before..position(&reader)];
+        match event {
+            Ok(Event::Eof) => return input.len(),
+            Ok(Event::Start(tag) | Event::Empty(tag)) => {
+                let name = lowercase(tag.name().as_ref());
+                if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                    push_text(tokens, input, start + before..start + before + 1);
+                    return start + before + 1;
+                }
+                let self_closing = raw.ends_with("/>");
+                let attrs = tag
+                    .html_attributes()
+                    .filter_map(Result::ok)
+                    .map(|attr| {
+                        let value = std::str::from_utf8(&attr.value).unwrap_or_default();
+                        (
+                            lowercase(attr.key.as_ref()),
+                            decode_entities(value).into_owned(),
+                        )
+                    })
+                    .collect();
+                let raw_text = !self_closing && matches!(name.as_str(), "script" | "style");
+                let close = format!("</{name}");
+                tokens.push(Token::Start {
+                    name,
+                    attrs,
+                    self_closing,
+                });
+                if raw_text {
+                    let body = &rest[position(&reader)..];
+                    let end = find_ignore_case(body, &close).unwrap_or(body.len());
+                    if end > 0 {
+                        tokens.push(Token::Text(&body[..end]));
+                    }
+                    return start + position(&reader) + end;
+                }
+            }
+            Ok(Event::End(tag)) => tokens.push(Token::End {
+                name: lowercase(tag.name().as_ref()).trim().to_string(),
+            }),
+            Ok(Event::Text(_) | Event::GeneralRef(_)) => {
+                push_text(tokens, input, start + before..start + before + raw.len());
+            }
+            Ok(Event::CData(_)) => tokens.push(Token::CData(
+                raw.strip_prefix("<![CDATA[")
+                    .and_then(|data| data.strip_suffix("]]>"))
+                    .unwrap_or_default(),
+            )),
+            Ok(_) => {}
+            Err(_) => {
+                let at = start + before;
+                if input[at..].starts_with('<') {
+                    push_text(tokens, input, at..at + 1);
+                    return at + 1;
+                }
+                push_text(tokens, input, at..input.len());
+                return input.len();
+            }
+        }
+    }
+}
+
+fn position(reader: &Reader<&[u8]>) -> usize {
+    usize::try_from(reader.buffer_position()).unwrap_or(usize::MAX)
+}
+
+fn lowercase(name: &[u8]) -> String {
+    String::from_utf8_lossy(name).to_ascii_lowercase()
+}
+
+/// Append `text`, merging it with a directly preceding text token so that
+/// entity references and restarts do not split the text around them.
+fn push_text<'a>(tokens: &mut Vec<Token<'a>>, input: &'a str, range: Range<usize>) {
+    if range.is_empty() {
+        return;
+    }
+    if let Some(Token::Text(previous)) = tokens.last_mut() {
+        let previous_start = previous.as_ptr() as usize - input.as_ptr() as usize;
+        if previous_start + previous.len() == range.start {
+            *previous = &input[previous_start..range.end];
+            return;
+        }
+    }
+    tokens.push(Token::Text(&input[range]));
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .char_indices()
+        .map(|(index, _)| index)
+        .find(|&index| {
+            haystack
+                .get(index..index + needle.len())
+                .is_some_and(|candidate| candidate.eq_ignore_ascii_case(needle))
+        })
+}
+
+/// Replace character references (`&amp;`, `&#8217;`, `&#x2014;`) with the
+/// characters they stand for. Unknown references are kept verbatim.
+pub(crate) fn decode_entities(input: &str) -> Cow<'_, str> {
+    if !input.contains('&') {
+        return Cow::Borrowed(input);
+    }
+
+    let mut decoded = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let reference = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 32)
+            .map(|end| &rest[1..=end]);
+        match reference.and_then(entity) {
+            Some(character) => {
+                decoded.push(character);
+                rest = &rest[reference.map_or(0, str::len) + 2..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    Cow::Owned(decoded)
+}
+
+fn entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "middot" => '·',
+        "bull" => '•',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "times" => '×',
+        "deg" => '°',
+        _ => return None,
+    })
+}
@@ -0,0 +1,429 @@
+//! WordPress eXtended RSS (WXR) exports.
+//!
+//! Posts become articles in the category of their first WordPress category
+//! (nested by its parents), post tags become tags and the old permalink is
+//! kept as an alias. Images under `wp-content/uploads/` that a post embeds or
+//! owns as attachments are copied from a local uploads directory when one is
+//! given, or downloaded otherwise.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::LazyLock,
+    time::Duration,
+};
+
+use color_eyre::eyre::{self, eyre};
+use regex::Regex;
+use slug::slugify;
+use tokio::fs as async_fs;
+use url::Url;
+
+use super::{
+    ImportOptions, ImportReport, SkippedPost, article_destination,
+    html::{to_markdown, to_text},
+    markup::{Token, decode_entities, tokenize},
+};
+use crate::{
+    front_matter::parse_date,
+    metadata::{ArticleMetadata, MetadataExt},
+    utils::{percent_decode, read_to_string, write},
+    workspace::Workspace,
+};
+
+/// How long a single upload may take to download.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+static UPLOAD_URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"https?://[^\s"'()<>\]]+?/wp-content/uploads/[^\s"'()<>\]]+"#)
+        .expect("valid upload url regex")
+});
+
+/// A `<category>` term attached to an item.
+#[derive(Debug)]
+struct Term {
+    domain: String,
+    nicename: String,
+    name: String,
+}
+
+/// Direct children of an `<item>`, `<wp:category>` or `<wp:author>`.
+#[derive(Debug, Default)]
+struct Record {
+    fields: HashMap<String, String>,
+    terms: Vec<Term>,
+}
+
+impl Record {
+    fn get(&self, field: &str) -> &str {
+        self.fields.get(field).map_or("", String::as_str)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Export {
+    items: Vec<Record>,
+    categories: Vec<Record>,
+    authors: Vec<Record>,
+}
+
+struct Field {
+    name: String,
+    domain: String,
+    nicename: String,
+    text: String,
+}
+
+fn parse(xml: &str) -> Export {
+    let mut export = Export::default();
+    let mut open: Vec<String> = Vec::new();
+    let mut record: Option<(String, Record)> = None;
+    let mut field: Option<Field> = None;
+
+    for token in tokenize(xml) {
+        match &token {
+            Token::Start {
+                name, self_closing, ..
+            } => {
+                let parent = open.last().map(String::as_str);
+                match &record {
+                    None if parent == Some("channel")
+                        && matches!(name.as_str(), "item" | "wp:category" | "wp:author") =>
+                    {
+                        record = Some((name.clone(), Record::default()));
+                    }
+                    Some((kind, _)) if parent == Some(kind.as_str()) && field.is_none() => {
+                        field = Some(Field {
+                            name: name.clone(),
+                            domain: token.attr("domain").unwrap_or_default().to_string(),
+                            nicename: token.attr("nicename").unwrap_or_default().to_string(),
+                            text: String::new(),
+                        });
+                    }
+                    _ => {}
+                }
+                if !self_closing {
+                    open.push(name.clone());
+                }
+                if *self_closing {
+                    finish_field(&mut record, &mut field, name, &open);
+                }
+            }
+            Token::End { name } => {
+                if let Some(position) = open.iter().rposition(|open| open == name) {
+                    open.truncate(position);
+                }
+                finish_field(&mut record, &mut field, name, &open);
+                let closes_record = record.as_ref().is_some_and(|(kind, _)| kind == name)
+                    && open.last().map(String::as_str) == Some("channel");
+                if closes_record && let Some((kind, finished)) = record.take() {
+                    match kind.as_str() {
+                        "item" => export.items.push(finished),
+                        "wp:category" => export.categories.push(finished),
+                        _ => export.authors.push(finished),
+                    }
+                }
+            }
+            Token::Text(text) => {
+                if let Some(field) = &mut field {
+                    field.text.push_str(&decode_entities(text));
+                }
+            }
+            Token::CData(text) => {
+                if let Some(field) = &mut field {
+                    field.text.push_str(text);
+                }
+            }
+        }
+    }
+    export
+}
+
+fn finish_field(
+    record: &mut Option<(String, Record)>,
+    field: &mut Option<Field>,
+    name: &str,
+    open: &[String],
+) {
+    let Some((kind, record)) = record else {
+        return;
+    };
+    if open.last() != Some(kind) || field.as_ref().is_none_or(|field| field.name != name) {
+        return;
+    }
+    let Some(field) = field.take() else {
+        return;
+    };
+    if field.name == "category" {
+        record.terms.push(Term {
+            domain: field.domain,
+            nicename: field.nicename,
+            name: field.text.trim().to_string(),
+        });
+    } else {
+        record
+            .fields
+            .insert(field.name, field.text.trim().to_string());
+    }
+}
+
+/// Import the posts of the WXR file at `source`.
+pub(super) async fn import(
+    workspace: &Workspace,
+    source: &Path,
+    options: &ImportOptions,
+    report: &mut ImportReport,
+) -> eyre::Result<()> {
+    let xml = read_to_string(source).await?;
+    let export = parse(&xml);
+    if export.items.is_empty() {
+        return Err(eyre!("`{}` contains no WordPress items", source.display()));
+    }
+
+    let categories = export
+        .categories
+        .iter()
+        .map(|category| (category.get("wp:category_nicename").to_string(), category))
+        .collect::<HashMap<_, _>>();
+    let authors = export
+        .authors
+        .iter()
+        .map(|author| {
+            let display = author.get("wp:author_display_name");
+            let login = author.get("wp:author_login");
+            (
+                login.to_string(),
+                if display.is_empty() { login } else { display }.to_string(),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut attachments: HashMap<&str, Vec<&str>> = HashMap::new();
+    for item in &export.items {
+        if item.get("wp:post_type") == "attachment" && !item.get("wp:attachment_url").is_empty() {
+            attachments
+                .entry(item.get("wp:post_parent"))
+                .or_default()
+                .push(item.get("wp:attachment_url"));
+        }
+    }
+
+    for item in export
+        .items
+        .iter()
+        .filter(|item| item.get("wp:post_type") == "post")
+    {
+        let id = item.get("wp:post_id");
+        let described = format!("{}#{id}", source.display());
+        let draft = match item.get("wp:status") {
+            "publish" | "" => false,
+            "draft" | "pending" | "private" | "future" => true,
+            status => {
+                report.skipped.push(SkippedPost {
+                    source: PathBuf::from(&described),
+                    reason: format!("post status is `{status}`"),
+                });
+                continue;
+            }
+        };
+
+        match import_item(
+            workspace,
+            item,
+            draft,
+            &categories,
+            &authors,
+            attachments.get(id).map(Vec::as_slice).unwrap_or_default(),
+            options,
+            report,
+        )
+        .await
+        {
+            Ok(path) => report.imported.push(path),
+            Err(err) => report.skipped.push(SkippedPost {
+                source: PathBuf::from(described),
+                reason: err.to_string(),
+            }),
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn import_item(
+    workspace: &Workspace,
+    item: &Record,
+    draft: bool,
+    categories: &HashMap<String, &Record>,
+    authors: &HashMap<String, String>,
+    attachments: &[&str],
+    options: &ImportOptions,
+    report: &mut ImportReport,
+) -> eyre::Result<String> {
+    let title = item.get("title");
+    let slug = [
+        percent_decode(item.get("wp:post_name")),
+        slugify(title),
+        format!("post-{}", item.get("wp:post_id")),
+    ]
+    .into_iter()
+    .find(|slug| !slug.trim().is_empty())
+    .unwrap_or_default();
+    if slug.contains(['/', '\\']) {
+        return Err(eyre!("cannot use `{slug}` as a slug"));
+    }
+
+    let category = item
+        .terms
+        .iter()
+        .find(|term| term.domain == "category")
+        .map(|term| {
+            let nicename = if term.nicename.is_empty() {
+                slugify(&term.name)
+            } else {
+                percent_decode(&term.nicename)
+            };
+            category_path(&nicename, categories)
+        })
+        .unwrap_or_default();
+    // Create the chain first so each level keeps its WordPress description.
+    for depth in 1..=category.len() {
+        let description = categories
+            .get(&category[depth - 1])
+            .map(|category| category.get("wp:category_description"))
+            .unwrap_or_default();
+        workspace
+            .create_category(category[..depth].to_vec(), description)
+            .await?;
+    }
+
+    let creator = item.get("dc:creator");
+    let author = authors
+        .get(creator)
+        .cloned()
+        .filter(|author| !author.is_empty())
+        .unwrap_or_else(|| {
+            if creator.is_empty() {
+                workspace.manifest().owner().to_string()
+            } else {
+                creator.to_string()
+            }
+        });
+    let mut metadata = ArticleMetadata::new(author);
+    let created = [item.get("wp:post_date_gmt"), item.get("wp:post_date")]
+        .into_iter()
+        .filter(|date| !date.is_empty() && !date.starts_with("0000"))
+        .find_map(|date| parse_date(date).ok());
+    if let Some(created) = created {
+        metadata.set_created(created);
+    }
+    for term in item.terms.iter().filter(|term| term.domain == "post_tag") {
+        if !term.name.is_empty() && !metadata.tags().contains(&term.name) {
+            metadata.add_tag(term.name.clone());
+        }
+    }
+    let excerpt = to_text(item.get("excerpt:encoded"));
+    if !excerpt.is_empty() {
+        metadata.set_description(excerpt);
+    }
+    if let Ok(link) = Url::parse(item.get("link"))
+        && link.path() != "/"
+        && link.query().is_none()
+    {
+        metadata.add_alias(link.path().to_string());
+    }
+    metadata.set_draft(draft);
+
+    let (path, article_dir) = article_destination(workspace, &category, &slug).await?;
+    let mut content = to_markdown(item.get("content:encoded"));
+
+    let mut uploads = UPLOAD_URL
+        .find_iter(&content)
+        .map(|found| found.as_str().to_string())
+        .collect::<BTreeSet<_>>();
+    uploads.extend(attachments.iter().map(|url| (*url).to_string()));
+    for url in uploads {
+        match fetch_upload(&url, options.uploads.as_deref()).await {
+            Ok((name, bytes)) => {
+                write(article_dir.join(&name), &bytes).await?;
+                content = content.replace(&url, &name);
+                report.assets += 1;
+            }
+            Err(err) => report.warnings.push(format!("{path}: {url}: {err}")),
+        }
+    }
+
+    let title = decode_entities(title);
+    let content = if title.trim().is_empty() {
+        content
+    } else {
+        format!("# {}\n\n{content}", title.trim())
+    };
+    metadata
+        .save_to_file(article_dir.join("Article.toml"))
+        .await?;
+    write(
+        article_dir.join("article.md"),
+        format!("{content}\n").as_bytes(),
+    )
+    .await?;
+    Ok(path)
+}
+
+/// Category segments for `nicename`, outermost parent first.
+fn category_path(nicename: &str, categories: &HashMap<String, &Record>) -> Vec<String> {
+    let mut path = vec![nicename.to_string()];
+    let mut current = nicename;
+    while let Some(parent) = categories
+        .get(current)
+        .map(|category| category.get("wp:category_parent"))
+        .filter(|parent| !parent.is_empty())
+    {
+        // A cycle in a hand-edited export must not loop forever.
+        if path.iter().any(|segment| segment == parent) || path.len() > 16 {
+            break;
+        }
+        path.push(parent.to_string());
+        current = parent;
+    }
+    path.reverse();
+    path.into_iter()
+        .map(|segment| percent_decode(&segment))
+        .collect()
+}
+
+/// Path relative to `wp-content/uploads` and contents of an uploaded file, read from `uploads` when given
+/// (the site's `wp-content/uploads` directory) or downloaded otherwise.
+async fn fetch_upload(url: &str, uploads: Option<&Path>) -> eyre::Result<(String, Vec<u8>)> {
+    let parsed = Url::parse(url)?;
+    let relative = parsed
+        .path()
+        .split_once("/wp-content/uploads/")
+        .map(|(_, relative)| percent_decode(relative))
+        .ok_or_else(|| eyre!("not an uploads URL"))?;
+    // Keep the `YYYY/MM/` folders: uploads from different months often share
+    // a file name.
+    if relative
+        .split('/')
+        .any(|segment| segment.is_empty() || segment.starts_with('.'))
+    {
+        return Err(eyre!("invalid upload path"));
+    }
+
+    let bytes = match uploads {
+        Some(dir) => async_fs::read(dir.join(&relative)).await?,
+        None => {
+            let mut client = zenwave::client();
+            let download = client
+                .get(url.to_string())
+                .header("User-Agent", "thought")
+                .bytes();
+            tokio::time::timeout(DOWNLOAD_TIMEOUT, download)
+                .await
+                .map_err(|_| eyre!("download timed out"))?
+                .map_err(|err| eyre!("download failed: {err}"))?
+                .to_vec()
+        }
+    };
+    Ok((relative, bytes))
+}
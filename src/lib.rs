@@ -1,9 +1,29 @@
+pub mod archive;
+pub mod bundle;
 pub mod cache;
+pub mod check;
 pub mod engine;
+pub mod feed;
+pub mod front_matter;
+pub mod glossary;
+pub mod history;
+pub mod import;
+pub mod link;
+pub mod locale;
 pub mod metadata;
+pub mod order;
+pub mod permalink;
 pub mod plugin;
+pub mod redirect;
+pub mod related;
+pub mod relocate;
+pub mod segment;
+pub mod series;
 pub mod serve;
 pub mod slug;
+pub mod stats;
+pub mod timeline;
+pub mod translation_memory;
 pub mod workspace;
 
 pub(crate) mod utils;
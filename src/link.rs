@@ -0,0 +1,394 @@
+//! Internal cross-references between articles.
+//!
+//! A Markdown link whose destination starts with `@` points at another article
+//! rather than at a hardcoded output file:
+//!
+//! ```markdown
+//! [by path](@/guides/a-helpful-guide)
+//! [by slug](@a-helpful-guide#installation)
+//! ```
+//!
+//! Before rendering, these destinations are rewritten into hrefs relative to the
+//! linking article, preferring the target's variant in the same locale.
+
+use std::{collections::HashMap, fmt, ops::Range};
+
+use pulldown_cmark::{Event, LinkType, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    article::{Article, ArticlePreview},
+    locale,
+    utils::percent_decode,
+};
+
+/// How unresolved `@` references are treated at build time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnresolvedLinkPolicy {
+    /// Abort the build.
+    #[default]
+    Error,
+    /// Log a warning and leave the link untouched.
+    Warn,
+}
+
+/// A cross-reference that does not point at any known article.
+#[derive(Debug, Clone, Serialize)]
+pub struct UnresolvedLink {
+    /// Output file of the article containing the link.
+    pub article: String,
+    /// The reference as written, without the leading `@`.
+    pub target: String,
+}
+
+impl fmt::Display for UnresolvedLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: unresolved article link `@{}`",
+            self.article, self.target
+        )
+    }
+}
+
+/// Errors returned when the policy rejects unresolved links.
+#[derive(Debug, thiserror::Error)]
+#[error("{} unresolved article link(s):\n{}", .0.len(), format_unresolved(.0))]
+pub struct UnresolvedLinks(pub Vec<UnresolvedLink>);
+
+fn format_unresolved(links: &[UnresolvedLink]) -> String {
+    links
+        .iter()
+        .map(|link| format!("  {link}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Apply `policy` to the links collected during a build.
+///
+/// # Errors
+/// Returns [`UnresolvedLinks`] when the policy is [`UnresolvedLinkPolicy::Error`]
+/// and at least one link could not be resolved.
+pub fn enforce(
+    policy: UnresolvedLinkPolicy,
+    unresolved: Vec<UnresolvedLink>,
+) -> Result<(), UnresolvedLinks> {
+    if unresolved.is_empty() {
+        return Ok(());
+    }
+    match policy {
+        UnresolvedLinkPolicy::Error => Err(UnresolvedLinks(unresolved)),
+        UnresolvedLinkPolicy::Warn => {
+            for link in &unresolved {
+                tracing::warn!("{link}");
+            }
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct LinkTarget {
+    default_file: Option<String>,
+    locales: HashMap<String, String>,
+}
+
+/// Lookup table from article paths and slugs to their output files.
+#[derive(Debug, Default)]
+pub struct LinkResolver {
+    by_path: HashMap<String, LinkTarget>,
+    by_slug: HashMap<String, Vec<String>>,
+}
+
+impl LinkResolver {
+    /// Build a resolver from every variant known to the build.
+    pub fn new<'a>(previews: impl IntoIterator<Item = &'a ArticlePreview>) -> Self {
+        let mut resolver = Self::default();
+        for preview in previews {
+            let mut segments = preview.category().segments().clone();
+            segments.push(preview.slug().to_string());
+            let path = segments.join("/");
+
+            let target = resolver.by_path.entry(path.clone()).or_default();
+            let file = preview.output_file();
+            if preview.is_default_locale() {
+                target.default_file = Some(file.clone());
+            }
+            target.locales.insert(preview.locale().to_string(), file);
+
+            // An article can be referenced by its file name or its URL slug.
+            let mut slugs = vec![preview.slug()];
+            if preview.url_slug() != preview.slug() {
+                slugs.push(preview.url_slug());
+            }
+            for slug in slugs {
+                let paths = resolver.by_slug.entry(slug.to_string()).or_default();
+                if !paths.contains(&path) {
+                    paths.push(path.clone());
+                }
+            }
+        }
+        resolver
+    }
+
+    /// Path (`category/slug`) of the only article whose file name or URL slug
+    /// is `slug`.
+    #[must_use]
+    pub fn slug_path(&self, slug: &str) -> Option<&str> {
+        match self.by_slug.get(slug).map(Vec::as_slice) {
+            Some([path]) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Output file of the article referenced by `reference` (without `@`),
+    /// in `locale` when such a variant exists.
+    #[must_use]
+    pub fn resolve(&self, reference: &str, locale: &str) -> Option<&str> {
+        let path = if let Some(path) = reference.strip_prefix('/') {
+            path.trim_end_matches('/')
+        } else {
+            self.slug_path(reference)?
+        };
+        let target = self.by_path.get(path)?;
+        locale::fallback_chain(locale, None)
+            .iter()
+            .find_map(|candidate| target.locales.get(candidate))
+            .or(target.default_file.as_ref())
+            .map(String::as_str)
+    }
+
+    /// Rewrite every `@` reference in the article's Markdown in place.
+    ///
+    /// Returns the references that could not be resolved; those are left untouched.
+    pub fn rewrite(&self, article: &mut Article) -> Vec<UnresolvedLink> {
+        let from_file = article.output_file();
+        let locale = article.locale().to_string();
+        let mut unresolved = Vec::new();
+        let mut replacements: Vec<(Range<usize>, String)> = Vec::new();
+
+        for (range, reference) in references(article.content()) {
+            let (path, fragment) = match reference.split_once('#') {
+                Some((path, fragment)) => (path, Some(fragment)),
+                None => (reference.as_str(), None),
+            };
+            match self.resolve(path, &locale) {
+                Some(file) => {
+                    let mut href = relative_href(&from_file, file);
+                    if let Some(fragment) = fragment {
+                        href.push('#');
+                        href.push_str(fragment);
+                    }
+                    replacements.push((range, href));
+                }
+                None => unresolved.push(UnresolvedLink {
+                    article: from_file.clone(),
+                    target: reference,
+                }),
+            }
+        }
+
+        if !replacements.is_empty() {
+            article.content = splice(&article.content, replacements);
+        }
+        unresolved
+    }
+}
+
+/// Point `@` references for which `matches` returns true at `replacement`
+/// (a reference without `@`), keeping any `#fragment`.
+///
+/// Returns `None` when nothing in `content` had to change.
+pub fn retarget(
+    content: &str,
+    matches: impl Fn(&str) -> bool,
+    replacement: &str,
+) -> Option<String> {
+    let replacements = references(content)
+        .into_iter()
+        .filter_map(|(range, reference)| {
+            let (path, fragment) = match reference.split_once('#') {
+                Some((path, fragment)) => (path, Some(fragment)),
+                None => (reference.as_str(), None),
+            };
+            if !matches(path) {
+                return None;
+            }
+            let mut dest = format!("@{replacement}");
+            if let Some(fragment) = fragment {
+                dest.push('#');
+                dest.push_str(fragment);
+            }
+            Some((range, dest))
+        })
+        .collect::<Vec<_>>();
+    if replacements.is_empty() {
+        None
+    } else {
+        Some(splice(content, replacements))
+    }
+}
+
+/// Byte ranges of every `@` link destination in `content`, with the reference
+/// that follows the `@`.
+fn references(content: &str) -> Vec<(Range<usize>, String)> {
+    destinations(content, false, reference)
+}
+
+/// Byte ranges of the link destinations in `content` (and image sources, with
+/// `images`) for which `select` returns a value, given the parsed destination.
+pub(crate) fn destinations<T>(
+    content: &str,
+    images: bool,
+    select: impl Fn(&str) -> Option<T>,
+) -> Vec<(Range<usize>, T)> {
+    let mut found = Vec::new();
+    let mut events = Parser::new(content).into_offset_iter();
+    // For each link being read, its selected value (for inline links) and
+    // where its text ends so far.
+    let mut open: Vec<(Option<T>, usize)> = Vec::new();
+    for (event, range) in events.by_ref() {
+        // Without `images`, an image is read as part of its enclosing link's text.
+        let skipped = !images
+            && matches!(
+                event,
+                Event::Start(Tag::Image { .. }) | Event::End(TagEnd::Image)
+            );
+        match event {
+            Event::Start(
+                Tag::Link {
+                    link_type,
+                    dest_url,
+                    ..
+                }
+                | Tag::Image {
+                    link_type,
+                    dest_url,
+                    ..
+                },
+            ) if !skipped => {
+                let selected = (link_type == LinkType::Inline)
+                    .then(|| select(&dest_url))
+                    .flatten();
+                open.push((selected, range.start + 1));
+            }
+            Event::End(TagEnd::Link | TagEnd::Image) if !skipped => {
+                let Some((selected, text_end)) = open.pop() else {
+                    continue;
+                };
+                if let Some((_, outer_end)) = open.last_mut() {
+                    *outer_end = (*outer_end).max(range.end);
+                }
+                let Some(selected) = selected else {
+                    continue;
+                };
+                // The destination follows the `](` that closes the link text,
+                // so a title or text repeating it is never mistaken for it.
+                let Some(close) = content[text_end..range.end].find("](") else {
+                    continue;
+                };
+                if let Some(dest) = destination(content, text_end + close + 2) {
+                    found.push((dest, selected));
+                }
+            }
+            _ => {
+                if let Some((_, text_end)) = open.last_mut() {
+                    *text_end = (*text_end).max(range.end);
+                }
+            }
+        }
+    }
+
+    // Reference-style links carry their destination in a definition.
+    for (_, definition) in events.reference_definitions().iter() {
+        let Some(selected) = select(&definition.dest) else {
+            continue;
+        };
+        let span = definition.span.clone();
+        if let Some(colon) = label_end(&content[span.clone()])
+            && let Some(dest) = destination(content, span.start + colon + 1)
+        {
+            found.push((dest, selected));
+        }
+    }
+    found
+}
+
+/// The reference after the `@` of a parsed destination, with its path
+/// percent-decoded and its `#fragment` kept as written.
+fn reference(dest: &str) -> Option<String> {
+    let reference = dest.strip_prefix('@')?;
+    Some(match reference.split_once('#') {
+        Some((path, fragment)) => format!("{}#{fragment}", percent_decode(path)),
+        None => percent_decode(reference),
+    })
+}
+
+/// Offset of the `:` after the `[label]` of a reference definition.
+fn label_end(definition: &str) -> Option<usize> {
+    let mut chars = definition.char_indices().skip_while(|&(_, c)| c != '[');
+    chars.next()?;
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            ']' => {
+                return definition[index + 1..]
+                    .starts_with(':')
+                    .then_some(index + 1);
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Byte range of the link destination starting at or after `start`, without
+/// the angle brackets of a `<…>` destination.
+fn destination(content: &str, start: usize) -> Option<Range<usize>> {
+    let rest = &content[start..];
+    let mut start = start + (rest.len() - rest.trim_start().len());
+    let angled = content[start..].starts_with('<');
+    if angled {
+        start += 1;
+    }
+    let mut depth = 0_usize;
+    let mut chars = content[start..].char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '>' if angled => return Some(start..start + index),
+            _ if angled => {}
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(start..start + index),
+            ')' => depth -= 1,
+            c if c.is_whitespace() => return Some(start..start + index),
+            _ => {}
+        }
+    }
+    (!angled).then_some(start..content.len())
+}
+
+pub(crate) fn splice(content: &str, mut replacements: Vec<(Range<usize>, String)>) -> String {
+    replacements.sort_by_key(|(range, _)| range.start);
+    let mut rewritten = String::with_capacity(content.len());
+    let mut cursor = 0;
+    for (range, text) in replacements {
+        rewritten.push_str(&content[cursor..range.start]);
+        rewritten.push_str(&text);
+        cursor = range.end;
+    }
+    rewritten.push_str(&content[cursor..]);
+    rewritten
+}
+
+/// Href to `to_file` from a page emitted at `from_file`, both relative to the site root.
+#[must_use]
+pub fn relative_href(from_file: &str, to_file: &str) -> String {
+    let depth = from_file.matches('/').count();
+    format!("{}{}", "../".repeat(depth), to_file)
+}
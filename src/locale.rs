@@ -0,0 +1,423 @@
+//! Locales and locale-aware site structure.
+//!
+//! Locales are BCP 47 language tags in canonical case (`zh-Hant-TW`, `en-US`).
+//! Each has a script, explicit in the tag or implied by its language and
+//! region, and the script decides the text direction.
+//! A variant that is missing in a locale is looked up along its fallback
+//! chain: `zh-Hant-TW`, then `zh-Hant`, then `zh`, then the article's default
+//! locale.
+//!
+//! Besides the root `index.html`, every locale with at least one article or
+//! translation gets `<locale>/index.html`. It lists every article, using the
+//! variant in that locale where one exists and the default variant otherwise.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use color_eyre::eyre::{self, eyre};
+
+use crate::{article::ArticlePreview, order};
+
+/// Two-letter ISO 639-1 language codes, sorted.
+const ISO_639_1: &[&str] = &[
+    "aa", "ab", "ae", "af", "ak", "am", "an", "ar", "as", "av", "ay", "az", "ba", "be", "bg", "bh",
+    "bi", "bm", "bn", "bo", "br", "bs", "ca", "ce", "ch", "co", "cr", "cs", "cu", "cv", "cy", "da",
+    "de", "dv", "dz", "ee", "el", "en", "eo", "es", "et", "eu", "fa", "ff", "fi", "fj", "fo", "fr",
+    "fy", "ga", "gd", "gl", "gn", "gu", "gv", "ha", "he", "hi", "ho", "hr", "ht", "hu", "hy", "hz",
+    "ia", "id", "ie", "ig", "ii", "ik", "io", "is", "it", "iu", "ja", "jv", "ka", "kg", "ki", "kj",
+    "kk", "kl", "km", "kn", "ko", "kr", "ks", "ku", "kv", "kw", "ky", "la", "lb", "lg", "li", "ln",
+    "lo", "lt", "lu", "lv", "mg", "mh", "mi", "mk", "ml", "mn", "mr", "ms", "mt", "my", "na", "nb",
+    "nd", "ne", "ng", "nl", "nn", "no", "nr", "nv", "ny", "oc", "oj", "om", "or", "os", "pa", "pi",
+    "pl", "ps", "pt", "qu", "rm", "rn", "ro", "ru", "rw", "sa", "sc", "sd", "se", "sg", "si", "sk",
+    "sl", "sm", "sn", "so", "sq", "sr", "ss", "st", "su", "sv", "sw", "ta", "te", "tg", "th", "ti",
+    "tk", "tl", "tn", "to", "tr", "ts", "tt", "tw", "ty", "ug", "uk", "ur", "uz", "ve", "vi", "vo",
+    "wa", "wo", "xh", "yi", "yo", "za", "zh", "zu",
+];
+
+/// Index pages for every locale in the workspace.
+#[derive(Debug, Default, Clone)]
+pub struct LocaleIndex {
+    pages: BTreeMap<String, Vec<ArticlePreview>>,
+}
+
+impl LocaleIndex {
+    /// Build the per-locale listings from the previews of every variant.
+    ///
+    /// # Errors
+    /// Returns an error if a locale index would overwrite an article.
+    pub fn new<'a>(previews: impl IntoIterator<Item = &'a ArticlePreview>) -> eyre::Result<Self> {
+        let previews = previews.into_iter().collect::<Vec<_>>();
+        let mut defaults = previews
+            .iter()
+            .filter(|preview| preview.is_default_locale() && preview.is_published())
+            .map(|preview| (*preview).clone())
+            .collect::<Vec<_>>();
+        order::sort_previews(&mut defaults);
+
+        let variants = previews
+            .iter()
+            .map(|preview| (preview.output_file(), *preview))
+            .collect::<HashMap<_, _>>();
+
+        let locales = previews
+            .iter()
+            .map(|preview| preview.locale())
+            .filter(|locale| is_path_safe(locale))
+            .collect::<HashSet<_>>();
+        let pages = locales
+            .into_iter()
+            .map(|locale| {
+                let listing = defaults
+                    .iter()
+                    .map(|preview| {
+                        preview
+                            .translation(locale)
+                            .and_then(|translation| variants.get(translation.output_file()))
+                            .map_or_else(|| preview.clone(), |variant| (*variant).clone())
+                    })
+                    .collect();
+                (locale.to_string(), listing)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let outputs = previews
+            .iter()
+            .map(|preview| preview.output_file())
+            .collect::<HashSet<_>>();
+        if let Some(locale) = pages
+            .keys()
+            .find(|locale| outputs.contains(&index_file(locale)))
+        {
+            return Err(eyre!(
+                "index page for locale `{locale}` collides with the article at `{}`",
+                index_file(locale)
+            ));
+        }
+        Ok(Self { pages })
+    }
+
+    /// Locales with an index page, each with its output file and listing.
+    pub fn pages(&self) -> impl Iterator<Item = (&str, String, &[ArticlePreview])> {
+        self.pages
+            .iter()
+            .map(|(locale, listing)| (locale.as_str(), index_file(locale), listing.as_slice()))
+    }
+
+    /// The locale and listing of the index emitted at `output_file`.
+    #[must_use]
+    pub fn page(&self, output_file: &str) -> Option<(&str, &[ArticlePreview])> {
+        let locale = output_file.strip_suffix("/index.html")?;
+        self.pages
+            .get_key_value(locale)
+            .map(|(locale, listing)| (locale.as_str(), listing.as_slice()))
+    }
+}
+
+/// Locale of a variant file named `stem` (`ja`, `zh_cn`), in canonical case.
+///
+/// Not every short file name is meant as a language tag (`faq.md`, `new.md`),
+/// so unlike [`canonicalize`] the language must be a two-letter ISO 639-1
+/// code, or the tag or its language must be listed in `configured` (the
+/// `locales` of `Thought.toml`), as languages like `yue` or `fil` need to be.
+#[must_use]
+pub fn from_file_stem(stem: &str, configured: &[String]) -> Option<String> {
+    let locale = canonicalize(stem)?;
+    let language = locale.split('-').next().unwrap_or_default();
+    let listed = configured
+        .iter()
+        .filter_map(|tag| canonicalize(tag))
+        .any(|tag| tag == locale || tag == language);
+    (listed || ISO_639_1.binary_search(&language).is_ok()).then_some(locale)
+}
+
+/// Parse a BCP 47 language tag and return it in canonical case: lowercase
+/// language, titlecase script, uppercase region, lowercase everything else.
+/// `_` is accepted as a separator.
+///
+/// Returns `None` when `tag` is not a well-formed language tag, such as the
+/// `notes` in `notes.md`.
+#[must_use]
+pub fn canonicalize(tag: &str) -> Option<String> {
+    let mut subtags = tag.trim().split(['-', '_']).peekable();
+    let language = subtags.next()?;
+    if !(2..=3).contains(&language.len()) || !is_alpha(language) {
+        return None;
+    }
+    let mut canonical = vec![language.to_ascii_lowercase()];
+
+    for _ in 0..3 {
+        match subtags.next_if(|extlang| extlang.len() == 3 && is_alpha(extlang)) {
+            Some(extlang) => canonical.push(extlang.to_ascii_lowercase()),
+            None => break,
+        }
+    }
+    if let Some(script) = subtags.next_if(|script| script.len() == 4 && is_alpha(script)) {
+        let (first, rest) = script.split_at(1);
+        canonical.push(first.to_ascii_uppercase() + &rest.to_ascii_lowercase());
+    }
+    if let Some(region) = subtags.next_if(|region| {
+        (region.len() == 2 && is_alpha(region))
+            || (region.len() == 3 && region.bytes().all(|b| b.is_ascii_digit()))
+    }) {
+        canonical.push(region.to_ascii_uppercase());
+    }
+    while let Some(variant) = subtags.next_if(|variant| is_variant(variant)) {
+        canonical.push(variant.to_ascii_lowercase());
+    }
+
+    // Extensions (`u-ca-buddhist`) and private use (`x-pirate`).
+    while let Some(singleton) = subtags.next() {
+        if singleton.len() != 1 || !is_alphanumeric(singleton) {
+            return None;
+        }
+        let private = singleton.eq_ignore_ascii_case("x");
+        let min_len = if private { 1 } else { 2 };
+        canonical.push(singleton.to_ascii_lowercase());
+        let mut count = 0;
+        while let Some(subtag) = subtags
+            .next_if(|subtag| (min_len..=8).contains(&subtag.len()) && is_alphanumeric(subtag))
+        {
+            canonical.push(subtag.to_ascii_lowercase());
+            count += 1;
+        }
+        if count == 0 {
+            return None;
+        }
+    }
+    Some(canonical.join("-"))
+}
+
+/// Locales to try, in order, for a variant in `locale`, ending with
+/// `default_locale` when given.
+#[must_use]
+pub fn fallback_chain(locale: &str, default_locale: Option<&str>) -> Vec<String> {
+    let mut chain = Vec::new();
+    let mut subtags = locale.split('-').collect::<Vec<_>>();
+    while !subtags.is_empty() {
+        chain.push(subtags.join("-"));
+        subtags.pop();
+        // An extension singleton is meaningless without its subtags.
+        while subtags.last().is_some_and(|subtag| subtag.len() == 1) {
+            subtags.pop();
+        }
+    }
+    if let Some(default_locale) = default_locale
+        && !chain.iter().any(|candidate| candidate == default_locale)
+    {
+        chain.push(default_locale.to_string());
+    }
+    chain
+}
+
+/// The first of `candidates` along the fallback chain of `locale`.
+pub fn best_match<'a, T>(
+    locale: &str,
+    default_locale: &str,
+    candidates: &'a [T],
+    locale_of: impl Fn(&T) -> &str,
+) -> Option<&'a T> {
+    fallback_chain(locale, Some(default_locale))
+        .iter()
+        .find_map(|wanted| {
+            candidates
+                .iter()
+                .find(|candidate| locale_of(candidate) == wanted)
+        })
+}
+
+/// Direction in which a locale's script is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextDirection {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+impl TextDirection {
+    /// Value for the HTML `dir` attribute.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Ltr => "ltr",
+            Self::Rtl => "rtl",
+        }
+    }
+}
+
+/// ISO 15924 code of the script `locale` is written in: the script subtag
+/// when present, otherwise the usual script of its language and region,
+/// assuming Latin for languages not listed here.
+#[must_use]
+pub fn script(locale: &str) -> String {
+    let mut subtags = locale.split(['-', '_']);
+    let language = subtags.next().unwrap_or_default().to_ascii_lowercase();
+    let mut region = None;
+    for subtag in subtags {
+        match subtag.len() {
+            4 if is_alpha(subtag) => {
+                let (first, rest) = subtag.split_at(1);
+                return first.to_ascii_uppercase() + &rest.to_ascii_lowercase();
+            }
+            2 if is_alpha(subtag) => region = Some(subtag.to_ascii_uppercase()),
+            1 => break,
+            _ => {}
+        }
+    }
+    likely_script(&language, region.as_deref()).to_string()
+}
+
+/// Writing direction of `locale`.
+#[must_use]
+pub fn direction(locale: &str) -> TextDirection {
+    match script(locale).as_str() {
+        "Adlm" | "Arab" | "Hebr" | "Mand" | "Nkoo" | "Rohg" | "Samr" | "Syrc" | "Thaa" | "Yezi" => {
+            TextDirection::Rtl
+        }
+        _ => TextDirection::Ltr,
+    }
+}
+
+fn likely_script(language: &str, region: Option<&str>) -> &'static str {
+    match (language, region) {
+        ("zh", Some("TW" | "HK" | "MO")) => "Hant",
+        ("zh", _) => "Hans",
+        ("pa", Some("PK")) => "Arab",
+        ("ja", _) => "Jpan",
+        ("ko", _) => "Kore",
+        ("ar" | "fa" | "ur" | "ps" | "sd" | "ug" | "ckb" | "ks" | "arz" | "prs", _) => "Arab",
+        ("he" | "iw" | "yi" | "lad", _) => "Hebr",
+        ("dv", _) => "Thaa",
+        ("syr", _) => "Syrc",
+        ("nqo", _) => "Nkoo",
+        (
+            "ru" | "uk" | "be" | "bg" | "mk" | "sr" | "kk" | "ky" | "tg" | "mn" | "ba" | "tt"
+            | "ce" | "cv" | "os" | "ab",
+            _,
+        ) => "Cyrl",
+        ("el", _) => "Grek",
+        ("hy", _) => "Armn",
+        ("ka", _) => "Geor",
+        ("hi" | "mr" | "ne" | "sa" | "kok" | "mai" | "bho" | "new", _) => "Deva",
+        ("bn" | "as", _) => "Beng",
+        ("pa", _) => "Guru",
+        ("gu", _) => "Gujr",
+        ("or", _) => "Orya",
+        ("ta", _) => "Taml",
+        ("te", _) => "Telu",
+        ("kn", _) => "Knda",
+        ("ml", _) => "Mlym",
+        ("si", _) => "Sinh",
+        ("th", _) => "Thai",
+        ("lo", _) => "Laoo",
+        ("my", _) => "Mymr",
+        ("km", _) => "Khmr",
+        ("bo" | "dz", _) => "Tibt",
+        ("am" | "ti", _) => "Ethi",
+        _ => "Latn",
+    }
+}
+
+/// Output file of the index page for `locale`.
+#[must_use]
+pub fn index_file(locale: &str) -> String {
+    format!("{locale}/index.html")
+}
+
+fn is_alpha(subtag: &str) -> bool {
+    subtag.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn is_alphanumeric(subtag: &str) -> bool {
+    subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+/// Variant subtags are 5 to 8 characters, or 4 starting with a digit (`1996`).
+fn is_variant(subtag: &str) -> bool {
+    is_alphanumeric(subtag)
+        && match subtag.len() {
+            5..=8 => true,
+            4 => subtag.as_bytes()[0].is_ascii_digit(),
+            _ => false,
+        }
+}
+
+/// Locales become directory names, so only tag-like values are used.
+fn is_path_safe(locale: &str) -> bool {
+    !locale.is_empty()
+        && locale
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonicalizes_case_and_separators() {
+        assert_eq!(canonicalize("EN-us").as_deref(), Some("en-US"));
+        assert_eq!(canonicalize("zh_hant_tw").as_deref(), Some("zh-Hant-TW"));
+        assert_eq!(canonicalize("SR-latn").as_deref(), Some("sr-Latn"));
+        assert_eq!(canonicalize(" es-419 ").as_deref(), Some("es-419"));
+        assert_eq!(
+            canonicalize("de-DE-1996-U-CO-Phonebk").as_deref(),
+            Some("de-DE-1996-u-co-phonebk")
+        );
+        assert_eq!(canonicalize("en-X-Pirate").as_deref(), Some("en-x-pirate"));
+    }
+
+    #[test]
+    fn rejects_malformed_tags() {
+        for tag in [
+            "",
+            "README",
+            "notes",
+            "e",
+            "en-",
+            "en--US",
+            "de-u",
+            "en-Latn-US-?",
+        ] {
+            assert_eq!(canonicalize(tag), None, "{tag}");
+        }
+    }
+
+    #[test]
+    fn reads_locales_from_file_stems() {
+        assert_eq!(from_file_stem("ja", &[]).as_deref(), Some("ja"));
+        assert_eq!(from_file_stem("zh_cn", &[]).as_deref(), Some("zh-CN"));
+        assert_eq!(from_file_stem("ZH-HANT", &[]).as_deref(), Some("zh-Hant"));
+        for stem in ["README", "notes", "faq", "new", "yue"] {
+            assert_eq!(from_file_stem(stem, &[]), None, "{stem}");
+        }
+
+        let configured = ["yue".to_string(), "FIL".to_string()];
+        assert_eq!(from_file_stem("yue", &configured).as_deref(), Some("yue"));
+        assert_eq!(
+            from_file_stem("fil_ph", &configured).as_deref(),
+            Some("fil-PH")
+        );
+        assert_eq!(from_file_stem("faq", &configured), None);
+    }
+
+    #[test]
+    fn falls_back_through_shorter_tags_to_the_default() {
+        assert_eq!(
+            fallback_chain("zh-Hant-TW", Some("en")),
+            ["zh-Hant-TW", "zh-Hant", "zh", "en"]
+        );
+        assert_eq!(fallback_chain("ja-JP", Some("ja")), ["ja-JP", "ja"]);
+        assert_eq!(fallback_chain("en-x-pirate", None), ["en-x-pirate", "en"]);
+    }
+
+    #[test]
+    fn matches_the_closest_available_locale() {
+        let candidates = ["en", "zh", "zh-Hant"];
+        let best = |locale, default_locale| {
+            best_match(locale, default_locale, &candidates, |candidate| candidate).copied()
+        };
+        assert_eq!(best("zh-Hant-TW", "en"), Some("zh-Hant"));
+        assert_eq!(best("zh-Hans-CN", "en"), Some("zh"));
+        assert_eq!(best("fr-CA", "en"), Some("en"));
+        assert_eq!(best("fr-CA", "de"), None);
+    }
+}
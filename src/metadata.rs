@@ -2,7 +2,7 @@
 //! This module provides the data structures and traits for working with article and category metadata.
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fs,
     path::{Path, PathBuf},
     str::FromStr,
@@ -11,7 +11,11 @@ use std::{
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use time::OffsetDateTime;
 
-use crate::utils::{read_to_string, write};
+use crate::{
+    link::UnresolvedLinkPolicy,
+    permalink::Permalink,
+    utils::{read_to_string, write},
+};
 
 /// Metadata for a category
 ///
@@ -29,10 +33,13 @@ pub struct CategoryMetadata {
     pub(crate) name: String,
     #[serde(default)]
     pub(crate) description: String,
+    #[serde(default, skip_serializing_if = "CategorySort::is_default")]
+    pub(crate) sort: CategorySort,
 }
 
 /// Metadata for an article
-/// It always locates in an `Article.toml` file inside the article directory.
+/// It usually locates in an `Article.toml` file inside the article directory;
+/// a front matter block in `article.md` overrides or replaces it.
 /// ```plain
 /// /articles
 ///  /programming
@@ -41,14 +48,60 @@ pub struct CategoryMetadata {
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ArticleMetadata {
+    // No field may use `skip_serializing_if`: the render cache stores metadata
+    // with bincode, which cannot read back skipped fields.
     #[serde(with = "time::serde::rfc3339")]
     pub(crate) created: OffsetDateTime,
+    #[serde(default, with = "rfc3339_option")]
+    pub(crate) updated: Option<OffsetDateTime>,
     #[serde(default)]
     pub(crate) tags: Vec<String>,
     pub(crate) author: String,
     pub(crate) description: Option<String>,
     #[serde(default)]
     pub(crate) lang: Option<String>,
+    #[serde(default)]
+    pub(crate) slug: Option<String>,
+    #[serde(default)]
+    pub(crate) aliases: Vec<String>,
+    #[serde(default)]
+    pub(crate) draft: bool,
+    #[serde(default)]
+    pub(crate) series: Option<SeriesMembership>,
+    #[serde(default)]
+    pub(crate) pinned: bool,
+    #[serde(default)]
+    pub(crate) weight: Option<i32>,
+}
+
+/// Place of an article in a multi-part series, written as
+/// `series = { name = "…", part = 1 }`.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SeriesMembership {
+    pub(crate) name: String,
+    pub(crate) part: u32,
+}
+
+impl SeriesMembership {
+    /// Membership of part `part` in the series called `name`.
+    pub fn new(name: impl Into<String>, part: u32) -> Self {
+        Self {
+            name: name.into(),
+            part,
+        }
+    }
+
+    /// Name of the series, shared by all of its parts.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Position of the article within the series.
+    #[must_use]
+    pub const fn part(&self) -> u32 {
+        self.part
+    }
 }
 
 impl ArticleMetadata {
@@ -56,10 +109,17 @@ impl ArticleMetadata {
     pub fn new(author: impl Into<String>) -> Self {
         Self {
             created: OffsetDateTime::now_utc(),
+            updated: None,
             author: author.into(),
             tags: Vec::new(),
             description: None,
             lang: None,
+            slug: None,
+            aliases: Vec::new(),
+            draft: false,
+            series: None,
+            pinned: false,
+            weight: None,
         }
     }
 
@@ -84,6 +144,22 @@ impl ArticleMetadata {
         self.created
     }
 
+    /// Set the creation time of the article
+    pub const fn set_created(&mut self, created: OffsetDateTime) {
+        self.created = created;
+    }
+
+    /// Time of the last meaningful revision, if known.
+    #[must_use]
+    pub const fn updated(&self) -> Option<OffsetDateTime> {
+        self.updated
+    }
+
+    /// Set the time of the last revision of the article
+    pub const fn set_updated(&mut self, updated: OffsetDateTime) {
+        self.updated = Some(updated);
+    }
+
     /// Get the author of the article
     #[must_use]
     pub fn author(&self) -> &str {
@@ -116,6 +192,75 @@ impl ArticleMetadata {
     pub fn set_lang(&mut self, lang: impl Into<String>) {
         self.lang = Some(lang.into());
     }
+
+    /// Slug used in the article's URL instead of its file or directory name.
+    #[must_use]
+    pub fn slug(&self) -> Option<&str> {
+        self.slug.as_deref()
+    }
+
+    /// Override the slug used in the article's URL.
+    pub fn set_slug(&mut self, slug: impl Into<String>) {
+        self.slug = Some(slug.into());
+    }
+
+    /// Former URLs of the article that should redirect to it.
+    #[must_use]
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    /// Whether the article is marked as a draft.
+    #[must_use]
+    pub const fn is_draft(&self) -> bool {
+        self.draft
+    }
+
+    /// Mark the article as a draft, or as ready.
+    pub const fn set_draft(&mut self, draft: bool) {
+        self.draft = draft;
+    }
+
+    /// Series the article belongs to, if any.
+    #[must_use]
+    pub const fn series(&self) -> Option<&SeriesMembership> {
+        self.series.as_ref()
+    }
+
+    /// Make the article a part of a series.
+    pub fn set_series(&mut self, series: SeriesMembership) {
+        self.series = Some(series);
+    }
+
+    /// Whether the article is listed ahead of unpinned ones.
+    #[must_use]
+    pub const fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+
+    /// Pin the article to the top of listings, or unpin it.
+    pub const fn set_pinned(&mut self, pinned: bool) {
+        self.pinned = pinned;
+    }
+
+    /// Position among weighted articles; lower weights are listed first.
+    #[must_use]
+    pub const fn weight(&self) -> Option<i32> {
+        self.weight
+    }
+
+    /// Set the position among weighted articles.
+    pub const fn set_weight(&mut self, weight: i32) {
+        self.weight = Some(weight);
+    }
+
+    /// Record a former URL of the article.
+    pub fn add_alias(&mut self, alias: impl Into<String>) {
+        let alias = alias.into();
+        if !self.aliases.contains(&alias) {
+            self.aliases.push(alias);
+        }
+    }
 }
 
 impl CategoryMetadata {
@@ -125,6 +270,7 @@ impl CategoryMetadata {
             created: OffsetDateTime::now_utc(),
             name: name.into(),
             description: String::new(),
+            sort: CategorySort::default(),
         }
     }
 
@@ -150,6 +296,37 @@ impl CategoryMetadata {
     pub fn set_description(&mut self, description: impl Into<String>) {
         self.description = description.into();
     }
+
+    /// Order of the category's articles in listings.
+    #[must_use]
+    pub const fn sort(&self) -> CategorySort {
+        self.sort
+    }
+}
+
+/// How a category orders its articles, set with `sort` in `Category.toml`.
+/// Pinned articles come first in every order.
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, Hash, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum CategorySort {
+    /// Weighted articles by ascending weight, then the rest newest first.
+    #[default]
+    Weight,
+    /// Newest first, ignoring weights.
+    Newest,
+    /// Oldest first, ignoring weights.
+    Oldest,
+    /// Alphabetically by title.
+    Title,
+}
+
+impl CategorySort {
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 /// Metadata for a workspace (your entire blog)
@@ -162,6 +339,22 @@ pub struct WorkspaceManifest {
     plugins: PluginRegistry,
     #[serde(default)]
     translation: Option<TranslationConfig>,
+    #[serde(default)]
+    links: LinkConfig,
+    #[serde(default)]
+    permalink: Permalink,
+    #[serde(default)]
+    redirects: RedirectConfig,
+    #[serde(default)]
+    base_url: Option<String>,
+    #[serde(default)]
+    dates: DateConfig,
+    #[serde(default)]
+    related: RelatedConfig,
+    /// Locales whose files are recognised as variants besides ISO 639-1
+    /// languages, see [`crate::locale::from_file_stem`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    locales: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -207,11 +400,21 @@ pub struct PluginEntry {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct TranslationConfig {
-    /// Model identifiers to try with OpenRouter (fall back in order).
+    /// Service translations are requested from.
+    pub provider: TranslationProvider,
+    /// Endpoint of an `openai` provider, e.g. `http://localhost:11434/v1`
+    /// for Ollama or `http://localhost:8080/v1` for llama.cpp.
+    pub base_url: Option<String>,
+    /// Environment variable holding the API key, overriding the provider's
+    /// default (`OPENROUTER_API_KEY` or `OPENAI_API_KEY`).
+    pub api_key_env: Option<String>,
+    /// Model identifiers to try with the provider (fall back in order).
     pub models: Vec<String>,
     /// Backward compat: single model field maps into `models`.
     #[serde(default)]
     pub model: Option<String>,
+    /// Words replaced by the `dictionary` provider.
+    pub dictionary: BTreeMap<String, String>,
     /// Maximum number of concurrent translation requests.
     pub max_concurrency: usize,
     /// Number of times to retry a failed translation.
@@ -221,14 +424,77 @@ pub struct TranslationConfig {
 impl Default for TranslationConfig {
     fn default() -> Self {
         Self {
+            provider: TranslationProvider::default(),
+            base_url: None,
+            api_key_env: None,
             models: default_models(),
             model: None,
+            dictionary: BTreeMap::new(),
             max_concurrency: 4,
             max_retries: 2,
         }
     }
 }
 
+/// Service `thought translate` sends articles to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranslationProvider {
+    /// OpenRouter, authenticated with `OPENROUTER_API_KEY`.
+    #[default]
+    OpenRouter,
+    /// Any OpenAI-compatible chat completions API at `base_url`, such as a
+    /// local llama.cpp or Ollama server. Defaults to OpenAI itself.
+    OpenAi,
+    /// Offline and deterministic: replaces the words listed in `dictionary`
+    /// and keeps everything else, for tests and CI.
+    #[serde(alias = "echo")]
+    Dictionary,
+}
+
+/// Settings for internal `@` cross-references and `thought check links`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinkConfig {
+    /// What `thought generate` does when a reference cannot be resolved.
+    pub unresolved: UnresolvedLinkPolicy,
+    /// Hosts (optionally `host:port`) that `thought check links --external` may probe.
+    pub external_allowlist: Vec<String>,
+}
+
+/// Where article dates come from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DateConfig {
+    /// Take `created` from the first commit touching an article's files and
+    /// `updated` from the last one, for articles that are committed.
+    pub git: bool,
+}
+
+/// Related articles listed under each article.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RelatedConfig {
+    /// How many related articles each article receives; `0` turns them off.
+    pub count: usize,
+}
+
+impl Default for RelatedConfig {
+    fn default() -> Self {
+        Self { count: 5 }
+    }
+}
+
+/// Server-side redirect files emitted next to the meta-refresh pages for aliases.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedirectConfig {
+    /// Write a Netlify-style `_redirects` file.
+    pub netlify: bool,
+    /// Write `redirects.nginx.conf` with an nginx `map` block.
+    pub nginx: bool,
+}
+
 fn default_models() -> Vec<String> {
     vec![
         "gpt-4o-mini".to_string(),
@@ -312,6 +578,13 @@ impl WorkspaceManifest {
             owner: owner.into(),
             plugins,
             translation: None,
+            links: LinkConfig::default(),
+            permalink: Permalink::default(),
+            redirects: RedirectConfig::default(),
+            base_url: None,
+            dates: DateConfig::default(),
+            related: RelatedConfig::default(),
+            locales: Vec::new(),
         }
     }
 
@@ -338,6 +611,52 @@ impl WorkspaceManifest {
         self.translation = Some(config);
     }
 
+    /// Settings for internal cross-references.
+    #[must_use]
+    pub const fn link_config(&self) -> &LinkConfig {
+        &self.links
+    }
+
+    /// Extra redirect files emitted for article aliases.
+    #[must_use]
+    pub const fn redirect_config(&self) -> &RedirectConfig {
+        &self.redirects
+    }
+
+    /// Where article dates come from.
+    #[must_use]
+    pub const fn date_config(&self) -> &DateConfig {
+        &self.dates
+    }
+
+    /// How related articles are chosen.
+    #[must_use]
+    pub const fn related_config(&self) -> &RelatedConfig {
+        &self.related
+    }
+
+    /// Extra locales recognised in variant file names, such as `yue`.
+    #[must_use]
+    pub fn locales(&self) -> &[String] {
+        &self.locales
+    }
+
+    /// Public URL of the site (e.g. `https://example.com/blog`), needed for
+    /// the feed and sitemap.
+    #[must_use]
+    pub fn base_url(&self) -> Option<&str> {
+        self.base_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+    }
+
+    /// Pattern deciding where articles are emitted.
+    #[must_use]
+    pub const fn permalink(&self) -> &Permalink {
+        &self.permalink
+    }
+
     /// Get the name of the workspace
     #[must_use]
     pub const fn name(&self) -> &str {
@@ -480,3 +799,31 @@ impl MetadataExt for CategoryMetadata {}
 impl MetadataExt for ArticleMetadata {}
 impl MetadataExt for WorkspaceManifest {}
 impl MetadataExt for PluginManifest {}
+
+/// RFC 3339 for an optional date, read back through `Option<String>`.
+///
+/// `time::serde::rfc3339::option` reads a present value with
+/// `deserialize_any`, which bincode (used by the render cache) does not support.
+mod rfc3339_option {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+    use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<OffsetDateTime>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value
+            .map(|datetime| datetime.format(&Rfc3339))
+            .transpose()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<OffsetDateTime>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|text| OffsetDateTime::parse(&text, &Rfc3339).map_err(D::Error::custom))
+            .transpose()
+    }
+}
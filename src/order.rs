@@ -0,0 +1,64 @@
+//! Listing order of articles.
+//!
+//! Listings are ordered deterministically: pinned articles first, then
+//! weighted articles by ascending `weight`, then the rest newest first, with
+//! ties broken by title and output file. A category whose `Category.toml` sets
+//! another `sort` keeps the positions its articles hold in that order, but
+//! fills them in its own order.
+
+use std::{cmp::Ordering, collections::HashMap};
+
+use crate::{article::ArticlePreview, metadata::CategorySort};
+
+/// Sort `previews` for a listing such as the index.
+pub fn sort_previews(previews: &mut [ArticlePreview]) {
+    previews.sort_by(|a, b| compare(CategorySort::Weight, a, b));
+
+    let mut slots: HashMap<&[String], Vec<usize>> = HashMap::new();
+    for (index, preview) in previews.iter().enumerate() {
+        let category = preview.category();
+        if category.metadata().sort() != CategorySort::Weight {
+            slots
+                .entry(category.segments().as_slice())
+                .or_default()
+                .push(index);
+        }
+    }
+    let slots = slots.into_values().collect::<Vec<_>>();
+
+    for indices in slots {
+        let mut members = indices
+            .iter()
+            .map(|&index| previews[index].clone())
+            .collect::<Vec<_>>();
+        let sort = members[0].category().metadata().sort();
+        members.sort_by(|a, b| compare(sort, a, b));
+        for (index, member) in indices.into_iter().zip(members) {
+            previews[index] = member;
+        }
+    }
+}
+
+/// Total order of two articles under `sort`.
+#[must_use]
+pub fn compare(sort: CategorySort, a: &ArticlePreview, b: &ArticlePreview) -> Ordering {
+    let (left, right) = (a.metadata(), b.metadata());
+    let newest_first = || right.created().cmp(&left.created());
+    right
+        .is_pinned()
+        .cmp(&left.is_pinned())
+        .then_with(|| match sort {
+            CategorySort::Weight => match (left.weight(), right.weight()) {
+                (Some(left), Some(right)) => left.cmp(&right),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+            .then_with(newest_first),
+            CategorySort::Newest => newest_first(),
+            CategorySort::Oldest => left.created().cmp(&right.created()),
+            CategorySort::Title => a.title().to_lowercase().cmp(&b.title().to_lowercase()),
+        })
+        .then_with(|| a.title().cmp(b.title()))
+        .then_with(|| a.output_file().cmp(&b.output_file()))
+}
@@ -0,0 +1,249 @@
+//! Site-wide permalink patterns.
+//!
+//! `Thought.toml` may set `permalink` to decide where articles are emitted:
+//!
+//! ```toml
+//! permalink = "/{year}/{month}/{slug}/"
+//! ```
+//!
+//! Supported placeholders are `{category}`, `{slug}`, `{year}`, `{month}` and
+//! `{day}`. A trailing `/` produces pretty URLs (`…/slug/index.html`), and a
+//! pattern without an extension gets `.html` appended. Locale variants insert
+//! `.<locale>` before the extension (`hello.ja.html`, `hello/index.ja.html`).
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// Pattern used when `Thought.toml` does not set one.
+pub const DEFAULT_PERMALINK: &str = "/{category}/{slug}.html";
+
+const PLACEHOLDERS: [&str; 5] = ["category", "slug", "year", "month", "day"];
+
+/// Errors raised when a permalink pattern is malformed.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidPermalink {
+    /// The pattern never references the article slug.
+    #[error("permalink pattern `{0}` must contain {{slug}}")]
+    MissingSlug(String),
+    /// The pattern uses a placeholder this version does not know.
+    #[error("unknown placeholder `{{{0}}}` in permalink pattern")]
+    UnknownPlaceholder(String),
+    /// A `{` was not closed.
+    #[error("unclosed `{{` in permalink pattern `{0}`")]
+    Unclosed(String),
+    /// A `.` or `..` segment or a `\` would leave the build directory.
+    #[error("permalink pattern `{0}` may not contain `.` or `..` segments or `\\`")]
+    Unsafe(String),
+}
+
+/// A validated permalink pattern.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Permalink(String);
+
+impl Default for Permalink {
+    fn default() -> Self {
+        Self(DEFAULT_PERMALINK.to_string())
+    }
+}
+
+impl FromStr for Permalink {
+    type Err = InvalidPermalink;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        if pattern.contains('\\')
+            || pattern
+                .split('/')
+                .any(|segment| matches!(segment, "." | ".."))
+        {
+            return Err(InvalidPermalink::Unsafe(pattern.to_string()));
+        }
+        let mut rest = pattern;
+        let mut has_slug = false;
+        while let Some(open) = rest.find('{') {
+            let after = &rest[open + 1..];
+            let close = after
+                .find('}')
+                .ok_or_else(|| InvalidPermalink::Unclosed(pattern.to_string()))?;
+            let name = &after[..close];
+            if !PLACEHOLDERS.contains(&name) {
+                return Err(InvalidPermalink::UnknownPlaceholder(name.to_string()));
+            }
+            has_slug |= name == "slug";
+            rest = &after[close + 1..];
+        }
+        if !has_slug {
+            return Err(InvalidPermalink::MissingSlug(pattern.to_string()));
+        }
+        Ok(Self(pattern.to_string()))
+    }
+}
+
+impl TryFrom<String> for Permalink {
+    type Error = InvalidPermalink;
+
+    fn try_from(pattern: String) -> Result<Self, Self::Error> {
+        pattern.parse()
+    }
+}
+
+impl From<Permalink> for String {
+    fn from(permalink: Permalink) -> Self {
+        permalink.0
+    }
+}
+
+impl fmt::Display for Permalink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Permalink {
+    /// The pattern as written in `Thought.toml`.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Output file, relative to the build directory, for an article.
+    ///
+    /// `locale` is `None` for the default-locale variant.
+    #[must_use]
+    pub fn output_file(
+        &self,
+        category: &[String],
+        slug: &str,
+        created: OffsetDateTime,
+        locale: Option<&str>,
+    ) -> String {
+        let month: u8 = created.month().into();
+        let expanded = self
+            .0
+            .replace("{category}", &category.join("/"))
+            .replace("{slug}", slug)
+            .replace("{year}", &format!("{:04}", created.year()))
+            .replace("{month}", &format!("{month:02}"))
+            .replace("{day}", &format!("{:02}", created.day()));
+
+        let mut segments = expanded
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let pretty = expanded.ends_with('/') || segments.is_empty();
+        if pretty {
+            segments.push("index.html".to_string());
+        }
+
+        // Only the pattern's own text after the last placeholder can name an
+        // extension; a dot coming from a slug (`v1.2`) is part of the stem.
+        let extension = if pretty {
+            Some("html")
+        } else {
+            let last = self.0.rsplit('/').next().unwrap_or_default();
+            let literal = last.rsplit_once('}').map_or(last, |(_, literal)| literal);
+            literal
+                .rsplit_once('.')
+                .map(|(_, extension)| extension)
+                .filter(|extension| !extension.is_empty())
+        };
+        let file = segments.last_mut().expect("segments is never empty");
+        let (stem, extension) = match extension {
+            Some(extension) => (
+                file[..file.len() - extension.len() - 1].to_string(),
+                extension.to_string(),
+            ),
+            None => (file.clone(), "html".to_string()),
+        };
+        *file = match locale {
+            Some(locale) => format!("{stem}.{locale}.{extension}"),
+            None => format!("{stem}.{extension}"),
+        };
+        segments.join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    const CREATED: OffsetDateTime = datetime!(2024-05-01 09:30:00 UTC);
+
+    fn output_file(pattern: &str, slug: &str, locale: Option<&str>) -> String {
+        let category = ["guides".to_string(), "rust".to_string()];
+        pattern
+            .parse::<Permalink>()
+            .unwrap()
+            .output_file(&category, slug, CREATED, locale)
+    }
+
+    #[test]
+    fn expands_every_placeholder() {
+        assert_eq!(
+            output_file(DEFAULT_PERMALINK, "hello", None),
+            "guides/rust/hello.html"
+        );
+        assert_eq!(
+            output_file("/{year}/{month}/{day}/{slug}.html", "hello", None),
+            "2024/05/01/hello.html"
+        );
+        assert_eq!(
+            output_file("/posts/{category}-{slug}.htm", "hello", None),
+            "posts/guides/rust-hello.htm"
+        );
+    }
+
+    #[test]
+    fn appends_html_or_index_html() {
+        assert_eq!(output_file("/{slug}", "hello", None), "hello.html");
+        assert_eq!(
+            output_file("/{year}/{slug}/", "hello", None),
+            "2024/hello/index.html"
+        );
+    }
+
+    #[test]
+    fn inserts_the_locale_before_the_extension() {
+        assert_eq!(
+            output_file(DEFAULT_PERMALINK, "hello", Some("ja")),
+            "guides/rust/hello.ja.html"
+        );
+        assert_eq!(
+            output_file("/{slug}", "hello", Some("zh-CN")),
+            "hello.zh-CN.html"
+        );
+        assert_eq!(
+            output_file("/{slug}/", "hello", Some("ja")),
+            "hello/index.ja.html"
+        );
+    }
+
+    #[test]
+    fn keeps_dots_in_slugs_in_the_stem() {
+        assert_eq!(output_file("/{slug}", "v1.2", None), "v1.2.html");
+        assert_eq!(output_file("/{slug}", "v1.2", Some("ja")), "v1.2.ja.html");
+        assert_eq!(
+            output_file("/{slug}.html", "v1.2", Some("ja")),
+            "v1.2.ja.html"
+        );
+        assert_eq!(output_file("/{slug}/", "v1.2", None), "v1.2/index.html");
+    }
+
+    #[test]
+    fn rejects_malformed_patterns() {
+        for (pattern, expected) in [
+            ("/{category}.html", "must contain {slug}"),
+            ("/{slug}/{week}", "unknown placeholder `{week}`"),
+            ("/{slug", "unclosed"),
+            ("/../{slug}", "may not contain"),
+        ] {
+            let error = pattern.parse::<Permalink>().unwrap_err().to_string();
+            assert!(error.contains(expected), "{pattern}: {error}");
+        }
+    }
+}
@@ -15,6 +15,8 @@ mod resolver;
 use crate::{
     article::{Article, ArticlePreview},
     metadata::PluginKind,
+    series::Series,
+    timeline::ArchivePage,
     workspace::Workspace,
 };
 
@@ -25,6 +27,9 @@ use bindings::{
 };
 use resolver::resolve_plugin;
 
+/// Version of `plugin/wit/plugin.wit` plugins must be built against.
+const WIT_PACKAGE: &str = "thought:plugin@0.2.0";
+
 pub struct PluginManager {
     engine: WasmEngine,
     theme: ThemeHandle,
@@ -60,13 +65,13 @@ impl PluginManager {
             match kind {
                 PluginKind::Theme => {
                     let theme_pre = theme::ThemeRuntimePre::new(pre)
-                        .map_err(|err: wasmtime::Error| eyre!(err))?;
+                        .map_err(|err: wasmtime::Error| incompatible(name, &err))?;
                     theme = Some(ThemeHandle { pre: theme_pre });
                     theme_root = Some(resolved.dir().to_path_buf());
                 }
                 PluginKind::Hook => {
                     let hook_pre = hook::HookRuntimePre::new(pre)
-                        .map_err(|err: wasmtime::Error| eyre!(err))?;
+                        .map_err(|err: wasmtime::Error| incompatible(name, &err))?;
                     hooks.push(HookHandle { pre: hook_pre });
                 }
             }
@@ -129,13 +134,45 @@ impl PluginManager {
 
     /// Render the index using the theme plugin
     /// Returns the rendered HTML.
-    pub fn render_index(&self, previews: Vec<ArticlePreview>) -> eyre::Result<String> {
+    pub fn render_index(
+        &self,
+        previews: Vec<ArticlePreview>,
+        locale: Option<&str>,
+    ) -> eyre::Result<String> {
         let (mut store, instance) = self.instantiate_theme()?;
         let wit_previews: Vec<WITArticlePreview> =
             previews.into_iter().map(|preview| preview.into()).collect();
         let rendered = instance
             .thought_plugin_theme()
-            .call_generate_index(&mut store, &wit_previews)
+            .call_generate_index(&mut store, &wit_previews, locale)
+            .map_err(|err| eyre!(err))?;
+        Ok(rendered)
+    }
+
+    /// Render a chronological archive page using the theme plugin
+    /// Returns the rendered HTML.
+    pub fn render_archive(&self, page: ArchivePage) -> eyre::Result<String> {
+        let (mut store, instance) = self.instantiate_theme()?;
+        let rendered = instance
+            .thought_plugin_theme()
+            .call_generate_archive(&mut store, &page.into())
+            .map_err(|err| eyre!(err))?;
+        Ok(rendered)
+    }
+
+    /// Render the landing page of a series using the theme plugin
+    /// Returns the rendered HTML.
+    pub fn render_series(&self, series: Series) -> eyre::Result<String> {
+        let (mut store, instance) = self.instantiate_theme()?;
+        let name = series.name().to_string();
+        let wit_previews: Vec<WITArticlePreview> = series
+            .into_parts()
+            .into_iter()
+            .map(|preview| preview.into())
+            .collect();
+        let rendered = instance
+            .thought_plugin_theme()
+            .call_generate_series(&mut store, &name, &wit_previews)
             .map_err(|err| eyre!(err))?;
         Ok(rendered)
     }
@@ -184,6 +221,15 @@ impl PluginManager {
     }
 }
 
+/// Error for a plugin whose exports do not match [`WIT_PACKAGE`], which is
+/// what a plugin built against an older `thought-plugin` looks like.
+fn incompatible(name: &str, err: &wasmtime::Error) -> eyre::Report {
+    eyre!(
+        "plugin `{name}` does not implement {WIT_PACKAGE} ({err}); \
+         rebuild it against the current thought-plugin"
+    )
+}
+
 fn build_engine() -> eyre::Result<WasmEngine> {
     let mut config = Config::new();
     config.wasm_component_model(true);
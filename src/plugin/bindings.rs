@@ -3,7 +3,10 @@ use time::OffsetDateTime;
 use crate::{
     article::{Article, ArticlePreview},
     category::Category,
-    metadata::{ArticleMetadata, CategoryMetadata},
+    locale::{self, TextDirection},
+    metadata::{ArticleMetadata, CategoryMetadata, SeriesMembership},
+    series::{SeriesEntry, SeriesNavigation},
+    timeline::{ArchiveMonth, ArchivePage, ArchiveYear},
 };
 
 pub mod hook {
@@ -17,7 +20,7 @@ pub mod theme {
     wasmtime::component::bindgen!({
         path: "plugin/wit/plugin.wit",
         with: {
-            "thought:plugin/types":super::hook::thought::plugin::types,
+            "thought:plugin/types@0.2.0":super::hook::thought::plugin::types,
         },
         world: "theme-runtime",
     });
@@ -29,11 +32,81 @@ pub type WITCategory = hook::thought::plugin::types::Category;
 pub type WITArticleMetadata = hook::thought::plugin::types::ArticleMetadata;
 pub type WITCategoryMetadata = hook::thought::plugin::types::CategoryMetadata;
 pub type WITTranslation = hook::thought::plugin::types::Translation;
+pub type WITSeries = hook::thought::plugin::types::Series;
+pub type WITSeriesEntry = hook::thought::plugin::types::SeriesEntry;
+pub type WITSeriesMembership = hook::thought::plugin::types::SeriesMembership;
+pub type WITArchive = hook::thought::plugin::types::Archive;
+pub type WITArchiveYear = hook::thought::plugin::types::ArchiveYear;
+pub type WITArchiveMonth = hook::thought::plugin::types::ArchiveMonth;
+pub type WITTextDirection = hook::thought::plugin::types::TextDirection;
 impl From<Article> for WITArticle {
     fn from(article: Article) -> Self {
         WITArticle {
             preview: article.preview.into(),
             content: article.content,
+            series: article.series.map(Into::into),
+            related: article.related.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<SeriesNavigation> for WITSeries {
+    fn from(series: SeriesNavigation) -> Self {
+        WITSeries {
+            name: series.name,
+            output_file: series.output_file,
+            part: series.part,
+            parts: series.parts.into_iter().map(Into::into).collect(),
+            prev: series.prev.map(Into::into),
+            next: series.next.map(Into::into),
+        }
+    }
+}
+
+impl From<ArchivePage> for WITArchive {
+    fn from(page: ArchivePage) -> Self {
+        WITArchive {
+            year: page.year,
+            month: page.month,
+            output_file: page.output_file,
+            years: page.years.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<ArchiveYear> for WITArchiveYear {
+    fn from(year: ArchiveYear) -> Self {
+        WITArchiveYear {
+            year: year.year,
+            months: year.months.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<ArchiveMonth> for WITArchiveMonth {
+    fn from(month: ArchiveMonth) -> Self {
+        WITArchiveMonth {
+            month: month.month,
+            articles: month.articles.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<SeriesEntry> for WITSeriesEntry {
+    fn from(entry: SeriesEntry) -> Self {
+        WITSeriesEntry {
+            part: entry.part,
+            title: entry.title,
+            output_file: entry.output_file,
+        }
+    }
+}
+
+impl From<SeriesMembership> for WITSeriesMembership {
+    fn from(series: SeriesMembership) -> Self {
+        WITSeriesMembership {
+            name: series.name,
+            part: series.part,
         }
     }
 }
@@ -51,32 +124,57 @@ impl From<ArticleMetadata> for WITArticleMetadata {
     fn from(metadata: ArticleMetadata) -> Self {
         WITArticleMetadata {
             created: metadata.created.into(),
+            updated: metadata.updated.map(Into::into),
             tags: metadata.tags,
             author: metadata.author,
             description: metadata.description,
             lang: metadata.lang,
+            series: metadata.series.map(Into::into),
+            pinned: metadata.pinned,
+            weight: metadata.weight,
         }
     }
 }
 
 impl From<ArticlePreview> for WITArticlePreview {
     fn from(article: ArticlePreview) -> Self {
+        let stale = article.is_stale();
         WITArticlePreview {
             title: article.title,
             slug: article.slug,
             category: article.category.into(),
             metadata: article.metadata.into(),
+            excerpt: article.excerpt,
+            word_count: u32::try_from(article.stats.word_count()).unwrap_or(u32::MAX),
+            reading_minutes: article.stats.reading_minutes(),
             description: article.description,
+            script: locale::script(&article.locale),
+            direction: locale::direction(&article.locale).into(),
             locale: article.locale,
             default_locale: article.default_locale,
             translations: article
                 .translations
                 .into_iter()
                 .map(|t| WITTranslation {
+                    script: locale::script(&t.locale),
+                    direction: locale::direction(&t.locale).into(),
                     locale: t.locale,
                     title: t.title,
+                    output_file: t.output_file,
+                    stale: t.stale,
                 })
                 .collect(),
+            output_file: article.output_file,
+            stale,
+        }
+    }
+}
+
+impl From<TextDirection> for WITTextDirection {
+    fn from(direction: TextDirection) -> Self {
+        match direction {
+            TextDirection::Ltr => WITTextDirection::Ltr,
+            TextDirection::Rtl => WITTextDirection::Rtl,
         }
     }
 }
@@ -0,0 +1,202 @@
+//! Redirects from former article URLs.
+//!
+//! Articles list their old URLs in `aliases`. `thought generate` writes a small
+//! meta-refresh page at each alias, and optionally `_redirects` (Netlify) and
+//! an nginx `map` file so servers can answer with real 301s. `thought serve`
+//! uses the same table to redirect directly.
+
+use std::{collections::BTreeMap, path::Path};
+
+use color_eyre::eyre::{self, eyre};
+
+use crate::{
+    article::ArticlePreview, feed::escape, link::relative_href, locale::LocaleIndex,
+    metadata::RedirectConfig, series::SeriesIndex, timeline::Timeline, utils::write,
+};
+
+/// Netlify redirect rules written at the site root.
+const NETLIFY_FILE: &str = "_redirects";
+/// nginx `map` written at the site root.
+const NGINX_FILE: &str = "redirects.nginx.conf";
+
+/// Former URLs mapped to the output file they now live at, both relative to
+/// the build directory.
+#[derive(Debug, Default, Clone)]
+pub struct RedirectTable {
+    entries: BTreeMap<String, String>,
+}
+
+impl RedirectTable {
+    /// Collect aliases from every default-locale article.
+    ///
+    /// # Errors
+    /// Returns an error if an alias collides with an emitted article, falls
+    /// under `assets/` (theme assets and the search bundle) or on a server
+    /// redirect file, or is claimed by two articles.
+    pub fn new<'a>(previews: impl IntoIterator<Item = &'a ArticlePreview>) -> eyre::Result<Self> {
+        let previews = previews.into_iter().collect::<Vec<_>>();
+        let outputs = previews
+            .iter()
+            .map(|preview| preview.output_file())
+            .collect::<Vec<_>>();
+
+        let mut entries = BTreeMap::new();
+        for preview in previews
+            .iter()
+            .filter(|preview| preview.is_default_locale())
+        {
+            let target = preview.output_file();
+            for alias in preview.metadata().aliases() {
+                let Some(from) = alias_file(alias) else {
+                    continue;
+                };
+                if outputs.contains(&from) {
+                    return Err(eyre!(
+                        "alias `{alias}` of `{target}` points at an existing article"
+                    ));
+                }
+                if from.starts_with("assets/") || from == NETLIFY_FILE || from == NGINX_FILE {
+                    return Err(eyre!(
+                        "alias `{alias}` of `{target}` would overwrite the reserved file `{from}`"
+                    ));
+                }
+                if let Some(previous) = entries.insert(from, target.clone())
+                    && previous != target
+                {
+                    return Err(eyre!(
+                        "alias `{alias}` is claimed by both `{previous}` and `{target}`"
+                    ));
+                }
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// Fail if an alias would overwrite another generated file: the index, a
+    /// locale index, a series landing page, an archive page or a feed.
+    ///
+    /// # Errors
+    /// Returns an error naming the first alias that collides.
+    pub fn check_listings(
+        &self,
+        locales: &LocaleIndex,
+        series: &SeriesIndex,
+        timeline: &Timeline,
+    ) -> eyre::Result<()> {
+        let listings = ["index.html", "atom.xml", "sitemap.xml"]
+            .into_iter()
+            .map(str::to_string)
+            .chain(locales.pages().map(|(_, output_file, _)| output_file))
+            .chain(series.iter().map(|series| series.output_file().to_string()))
+            .chain(
+                timeline
+                    .pages()
+                    .iter()
+                    .map(|page| page.output_file().to_string()),
+            );
+        for listing in listings {
+            if let Some(target) = self.entries.get(&listing) {
+                return Err(eyre!(
+                    "an alias of `{target}` would overwrite the generated page `{listing}`"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether no aliases were declared.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Output file a request for `path` (relative to the site root) should be
+    /// redirected to.
+    #[must_use]
+    pub fn target(&self, path: &str) -> Option<&str> {
+        let path = path.trim_start_matches('/');
+        if let Some(target) = self.entries.get(path) {
+            return Some(target);
+        }
+        let file = alias_file(path)?;
+        self.entries.get(&file).map(String::as_str)
+    }
+
+    /// Write the redirect pages, plus the server files enabled in `config`.
+    ///
+    /// # Errors
+    /// Returns an error if a file cannot be written.
+    pub async fn emit(&self, output: &Path, config: &RedirectConfig) -> eyre::Result<()> {
+        for (from, to) in &self.entries {
+            let page = redirect_page(&relative_href(from, to), to);
+            write(output.join(from), page.as_bytes()).await?;
+        }
+
+        if config.netlify && !self.is_empty() {
+            let mut rules = String::new();
+            for (from, to) in &self.entries {
+                for source in request_paths(from) {
+                    rules.push_str(&format!("{source} /{to} 301\n"));
+                }
+            }
+            write(output.join(NETLIFY_FILE), rules.as_bytes()).await?;
+        }
+
+        if config.nginx && !self.is_empty() {
+            let mut map = String::from(
+                "# Include inside the `http` block and add to your server:\n\
+                 #   if ($thought_redirect) { return 301 $thought_redirect; }\n\
+                 map $uri $thought_redirect {\n    default \"\";\n",
+            );
+            for (from, to) in &self.entries {
+                for source in request_paths(from) {
+                    map.push_str(&format!("    \"{source}\" \"/{to}\";\n"));
+                }
+            }
+            map.push_str("}\n");
+            write(output.join(NGINX_FILE), map.as_bytes()).await?;
+        }
+        Ok(())
+    }
+}
+
+/// File written for an alias: `/old/post.html` stays as is, while `/old/post/`
+/// and `/old/post` become `old/post/index.html`.
+pub(crate) fn alias_file(alias: &str) -> Option<String> {
+    let trimmed = alias.trim().trim_start_matches('/');
+    if trimmed.is_empty() || trimmed.split('/').any(|segment| segment == "..") {
+        return None;
+    }
+    if trimmed.ends_with('/') {
+        return Some(format!("{trimmed}index.html"));
+    }
+    let last = trimmed.rsplit('/').next().unwrap_or(trimmed);
+    if last.contains('.') {
+        Some(trimmed.to_string())
+    } else {
+        Some(format!("{trimmed}/index.html"))
+    }
+}
+
+/// Request paths served by the redirect page at `file`.
+fn request_paths(file: &str) -> Vec<String> {
+    match file.strip_suffix("index.html") {
+        Some("") => vec!["/".to_string()],
+        Some(dir) => vec![format!("/{}", dir.trim_end_matches('/')), format!("/{dir}")],
+        None => vec![format!("/{file}")],
+    }
+}
+
+fn redirect_page(href: &str, target: &str) -> String {
+    let href = escape(href);
+    let target = escape(target);
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Redirecting…</title>\n\
+         <meta http-equiv=\"refresh\" content=\"0; url={href}\">\n\
+         <link rel=\"canonical\" href=\"{href}\">\n\
+         <meta name=\"robots\" content=\"noindex\">\n\
+         </head>\n<body>\n<p>This page has moved to <a href=\"{href}\">{target}</a>.</p>\n\
+         </body>\n</html>\n"
+    )
+}
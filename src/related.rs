@@ -0,0 +1,157 @@
+//! Related articles.
+//!
+//! Every other published article is scored against the one being rendered by
+//! the tags they share, how much of their category path they share, and how
+//! similar their text is according to the search index. The best
+//! `[related] count` matches are handed to the theme with the article, in the
+//! article's locale when they are translated.
+
+use std::collections::HashMap;
+
+use color_eyre::eyre;
+
+use crate::{
+    article::{Article, ArticlePreview},
+    locale,
+    search::Searcher,
+};
+
+/// Weight of each tag two articles share.
+const TAG_WEIGHT: f32 = 3.0;
+/// Weight of each leading category segment two articles share.
+const CATEGORY_WEIGHT: f32 = 1.0;
+/// Weight of the most similar text; weaker matches scale down from it.
+const TEXT_WEIGHT: f32 = 2.0;
+/// Similarity below this fraction of an article's match with itself is noise
+/// from short n-grams every text shares.
+const MIN_SIMILARITY: f32 = 0.1;
+
+/// Ranks related articles among a fixed set of candidates.
+#[derive(Debug, Clone)]
+pub struct RelatedArticles {
+    count: usize,
+    /// Default-locale, non-draft articles that may be recommended.
+    candidates: Vec<ArticlePreview>,
+    /// Every variant, by article key and locale.
+    variants: HashMap<(String, String), ArticlePreview>,
+    /// Article key of every output file.
+    keys: HashMap<String, String>,
+}
+
+impl RelatedArticles {
+    /// Prepare to recommend up to `count` of `previews` per article.
+    pub fn new<'a>(previews: impl IntoIterator<Item = &'a ArticlePreview>, count: usize) -> Self {
+        let mut candidates = Vec::new();
+        let mut variants = HashMap::new();
+        let mut keys = HashMap::new();
+        for preview in previews {
+            let key = article_key(preview);
+            keys.insert(preview.output_file(), key.clone());
+            if preview.is_default_locale() && preview.is_published() {
+                candidates.push(preview.clone());
+            }
+            variants.insert((key, preview.locale().to_string()), preview.clone());
+        }
+        Self {
+            count,
+            candidates,
+            variants,
+            keys,
+        }
+    }
+
+    /// Attach the articles most related to `article`.
+    ///
+    /// # Errors
+    /// Returns an error if the search index cannot be queried.
+    pub fn attach(&self, searcher: &Searcher, article: &mut Article) -> eyre::Result<()> {
+        if self.count == 0 {
+            return Ok(());
+        }
+        let own_key = article_key(article.preview());
+
+        // The article matches itself best, which gives a scale for the others.
+        let mut own_score = 0.0_f32;
+        let mut text_scores: HashMap<&str, f32> = HashMap::new();
+        let limit = (self.count * 4).max(20);
+        for (score, permalink) in searcher.similar(article.title(), article.content(), limit)? {
+            let Some(key) = self.keys.get(&permalink) else {
+                continue;
+            };
+            if *key == own_key {
+                own_score = own_score.max(score);
+                continue;
+            }
+            let best = text_scores.entry(key.as_str()).or_default();
+            *best = best.max(score);
+        }
+        if own_score <= 0.0 {
+            own_score = text_scores.values().copied().fold(0.0_f32, f32::max);
+        }
+        text_scores.retain(|_, score| *score >= MIN_SIMILARITY * own_score);
+        let best_text = text_scores.values().copied().fold(0.0_f32, f32::max);
+
+        let tags = article
+            .metadata()
+            .tags()
+            .iter()
+            .map(|tag| tag.to_lowercase())
+            .collect::<Vec<_>>();
+        let segments = article.category().segments();
+
+        let mut ranked = self
+            .candidates
+            .iter()
+            .filter_map(|candidate| {
+                let key = article_key(candidate);
+                if key == own_key {
+                    return None;
+                }
+                #[allow(clippy::cast_precision_loss)]
+                let shared_tags = candidate
+                    .metadata()
+                    .tags()
+                    .iter()
+                    .filter(|tag| tags.contains(&tag.to_lowercase()))
+                    .count() as f32;
+                #[allow(clippy::cast_precision_loss)]
+                let shared_segments = segments
+                    .iter()
+                    .zip(candidate.category().segments())
+                    .take_while(|(a, b)| a == b)
+                    .count() as f32;
+                let text = text_scores
+                    .get(key.as_str())
+                    .map_or(0.0, |score| TEXT_WEIGHT * score / best_text);
+                let score = TAG_WEIGHT * shared_tags + CATEGORY_WEIGHT * shared_segments + text;
+                (score > 0.0).then_some((score, key, candidate))
+            })
+            .collect::<Vec<_>>();
+        // Ties go to the newer article.
+        ranked.sort_by(|a, b| {
+            b.0.total_cmp(&a.0)
+                .then_with(|| b.2.metadata().created().cmp(&a.2.metadata().created()))
+        });
+
+        let chain = locale::fallback_chain(article.locale(), Some(article.default_locale()));
+        article.related = ranked
+            .into_iter()
+            .take(self.count)
+            .map(|(_, key, candidate)| {
+                chain
+                    .iter()
+                    .find_map(|locale| self.variants.get(&(key.clone(), locale.clone())))
+                    .unwrap_or(candidate)
+                    .clone()
+            })
+            .collect();
+        Ok(())
+    }
+}
+
+/// Identifies an article across its locale variants.
+fn article_key(preview: &ArticlePreview) -> String {
+    let mut segments = preview.category().segments().clone();
+    segments.push(preview.slug().to_string());
+    segments.join("/")
+}
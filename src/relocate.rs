@@ -0,0 +1,283 @@
+//! Moving and renaming articles.
+//!
+//! `thought article move` and `thought article rename` relocate an article's
+//! files together with every locale variant, point `@` references in other
+//! articles at the new location, and record the old URL as an alias so the
+//! next build leaves a redirect behind.
+
+use std::{path::PathBuf, str::FromStr};
+
+use color_eyre::eyre::{self, Context, eyre};
+use futures::TryStreamExt;
+use tokio::fs as async_fs;
+
+use crate::{
+    article::{Article, ArticleLayout, ArticlePreview, content_path},
+    cache::RenderCache,
+    front_matter,
+    link::{self, LinkResolver},
+    metadata::{ArticleMetadata, MetadataExt},
+    redirect::alias_file,
+    slug::ArticleSlug,
+    utils::{read_to_string, write},
+    workspace::Workspace,
+};
+
+impl Workspace {
+    /// Move the article at `segments` into `category`, keeping its slug.
+    ///
+    /// # Errors
+    /// Returns an error if the article or category does not exist, the
+    /// destination is taken, or a file cannot be rewritten.
+    pub async fn move_article(
+        &self,
+        segments: &[String],
+        category: &[String],
+    ) -> eyre::Result<Article> {
+        let slug = segments
+            .last()
+            .ok_or_else(|| eyre!("Article path cannot be empty"))?;
+        for segment in category {
+            ArticleSlug::from_str(segment)
+                .map_err(|err| eyre!("Invalid category segment `{segment}`: {err}"))?;
+        }
+        relocate(self, segments, category.to_vec(), slug.clone()).await
+    }
+
+    /// Rename the article at `segments` to `slug` within its category.
+    ///
+    /// # Errors
+    /// Returns an error if the slug is invalid, the destination is taken, or a
+    /// file cannot be rewritten.
+    pub async fn rename_article(&self, segments: &[String], slug: &str) -> eyre::Result<Article> {
+        let category = segments
+            .split_last()
+            .map(|(_, category)| category.to_vec())
+            .ok_or_else(|| eyre!("Article path cannot be empty"))?;
+        let slug = ArticleSlug::from_str(slug)?.into_string();
+        if slug.contains(['/', '\\', '.']) {
+            return Err(eyre!("Slug `{slug}` may not contain `/`, `\\` or `.`"));
+        }
+        relocate(self, segments, category, slug).await
+    }
+}
+
+async fn relocate(
+    workspace: &Workspace,
+    from: &[String],
+    category: Vec<String>,
+    slug: String,
+) -> eyre::Result<Article> {
+    let article = Article::open(workspace.clone(), from.to_vec())
+        .await
+        .map_err(|err| eyre!("Cannot open article `{}`: {err}", from.join("/")))?;
+
+    let mut to = category.clone();
+    to.push(slug.clone());
+    if to == from {
+        return Err(eyre!("Article is already at `{}`", to.join("/")));
+    }
+
+    let category_dir = category
+        .iter()
+        .fold(workspace.articles_dir(), |dir, segment| dir.join(segment));
+    if !category_dir.join("Category.toml").exists() {
+        return Err(eyre!("Category `{}` does not exist", category.join("/")));
+    }
+    if category_dir.join(&slug).exists() || category_dir.join(format!("{slug}.md")).exists() {
+        return Err(eyre!("An article already exists at `{}`", to.join("/")));
+    }
+
+    // A bare `@slug` only follows the article when it named it unambiguously.
+    let before = LinkResolver::new(&previews(workspace).await?);
+    let follow_slug = before.slug_path(&slug_of(from)) == Some(from.join("/").as_str());
+
+    let old_output = article.output_file();
+    let mut stale = article
+        .translations()
+        .iter()
+        .map(|translation| output_path(translation.output_file()))
+        .collect::<Vec<_>>();
+
+    let renames = match article.layout() {
+        ArticleLayout::Directory => vec![(article.dir(), category_dir.join(&slug))],
+        ArticleLayout::File => article
+            .translations()
+            .iter()
+            .map(|translation| {
+                let locale = translation.locale();
+                let target = content_path(
+                    ArticleLayout::File,
+                    &category_dir,
+                    &slug,
+                    locale,
+                    article.default_locale(),
+                );
+                (article.content_path_for_locale(locale), target)
+            })
+            .collect(),
+    };
+    rename_all(&renames).await?;
+
+    let moved = Article::open(workspace.clone(), to.clone())
+        .await
+        .map_err(|err| eyre!("Cannot open moved article `{}`: {err}", to.join("/")))?;
+    let new_output = moved.output_file();
+    if new_output != old_output {
+        record_alias(&moved, &format!("/{old_output}"), &new_output).await?;
+    }
+    stale.extend(
+        moved
+            .translations()
+            .iter()
+            .map(|translation| output_path(translation.output_file())),
+    );
+
+    rewrite_references(workspace, from, &to, follow_slug).await?;
+
+    let cache = RenderCache::load(workspace.cache_dir().join("cache.redb")).await?;
+    cache.invalidate(stale).await?;
+
+    Article::open(workspace.clone(), to.clone())
+        .await
+        .map_err(|err| eyre!("Cannot open moved article `{}`: {err}", to.join("/")))
+}
+
+/// Rename every `(from, to)` pair, putting the files already moved back if one
+/// rename fails, so a move never leaves an article half in each place.
+async fn rename_all(renames: &[(PathBuf, PathBuf)]) -> eyre::Result<()> {
+    for (done, (from, to)) in renames.iter().enumerate() {
+        if let Err(err) = async_fs::rename(from, to).await {
+            for (from, to) in renames[..done].iter().rev() {
+                if let Err(undo) = async_fs::rename(to, from).await {
+                    tracing::warn!(
+                        "Failed to move {} back to {}: {undo}",
+                        to.display(),
+                        from.display()
+                    );
+                }
+            }
+            return Err(err).wrap_err_with(|| {
+                format!("Failed to move {} to {}", from.display(), to.display())
+            });
+        }
+    }
+    Ok(())
+}
+
+async fn previews(workspace: &Workspace) -> eyre::Result<Vec<ArticlePreview>> {
+    workspace
+        .articles()
+        .map_ok(|article| article.preview().clone())
+        .try_collect()
+        .await
+        .wrap_err("Failed to open an article while scanning for references")
+}
+
+fn slug_of(segments: &[String]) -> String {
+    segments.last().cloned().unwrap_or_default()
+}
+
+/// Cache key for an output file.
+fn output_path(output_file: &str) -> String {
+    output_file
+        .strip_suffix(".html")
+        .unwrap_or(output_file)
+        .to_string()
+}
+
+/// Add `alias` to the article and drop any alias that now points at the
+/// article itself (e.g. after moving it back).
+///
+/// Aliases go into `Article.toml` when the article has one and its front
+/// matter does not list aliases itself; otherwise into the front matter.
+async fn record_alias(article: &Article, alias: &str, output_file: &str) -> eyre::Result<()> {
+    let update = |aliases: &mut Vec<String>| {
+        aliases.retain(|existing| alias_file(existing).as_deref() != Some(output_file));
+        if !aliases.iter().any(|existing| existing == alias) {
+            aliases.push(alias.to_string());
+        }
+    };
+
+    let content_path = article.content_path_for_locale(article.default_locale());
+    let content = read_to_string(&content_path).await?;
+    let listed_in_front_matter = front_matter::split(&content)?
+        .0
+        .is_some_and(|front_matter| front_matter.aliases.is_some());
+
+    let metadata_path = article.dir().join("Article.toml");
+    if article.layout() == ArticleLayout::Directory
+        && !listed_in_front_matter
+        && metadata_path.exists()
+    {
+        let mut metadata = ArticleMetadata::open(&metadata_path).await?;
+        update(&mut metadata.aliases);
+        metadata.save_to_file(&metadata_path).await?;
+    } else {
+        let updated = front_matter::update_aliases(&content, update)?;
+        write(&content_path, updated.as_bytes()).await?;
+    }
+    Ok(())
+}
+
+/// Point references to `from` in every article at `to`.
+///
+/// `@/category/slug` references are rewritten to the new path. Bare `@slug`
+/// references follow a rename when `follow_slug` says they named this article;
+/// they become `@new-slug`, or the new path if that slug is not unique.
+async fn rewrite_references(
+    workspace: &Workspace,
+    from: &[String],
+    to: &[String],
+    follow_slug: bool,
+) -> eyre::Result<()> {
+    let old_path = from.join("/");
+    let new_path = format!("/{}", to.join("/"));
+    let old_slug = slug_of(from);
+    let new_slug = slug_of(to);
+
+    let articles = workspace
+        .articles()
+        .try_collect::<Vec<_>>()
+        .await
+        .wrap_err("Failed to open an article while rewriting references")?;
+    let after = LinkResolver::new(articles.iter().map(Article::preview));
+    let slug_target = if after.slug_path(&new_slug) == Some(to.join("/").as_str()) {
+        new_slug.clone()
+    } else {
+        new_path.clone()
+    };
+
+    for article in articles {
+        let path = article.content_path_for_locale(article.locale());
+        let raw = read_to_string(&path).await?;
+        // Only touch the body, so front matter is written back byte for byte.
+        let body_start = raw.len() - front_matter::strip(&raw).len();
+        let body = &raw[body_start..];
+
+        let by_path = link::retarget(
+            body,
+            |reference| {
+                reference
+                    .strip_prefix('/')
+                    .is_some_and(|path| path.trim_end_matches('/') == old_path)
+            },
+            &new_path,
+        );
+        let by_slug = if !follow_slug || old_slug == slug_target {
+            None
+        } else {
+            link::retarget(
+                by_path.as_deref().unwrap_or(body),
+                |reference| reference == old_slug,
+                &slug_target,
+            )
+        };
+
+        if let Some(body) = by_slug.or(by_path) {
+            let rewritten = format!("{}{body}", &raw[..body_start]);
+            write(&path, rewritten.as_bytes()).await?;
+        }
+    }
+    Ok(())
+}
@@ -14,7 +14,7 @@ use tantivy::{
     Index, Term,
     collector::TopDocs,
     doc,
-    query::{BooleanQuery, FuzzyTermQuery, Occur, Query, QueryParser},
+    query::{BooleanQuery, FuzzyTermQuery, MoreLikeThisQuery, Occur, Query, QueryParser},
     schema::{
         Field, IndexRecordOption, OwnedValue, STORED, Schema, TantivyDocument, TextFieldIndexing,
         TextOptions,
@@ -36,6 +36,8 @@ pub(crate) const SEARCH_WRAPPER: &str = include_str!("../assets/thought-search.j
 const TOKENIZER: &str = "thought_tokenizer";
 const SEARCH_META_TABLE: TableDefinition<&str, &str> = TableDefinition::new("search_meta");
 const INDEX_WRITER_MEMORY: usize = 256 * 1024 * 1024;
+/// Terms kept from an article when looking for similar ones.
+const SIMILAR_QUERY_TERMS: usize = 64;
 const FIELD_TITLE: &str = "title";
 const FIELD_CONTENT: &str = "content";
 const FIELD_DESCRIPTION: &str = "description";
@@ -215,6 +217,9 @@ impl Searcher {
         let stream = self.workspace.articles();
         futures::pin_mut!(stream);
         while let Some(article) = stream.as_mut().try_next().await? {
+            if !article.is_published() {
+                continue;
+            }
             docs.push(IndexedDoc {
                 title: article.title().to_string(),
                 content: article.content().to_string(),
@@ -257,6 +262,19 @@ impl Searcher {
         Ok(())
     }
 
+    /// Write the search module and its JavaScript wrapper under `output`.
+    pub async fn emit_bundle(&self, output: &Path) -> eyre::Result<()> {
+        let asset_dir = output.join(search_asset_dir());
+        fs::create_dir_all(&asset_dir).await?;
+
+        let wasm_path = asset_dir.join(search_wasm_filename());
+        self.build_wasm(&wasm_path).await?;
+
+        let js_path = asset_dir.join(search_js_filename());
+        write(js_path, SEARCH_WRAPPER.as_bytes()).await?;
+        Ok(())
+    }
+
     /// Rebuild the index only when the provided fingerprint differs from the cached value.
     pub async fn ensure_index(&self, fingerprint: Option<&str>) -> eyre::Result<bool> {
         if let Some(expected) = fingerprint {
@@ -312,6 +330,39 @@ impl Searcher {
         Ok(prefer_default_locale(hits))
     }
 
+    /// Articles whose title and content resemble the given text, as
+    /// `(score, permalink)` pairs with the best match first. Every locale
+    /// variant is a separate document.
+    pub fn similar(
+        &self,
+        title: &str,
+        content: &str,
+        limit: usize,
+    ) -> eyre::Result<Vec<(f32, String)>> {
+        let reader = self.index.reader()?;
+        let searcher = reader.searcher();
+        let query = MoreLikeThisQuery::builder()
+            .with_min_doc_frequency(1)
+            .with_min_term_frequency(1)
+            .with_max_query_terms(SIMILAR_QUERY_TERMS)
+            .with_document_fields(vec![
+                (self.title_field, vec![OwnedValue::Str(title.to_string())]),
+                (
+                    self.content_field,
+                    vec![OwnedValue::Str(content.to_string())],
+                ),
+            ]);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+
+        let mut similar = Vec::new();
+        for (score, address) in top_docs {
+            let doc: TantivyDocument = searcher.doc(address)?;
+            let permalink = Self::stored_string(&doc, self.permalink_field, FIELD_PERMALINK)?;
+            similar.push((score, permalink));
+        }
+        Ok(similar)
+    }
+
     fn search_hit_from_doc(&self, doc: &TantivyDocument) -> eyre::Result<SearchHit> {
         let category = Self::stored_string(doc, self.category_field, FIELD_CATEGORY)?;
         Ok(SearchHit {
@@ -408,6 +459,9 @@ impl Searcher {
         let stream = self.workspace.articles();
         futures::pin_mut!(stream);
         while let Some(article) = stream.as_mut().try_next().await? {
+            if !article.is_published() {
+                continue;
+            }
             records.push(json!({
                 "title": article.title(),
                 "slug": article.slug(),
@@ -515,16 +569,7 @@ pub async fn emit_search_bundle(
 ) -> eyre::Result<()> {
     let searcher = Searcher::open(workspace.clone()).await?;
     searcher.ensure_index(fingerprint).await?;
-
-    let asset_dir = output.join(search_asset_dir());
-    fs::create_dir_all(&asset_dir).await?;
-
-    let wasm_path = asset_dir.join(search_wasm_filename());
-    searcher.build_wasm(&wasm_path).await?;
-
-    let js_path = asset_dir.join(search_js_filename());
-    write(js_path, SEARCH_WRAPPER.as_bytes()).await?;
-    Ok(())
+    searcher.emit_bundle(output).await
 }
 
 async fn open_meta_database(path: PathBuf) -> eyre::Result<Arc<Database>> {
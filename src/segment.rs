@@ -0,0 +1,567 @@
+//! Markdown split into translatable segments.
+//!
+//! Only the text of paragraphs, headings, list items and table cells is handed
+//! to a translator. Inline code, inline HTML, link and image targets,
+//! footnote references, hard line breaks, shortcodes (`{{< … >}}`, `{% … %}`)
+//! and bare URLs inside that text are replaced by numbered placeholders such
+//! as `⟦0⟧`, which must come back exactly once. Everything outside the
+//! segments, including code blocks and HTML blocks, is kept byte for byte,
+//! and the reassembled document is checked to have the same code blocks and
+//! link targets as the source. A segment that comes back unchanged is kept
+//! as written, line breaks included.
+//!
+//! The input is an article body without its front matter; the title and
+//! description in front matter are translated as separate strings and written
+//! into the translation's own front matter.
+
+use std::{ops::Range, sync::LazyLock};
+
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag, TagEnd};
+use regex::Regex;
+
+/// Shortcodes of Hugo, Liquid and Zola, and bare URLs without trailing
+/// punctuation.
+static PROTECTED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"\{\{[<%].*?[%>]\}\}|\{%.*?%\}|\{\{.*?\}\}|https?://[^\s<>()\[\]]*[^\s<>()\[\].,;:!?'"]"#,
+    )
+    .expect("valid protected span regex")
+});
+
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"⟦(\d+)⟧").expect("valid placeholder regex"));
+
+/// Errors raised when putting translated segments back together.
+#[derive(Debug, thiserror::Error)]
+pub enum SegmentError {
+    /// The translator returned more or fewer segments than it was given.
+    #[error("expected {expected} translated segments, got {found}")]
+    SegmentCount { expected: usize, found: usize },
+    /// A placeholder was dropped, repeated or made up.
+    #[error("placeholder ⟦{placeholder}⟧ is missing, repeated or unknown in segment {segment}")]
+    Placeholder { segment: usize, placeholder: usize },
+    /// The translation gained or lost code blocks.
+    #[error("translation has {found} code blocks instead of {expected}")]
+    CodeBlocks { expected: usize, found: usize },
+    /// The translation links to different targets.
+    #[error("translation links to {found:?} instead of {expected:?}")]
+    LinkTargets {
+        expected: Vec<String>,
+        found: Vec<String>,
+    },
+}
+
+/// A Markdown document split into translatable segments.
+#[derive(Debug, Clone)]
+pub struct Segments {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+struct Segment {
+    range: Range<usize>,
+    text: String,
+    protected: Vec<String>,
+}
+
+impl Segments {
+    /// Split `markdown` into segments.
+    #[must_use]
+    pub fn new(markdown: &str) -> Self {
+        let mut builder = Builder {
+            source: markdown,
+            segments: Vec::new(),
+            verbatim: 0,
+            opaque: 0,
+            run: None,
+        };
+        for (event, range) in Parser::new_ext(markdown, options()).into_offset_iter() {
+            builder.push(&event, range);
+        }
+        builder.close();
+        Self {
+            source: markdown.to_string(),
+            segments: builder.segments,
+        }
+    }
+
+    /// Text of each segment, with placeholders, in document order.
+    pub fn texts(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().map(|segment| segment.text.as_str())
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Put `translated`, one entry per segment, back into the document.
+    ///
+    /// # Errors
+    /// Returns [`SegmentError`] if the segment count or a placeholder does not
+    /// match, or the result does not have the source's structure.
+    pub fn reassemble(&self, translated: &[String]) -> Result<String, SegmentError> {
+        if translated.len() != self.segments.len() {
+            return Err(SegmentError::SegmentCount {
+                expected: self.segments.len(),
+                found: translated.len(),
+            });
+        }
+        let mut output = String::with_capacity(self.source.len());
+        let mut cursor = 0;
+        for (index, (segment, translation)) in self.segments.iter().zip(translated).enumerate() {
+            output.push_str(&self.source[cursor..segment.range.start]);
+            // A segment left as it was keeps its line breaks.
+            if translation.trim() == segment.text {
+                output.push_str(&self.source[segment.range.clone()]);
+            } else {
+                output.push_str(&segment.restore(index, translation)?);
+            }
+            cursor = segment.range.end;
+        }
+        output.push_str(&self.source[cursor..]);
+        verify(&self.source, &output)?;
+        Ok(output)
+    }
+}
+
+impl Segment {
+    /// Replace the placeholders in `translation` with the spans they stand for.
+    fn restore(&self, index: usize, translation: &str) -> Result<String, SegmentError> {
+        // A segment sits on one line so that container prefixes (`> `, list
+        // indentation) need not be repeated; hard breaks are placeholders.
+        let translation = translation.trim().replace(['\r', '\n'], " ");
+        let mut seen = vec![false; self.protected.len()];
+        let mut output = String::with_capacity(translation.len());
+        let mut last = 0;
+        for captures in PLACEHOLDER.captures_iter(&translation) {
+            let whole = captures.get(0).expect("capture group 0 always exists");
+            let placeholder = captures[1].parse().unwrap_or(usize::MAX);
+            match seen.get_mut(placeholder) {
+                Some(seen) if !*seen => *seen = true,
+                _ => {
+                    return Err(SegmentError::Placeholder {
+                        segment: index,
+                        placeholder,
+                    });
+                }
+            }
+            output.push_str(&translation[last..whole.start()]);
+            output.push_str(&self.protected[placeholder]);
+            last = whole.end();
+        }
+        output.push_str(&translation[last..]);
+        if let Some(placeholder) = seen.iter().position(|seen| !seen) {
+            return Err(SegmentError::Placeholder {
+                segment: index,
+                placeholder,
+            });
+        }
+        Ok(output)
+    }
+}
+
+/// Check that `translation` has the code blocks and link targets of `source`.
+///
+/// # Errors
+/// Returns [`SegmentError::CodeBlocks`] or [`SegmentError::LinkTargets`] on a
+/// mismatch.
+pub fn verify(source: &str, translation: &str) -> Result<(), SegmentError> {
+    let (expected_blocks, expected_links) = structure(source);
+    let (found_blocks, found_links) = structure(translation);
+    if expected_blocks != found_blocks {
+        return Err(SegmentError::CodeBlocks {
+            expected: expected_blocks,
+            found: found_blocks,
+        });
+    }
+    if expected_links != found_links {
+        return Err(SegmentError::LinkTargets {
+            expected: expected_links,
+            found: found_links,
+        });
+    }
+    Ok(())
+}
+
+/// Number of code blocks and the sorted link and image targets of `markdown`.
+fn structure(markdown: &str) -> (usize, Vec<String>) {
+    let mut code_blocks = 0;
+    let mut targets = Vec::new();
+    for event in Parser::new_ext(markdown, options()) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => code_blocks += 1,
+            Event::Start(Tag::Link { dest_url, .. } | Tag::Image { dest_url, .. }) => {
+                targets.push(dest_url.to_string());
+            }
+            _ => {}
+        }
+    }
+    targets.sort();
+    (code_blocks, targets)
+}
+
+fn options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+        | Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS
+}
+
+enum Piece {
+    Text(String),
+    Protected(String),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Break {
+    None,
+    Soft,
+    Hard,
+}
+
+/// Consecutive inline events of one block.
+struct Run {
+    start: usize,
+    cursor: usize,
+    pieces: Vec<Piece>,
+    after: Break,
+}
+
+impl Run {
+    /// Take in the source between the last event and `to`: markup such as
+    /// `**` is kept as text, the prefix of a continuation line is dropped, and
+    /// the prefix after a hard break joins the break's placeholder.
+    fn gap(&mut self, source: &str, to: usize) {
+        if to > self.cursor {
+            let gap = &source[self.cursor..to];
+            match (self.after, self.pieces.last_mut()) {
+                (Break::Soft, _) => {}
+                (Break::Hard, Some(Piece::Protected(protected))) => protected.push_str(gap),
+                _ => self.text(gap),
+            }
+            self.cursor = to;
+        }
+        self.after = Break::None;
+    }
+
+    /// Append literal text, joining it to the previous text so that
+    /// shortcodes split across events are still found.
+    fn text(&mut self, text: &str) {
+        match self.pieces.last_mut() {
+            Some(Piece::Text(last)) => last.push_str(text),
+            _ => self.pieces.push(Piece::Text(text.to_string())),
+        }
+    }
+
+    fn protect(&mut self, source: &str, range: Range<usize>) {
+        self.gap(source, range.start);
+        self.pieces
+            .push(Piece::Protected(source[range.clone()].to_string()));
+        self.cursor = range.end;
+    }
+
+    fn into_segment(self) -> Option<Segment> {
+        let mut text = String::new();
+        let mut protected = Vec::new();
+        let mut translatable = false;
+        let mut placeholder = |text: &mut String, span: &str| {
+            text.push_str(&format!("⟦{}⟧", protected.len()));
+            protected.push(span.to_string());
+        };
+        for piece in self.pieces {
+            match piece {
+                Piece::Text(literal) => {
+                    let mut last = 0;
+                    for span in PROTECTED.find_iter(&literal) {
+                        text.push_str(&literal[last..span.start()]);
+                        placeholder(&mut text, span.as_str());
+                        last = span.end();
+                    }
+                    text.push_str(&literal[last..]);
+                    translatable |= literal.chars().any(char::is_alphabetic);
+                }
+                Piece::Protected(span) => placeholder(&mut text, &span),
+            }
+        }
+        translatable.then_some(Segment {
+            range: self.start..self.cursor,
+            text,
+            protected,
+        })
+    }
+}
+
+struct Builder<'a> {
+    source: &'a str,
+    segments: Vec<Segment>,
+    /// Depth of code, HTML and metadata blocks, whose text is kept as is.
+    verbatim: usize,
+    /// Depth of a link whose text must not change: an autolink, or a
+    /// shortcut reference whose text is its label.
+    opaque: usize,
+    run: Option<Run>,
+}
+
+impl Builder<'_> {
+    fn push(&mut self, event: &Event<'_>, range: Range<usize>) {
+        match event {
+            Event::Start(Tag::CodeBlock(_) | Tag::HtmlBlock | Tag::MetadataBlock(_)) => {
+                self.close();
+                self.verbatim += 1;
+                return;
+            }
+            Event::End(TagEnd::CodeBlock | TagEnd::HtmlBlock | TagEnd::MetadataBlock(_)) => {
+                self.verbatim = self.verbatim.saturating_sub(1);
+                return;
+            }
+            _ if self.verbatim > 0 => return,
+            _ => {}
+        }
+        if self.opaque > 0 {
+            match event {
+                Event::Start(Tag::Link { .. }) => self.opaque += 1,
+                Event::End(TagEnd::Link) => self.opaque -= 1,
+                _ => {}
+            }
+            return;
+        }
+        if !is_inline(event) {
+            self.close();
+            return;
+        }
+
+        let source = self.source;
+        let run = self.run.get_or_insert_with(|| Run {
+            start: range.start,
+            cursor: range.start,
+            pieces: Vec::new(),
+            after: Break::None,
+        });
+        match event {
+            Event::Start(Tag::Link {
+                link_type:
+                    LinkType::Autolink
+                    | LinkType::Email
+                    | LinkType::Shortcut
+                    | LinkType::ShortcutUnknown
+                    | LinkType::Collapsed
+                    | LinkType::CollapsedUnknown,
+                ..
+            }) => {
+                run.protect(source, range);
+                self.opaque = 1;
+            }
+            Event::Start(_) => run.gap(source, range.start),
+            // `](target)` after the text of a link or image.
+            Event::End(TagEnd::Link | TagEnd::Image) => {
+                let tail = run.cursor..range.end.max(run.cursor);
+                run.protect(source, tail);
+            }
+            Event::End(_) => run.gap(source, range.end),
+            Event::Text(_) => {
+                run.gap(source, range.start);
+                run.text(&source[range.clone()]);
+                run.cursor = range.end;
+            }
+            Event::SoftBreak => {
+                run.gap(source, range.start);
+                run.text(" ");
+                run.cursor = range.end;
+                run.after = Break::Soft;
+            }
+            Event::HardBreak => {
+                run.protect(source, range);
+                run.after = Break::Hard;
+            }
+            _ => run.protect(source, range),
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some(segment) = self.run.take().and_then(Run::into_segment) {
+            self.segments.push(segment);
+        }
+    }
+}
+
+fn is_inline(event: &Event<'_>) -> bool {
+    match event {
+        Event::Text(_)
+        | Event::Code(_)
+        | Event::InlineHtml(_)
+        | Event::FootnoteReference(_)
+        | Event::SoftBreak
+        | Event::HardBreak
+        | Event::TaskListMarker(_) => true,
+        Event::Start(tag) => matches!(
+            tag,
+            Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. } | Tag::Image { .. }
+        ),
+        Event::End(tag) => matches!(
+            tag,
+            TagEnd::Emphasis
+                | TagEnd::Strong
+                | TagEnd::Strikethrough
+                | TagEnd::Link
+                | TagEnd::Image
+        ),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CORPUS: &[&str] = &[
+        "# Title\n\nA paragraph with *emphasis*, **strong** and `code`.\n",
+        "> Quoted text\n> over two lines.\n>\n> > A nested quote.\n",
+        "- First item\n  - Nested item with [a link](https://example.com)\n    1. Deeper\n- Last item\n",
+        "| Name | Value |\n| ---- | ----- |\n| Speed | fast |\n| Size | `small` |\n",
+        "See [the guide][guide] and [guide].\n\n[guide]: https://example.com/guide \"Guide\"\n",
+        "A claim.[^note]\n\n[^note]: The footnote text.\n",
+        "First line  \nsecond line\\\nthird line.\n",
+        "Before {{< figure src=\"a.png\" >}} after {% include note.html %} and https://example.com/x.\n",
+        "Text\n\n```rust\nlet x = 1;\n```\n\n<div>\nraw html\n</div>\n\n![An image](pic.png) <span>inline</span>\n",
+        "- [ ] open task\n- [x] done task\n\n<https://example.com> ~~gone~~\n",
+    ];
+
+    fn identity(segments: &Segments) -> Vec<String> {
+        segments.texts().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn reassembles_untouched_segments_into_the_source() {
+        for source in CORPUS {
+            let segments = Segments::new(source);
+            assert!(!segments.is_empty(), "{source}");
+            assert_eq!(
+                segments.reassemble(&identity(&segments)).unwrap(),
+                *source,
+                "{source}"
+            );
+            // Placeholders alone restore segments without soft line breaks.
+            for (index, segment) in segments.segments.iter().enumerate() {
+                let original = &source[segment.range.clone()];
+                let unprotected = segment
+                    .protected
+                    .iter()
+                    .fold(original.to_string(), |rest, span| {
+                        rest.replacen(span, "", 1)
+                    });
+                if !unprotected.contains('\n') {
+                    assert_eq!(segment.restore(index, &segment.text).unwrap(), original);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_markup_out_of_the_segments() {
+        let segments = Segments::new(CORPUS[7]);
+        assert_eq!(identity(&segments), ["Before ⟦0⟧ after ⟦1⟧ and ⟦2⟧."]);
+        let segments = Segments::new(CORPUS[6]);
+        assert_eq!(
+            identity(&segments),
+            ["First line⟦0⟧second line⟦1⟧third line."]
+        );
+    }
+
+    #[test]
+    fn rejects_a_dropped_placeholder() {
+        let segments = Segments::new(CORPUS[0]);
+        let mut translated = identity(&segments);
+        translated[1] = translated[1].replace("⟦0⟧", "");
+        assert!(matches!(
+            segments.reassemble(&translated),
+            Err(SegmentError::Placeholder {
+                segment: 1,
+                placeholder: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_duplicated_placeholder() {
+        let segments = Segments::new(CORPUS[0]);
+        let mut translated = identity(&segments);
+        translated[1].push_str(" ⟦0⟧");
+        assert!(matches!(
+            segments.reassemble(&translated),
+            Err(SegmentError::Placeholder {
+                segment: 1,
+                placeholder: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_an_unknown_placeholder() {
+        let segments = Segments::new(CORPUS[0]);
+        let mut translated = identity(&segments);
+        translated[0].push_str(" ⟦7⟧");
+        assert!(matches!(
+            segments.reassemble(&translated),
+            Err(SegmentError::Placeholder {
+                segment: 0,
+                placeholder: 7
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_changed_segment_count() {
+        let segments = Segments::new(CORPUS[0]);
+        let mut translated = identity(&segments);
+        translated.pop();
+        assert!(matches!(
+            segments.reassemble(&translated),
+            Err(SegmentError::SegmentCount { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_changed_link_target() {
+        let source = "Read [the docs](https://example.com/docs).\n";
+        let segments = Segments::new(source);
+        assert_eq!(identity(&segments), ["Read [the docs⟦0⟧."]);
+        let translated = ["Lire [la doc](https://example.com/other).".to_string()];
+        assert!(matches!(
+            segments.reassemble(&translated),
+            Err(SegmentError::Placeholder { .. })
+        ));
+        assert!(matches!(
+            verify(source, "Lire [la doc](https://example.com/other).\n"),
+            Err(SegmentError::LinkTargets { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_translation_that_adds_a_link() {
+        let segments = Segments::new("Plain text.\n");
+        let translated = ["Texte [simple](https://example.com).".to_string()];
+        assert!(matches!(
+            segments.reassemble(&translated),
+            Err(SegmentError::LinkTargets { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_translation_that_opens_a_code_block() {
+        let segments = Segments::new("Plain text.\n");
+        let translated = ["```".to_string()];
+        assert!(matches!(
+            segments.reassemble(&translated),
+            Err(SegmentError::CodeBlocks { .. })
+        ));
+    }
+}
@@ -0,0 +1,263 @@
+//! Multi-part series.
+//!
+//! Articles that set `series = { name = "…", part = N }` are grouped by series
+//! name and ordered by part. Each part is rendered with links to its neighbours
+//! and to the full list of parts, and every series gets a landing page at
+//! `series/<slug>.html`, rendered by the theme.
+
+use std::collections::BTreeMap;
+
+use color_eyre::eyre::{self, eyre};
+use slug::slugify;
+
+use crate::article::{Article, ArticlePreview};
+
+/// Directory holding series landing pages, relative to the build directory.
+const SERIES_DIR: &str = "series";
+
+/// One part of a series, as linked from the other parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesEntry {
+    pub(crate) part: u32,
+    pub(crate) title: String,
+    pub(crate) output_file: String,
+}
+
+impl SeriesEntry {
+    /// Position of this part within the series.
+    #[must_use]
+    pub const fn part(&self) -> u32 {
+        self.part
+    }
+
+    /// Title of the part, in the locale of the linking article when translated.
+    #[must_use]
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Output file of the part, relative to the site root.
+    #[must_use]
+    pub fn output_file(&self) -> &str {
+        &self.output_file
+    }
+}
+
+/// Where an article sits in its series.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesNavigation {
+    pub(crate) name: String,
+    pub(crate) output_file: String,
+    pub(crate) part: u32,
+    pub(crate) parts: Vec<SeriesEntry>,
+    pub(crate) prev: Option<SeriesEntry>,
+    pub(crate) next: Option<SeriesEntry>,
+}
+
+impl SeriesNavigation {
+    /// Name of the series.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Output file of the series landing page, relative to the site root.
+    #[must_use]
+    pub fn output_file(&self) -> &str {
+        &self.output_file
+    }
+
+    /// Position of the article within the series.
+    #[must_use]
+    pub const fn part(&self) -> u32 {
+        self.part
+    }
+
+    /// Every part of the series, in order.
+    #[must_use]
+    pub fn parts(&self) -> &[SeriesEntry] {
+        &self.parts
+    }
+
+    /// The part before this one.
+    #[must_use]
+    pub const fn prev(&self) -> Option<&SeriesEntry> {
+        self.prev.as_ref()
+    }
+
+    /// The part after this one.
+    #[must_use]
+    pub const fn next(&self) -> Option<&SeriesEntry> {
+        self.next.as_ref()
+    }
+}
+
+/// A series and its parts in the default locale, for the landing page.
+#[derive(Debug, Clone)]
+pub struct Series {
+    name: String,
+    output_file: String,
+    parts: Vec<ArticlePreview>,
+}
+
+impl Series {
+    /// Name of the series.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Output file of the landing page, relative to the build directory.
+    #[must_use]
+    pub fn output_file(&self) -> &str {
+        &self.output_file
+    }
+
+    /// The parts of the series, ordered by part number.
+    #[must_use]
+    pub fn parts(&self) -> &[ArticlePreview] {
+        &self.parts
+    }
+
+    /// Take the parts of the series, ordered by part number.
+    #[must_use]
+    pub fn into_parts(self) -> Vec<ArticlePreview> {
+        self.parts
+    }
+}
+
+/// Every series in the workspace, keyed by name.
+#[derive(Debug, Default, Clone)]
+pub struct SeriesIndex {
+    series: BTreeMap<String, Series>,
+}
+
+impl SeriesIndex {
+    /// Group published default-locale articles by series.
+    ///
+    /// # Errors
+    /// Returns an error if a landing page would collide with an article or
+    /// with the landing page of a differently named series.
+    pub fn new<'a>(previews: impl IntoIterator<Item = &'a ArticlePreview>) -> eyre::Result<Self> {
+        let previews = previews.into_iter().collect::<Vec<_>>();
+        let mut series: BTreeMap<String, Series> = BTreeMap::new();
+        for preview in previews
+            .iter()
+            .filter(|preview| preview.is_default_locale() && preview.is_published())
+        {
+            let Some(membership) = preview.metadata().series() else {
+                continue;
+            };
+            series
+                .entry(membership.name().to_string())
+                .or_insert_with(|| Series {
+                    name: membership.name().to_string(),
+                    output_file: landing_file(membership.name()),
+                    parts: Vec::new(),
+                })
+                .parts
+                .push((*preview).clone());
+        }
+
+        let mut landings = BTreeMap::new();
+        for series in series.values_mut() {
+            series
+                .parts
+                .sort_by_key(|preview| (part_of(preview), preview.title().to_string()));
+            if previews
+                .iter()
+                .any(|preview| preview.output_file() == series.output_file)
+            {
+                return Err(eyre!(
+                    "landing page of series `{}` collides with the article at `{}`",
+                    series.name,
+                    series.output_file
+                ));
+            }
+            if let Some(other) = landings.insert(series.output_file.clone(), series.name.clone()) {
+                return Err(eyre!(
+                    "series `{other}` and `{}` both map to `{}`; rename one of them",
+                    series.name,
+                    series.output_file
+                ));
+            }
+        }
+        Ok(Self { series })
+    }
+
+    /// All series, ordered by name.
+    pub fn iter(&self) -> impl Iterator<Item = &Series> {
+        self.series.values()
+    }
+
+    /// Attach series navigation to `article`, preferring the parts' variants in
+    /// the article's locale.
+    pub fn attach(&self, article: &mut Article) {
+        let Some(membership) = article.metadata().series() else {
+            return;
+        };
+        let Some(series) = self.series.get(membership.name()) else {
+            return;
+        };
+        let locale = article.locale().to_string();
+        let parts = series
+            .parts
+            .iter()
+            .map(|preview| {
+                let (title, output_file) = preview.translation(&locale).map_or_else(
+                    || (preview.title().to_string(), preview.output_file()),
+                    |translation| {
+                        (
+                            translation.title().to_string(),
+                            translation.output_file().to_string(),
+                        )
+                    },
+                );
+                SeriesEntry {
+                    part: part_of(preview),
+                    title,
+                    output_file,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let own_file = article.output_file();
+        let position = parts.iter().position(|entry| entry.output_file == own_file);
+        let (prev, next) = position.map_or((None, None), |index| {
+            (
+                index.checked_sub(1).map(|prev| parts[prev].clone()),
+                parts.get(index + 1).cloned(),
+            )
+        });
+
+        article.series = Some(SeriesNavigation {
+            name: series.name.clone(),
+            output_file: series.output_file.clone(),
+            part: membership.part(),
+            parts,
+            prev,
+            next,
+        });
+    }
+
+    /// The series whose landing page is emitted at `output_file`.
+    #[must_use]
+    pub fn landing(&self, output_file: &str) -> Option<&Series> {
+        self.series
+            .values()
+            .find(|series| series.output_file == output_file)
+    }
+}
+
+fn part_of(preview: &ArticlePreview) -> u32 {
+    preview
+        .metadata()
+        .series()
+        .map_or(0, |membership| membership.part())
+}
+
+fn landing_file(name: &str) -> String {
+    let slug = slugify(name);
+    let slug = if slug.is_empty() { "untitled" } else { &slug };
+    format!("{SERIES_DIR}/{slug}.html")
+}
@@ -7,6 +7,7 @@ use std::{
         Arc,
         atomic::{AtomicBool, Ordering},
     },
+    time::{Duration, Instant},
 };
 
 use color_eyre::eyre::{self, Report, eyre};
@@ -24,9 +25,17 @@ use tracing::info;
 
 use crate::{
     article::{Article, ArticlePreview, FailToOpenArticle},
+    bundle::{self, Bundle},
     cache::RenderCache,
+    link::{self, LinkResolver},
+    locale::LocaleIndex,
+    order,
     plugin::PluginManager,
-    search,
+    redirect::RedirectTable,
+    related::RelatedArticles,
+    search::Searcher,
+    series::SeriesIndex,
+    timeline::Timeline,
     utils::write,
     workspace::Workspace,
 };
@@ -97,10 +106,61 @@ struct ServeState {
     article_guards: AsyncMutex<HashMap<String, Arc<AsyncMutex<()>>>>,
     index_lock: AsyncMutex<()>,
     index_dirty: AtomicBool,
-    search_lock: AsyncMutex<()>,
-    search_ready: AtomicBool,
+    search: AsyncMutex<Option<SearchState>>,
     index_fingerprint: AsyncMutex<Option<String>>,
     theme_fingerprint: String,
+    /// The current snapshot and when its stamp was last compared.
+    snapshot: AsyncMutex<Option<(Arc<Snapshot>, Instant)>>,
+}
+
+/// How long a snapshot is trusted before the sources are checked again, so
+/// that the requests of one page load share a single walk of `articles/`.
+const SNAPSHOT_RECHECK: Duration = Duration::from_millis(500);
+
+/// The search index and bundle, with the [`Snapshot::stamp`] they were built
+/// from.
+struct SearchState {
+    stamp: String,
+    searcher: Arc<Searcher>,
+}
+
+/// Everything derived from the whole workspace, rebuilt when a file under
+/// `articles/` changes.
+struct Snapshot {
+    stamp: String,
+    /// Previews of every article in every locale.
+    previews: Vec<ArticlePreview>,
+    resolver: LinkResolver,
+    redirects: RedirectTable,
+    series: SeriesIndex,
+    locales: LocaleIndex,
+    timeline: Timeline,
+}
+
+impl Snapshot {
+    async fn build(workspace: &Workspace, stamp: String) -> Result<Self, ServeError> {
+        let mut previews = Vec::new();
+        let mut stream = workspace.articles();
+        while let Some(article) = stream.try_next().await.map_err(ServeError::internal)? {
+            previews.push(article.preview().clone());
+        }
+        let redirects = RedirectTable::new(&previews).map_err(ServeError::Internal)?;
+        let series = SeriesIndex::new(&previews).map_err(ServeError::Internal)?;
+        let locales = LocaleIndex::new(&previews).map_err(ServeError::Internal)?;
+        let timeline = Timeline::new(&previews).map_err(ServeError::Internal)?;
+        redirects
+            .check_listings(&locales, &series, &timeline)
+            .map_err(ServeError::Internal)?;
+        Ok(Self {
+            stamp,
+            resolver: LinkResolver::new(&previews),
+            redirects,
+            series,
+            locales,
+            timeline,
+            previews,
+        })
+    }
 }
 
 impl ServeState {
@@ -116,12 +176,6 @@ impl ServeState {
             .map_err(|err| eyre!(err))?;
         let cache_path = workspace.cache_dir().join("cache.redb");
         let cache = RenderCache::load(cache_path).await?;
-        let search_ready = async_fs::metadata(workspace.build_dir().join(search_script_path()))
-            .await
-            .is_ok()
-            && async_fs::metadata(workspace.build_dir().join(search_wasm_path()))
-                .await
-                .is_ok();
         let index_exists = async_fs::metadata(workspace.build_dir().join("index.html"))
             .await
             .is_ok();
@@ -133,19 +187,12 @@ impl ServeState {
             article_guards: AsyncMutex::new(HashMap::new()),
             index_lock: AsyncMutex::new(()),
             index_dirty: AtomicBool::new(!index_exists),
-            search_lock: AsyncMutex::new(()),
-            search_ready: AtomicBool::new(search_ready),
+            search: AsyncMutex::new(None),
             index_fingerprint: AsyncMutex::new(None),
             theme_fingerprint,
+            snapshot: AsyncMutex::new(None),
         };
 
-        if !search_ready {
-            state
-                .ensure_search_assets()
-                .await
-                .map_err(|err| eyre!(format!("{err:?}")))?;
-        }
-
         Ok(state)
     }
 
@@ -165,23 +212,42 @@ impl ServeState {
         }
 
         if is_search_asset(&sanitized) {
-            self.ensure_search_assets().await?;
+            self.searcher().await?;
+        }
+
+        let is_page = matches!(
+            sanitized.extension().and_then(|ext| ext.to_str()),
+            None | Some("html")
+        );
+        if is_page && let Some(response) = self.redirect_for(raw_path).await? {
+            return Ok(response);
         }
 
         if let Some(path) = self.resolve_static(&sanitized).await? {
             return self.serve_file(&path).await;
         }
 
+        if !is_page
+            && let Some(key) = route_key(&sanitized)
+            && let Some(source) = bundle::source_for(&self.snapshot().await?.previews, &key)
+        {
+            return self.serve_file(&source).await;
+        }
+
         if sanitized.extension().and_then(|ext| ext.to_str()) == Some("html") {
             return self.render_article_for(&sanitized).await;
         }
 
         if sanitized.extension().is_none() {
-            let html_candidate = sanitized.with_extension("html");
-            match self.render_article_for(&html_candidate).await {
-                Ok(resp) => return Ok(resp),
-                Err(ServeError::NotFound) => {}
-                Err(err) => return Err(err),
+            for html_candidate in [
+                sanitized.with_extension("html"),
+                sanitized.join("index.html"),
+            ] {
+                match self.render_article_for(&html_candidate).await {
+                    Ok(resp) => return Ok(resp),
+                    Err(ServeError::NotFound) => {}
+                    Err(err) => return Err(err),
+                }
             }
         }
 
@@ -212,17 +278,37 @@ impl ServeState {
     }
 
     async fn render_article_for(&self, html_path: &Path) -> Result<Response, ServeError> {
-        let segments = path_segments(html_path).ok_or(ServeError::NotFound)?;
-        if segments.is_empty() {
-            return Err(ServeError::NotFound);
-        }
-        let (segments, locale) =
-            split_locale_from_segments(segments).ok_or(ServeError::NotFound)?;
+        let key = route_key(html_path).ok_or(ServeError::NotFound)?;
+        let snapshot = self.snapshot().await?;
+        // Output files follow the permalink pattern, so look the article up by
+        // the file it would be emitted to rather than by its source path.
+        let Some(preview) = snapshot
+            .previews
+            .iter()
+            .find(|preview| preview.output_file() == key)
+        else {
+            return self.render_listing_for(&key, &snapshot);
+        };
+        let mut segments = preview.category().segments().clone();
+        segments.push(preview.slug().to_string());
+        let locale = Some(preview.locale().to_string());
         let guard = self.article_guard(&segments).await;
         let _lock = guard.lock().await;
 
-        let article =
+        let mut article =
             Article::open_with_locale(self.workspace.clone(), segments.clone(), locale).await?;
+        let unresolved = snapshot.resolver.rewrite(&mut article);
+        Bundle::of(preview)
+            .await
+            .map_err(ServeError::from)?
+            .rewrite(&mut article);
+        link::enforce(
+            self.workspace.manifest().link_config().unresolved,
+            unresolved,
+        )
+        .map_err(ServeError::internal)?;
+        snapshot.series.attach(&mut article);
+        self.attach_related(&snapshot.previews, &mut article).await?;
         let html = self.render_article(article.clone()).await?;
 
         let output_path = self.workspace.build_dir().join(html_path);
@@ -234,11 +320,40 @@ impl ServeState {
             let mut guard = self.index_fingerprint.lock().await;
             *guard = None;
         }
-        self.search_ready.store(false, Ordering::SeqCst);
 
         Ok(html_response(html))
     }
 
+    /// Render a series landing page, an archive page or a locale index.
+    fn render_listing_for(&self, key: &str, snapshot: &Snapshot) -> Result<Response, ServeError> {
+        let html = if let Some(landing) = snapshot.series.landing(key) {
+            self.plugins.render_series(landing.clone())
+        } else if let Some((locale, listing)) = snapshot.locales.page(key) {
+            self.plugins.render_index(listing.to_vec(), Some(locale))
+        } else {
+            let page = snapshot.timeline.page(key).ok_or(ServeError::NotFound)?;
+            self.plugins.render_archive(page.clone())
+        }
+        .map_err(ServeError::internal)?;
+        Ok(html_response(html))
+    }
+
+    /// Rank related articles with the search index, building it if needed.
+    async fn attach_related(
+        &self,
+        previews: &[ArticlePreview],
+        article: &mut Article,
+    ) -> Result<(), ServeError> {
+        let count = self.workspace.manifest().related_config().count;
+        if count == 0 {
+            return Ok(());
+        }
+        let searcher = self.searcher().await?;
+        RelatedArticles::new(previews, count)
+            .attach(&searcher, article)
+            .map_err(ServeError::Internal)
+    }
+
     async fn render_article(&self, article: Article) -> Result<String, ServeError> {
         // Use async cache hit - no Mutex needed
         if let Some(html) = self.cache.hit(&article, &self.theme_fingerprint).await {
@@ -290,7 +405,7 @@ impl ServeState {
         let previews = self.collect_previews().await?;
         let rendered = self
             .plugins
-            .render_index(previews)
+            .render_index(previews, None)
             .map_err(ServeError::internal)?;
         write(&index_path, rendered.as_bytes())
             .await
@@ -305,30 +420,100 @@ impl ServeState {
     }
 
     async fn collect_previews(&self) -> Result<Vec<ArticlePreview>, ServeError> {
-        let mut previews = Vec::new();
-        let mut stream = self.workspace.articles();
-        while let Some(article) = stream.try_next().await.map_err(ServeError::internal)? {
-            if article.is_default_locale() {
-                previews.push(article.preview().clone());
-            }
-        }
+        let mut previews = self
+            .snapshot()
+            .await?
+            .previews
+            .iter()
+            .filter(|preview| preview.is_default_locale() && preview.is_published())
+            .cloned()
+            .collect::<Vec<_>>();
+        order::sort_previews(&mut previews);
         Ok(previews)
     }
 
-    async fn ensure_search_assets(&self) -> Result<(), ServeError> {
-        if self.search_ready.load(Ordering::SeqCst) && self.search_files_exist().await? {
-            return Ok(());
+    /// A 301 to the article that lists `raw_path` among its aliases.
+    async fn redirect_for(&self, raw_path: &str) -> Result<Option<Response>, ServeError> {
+        let snapshot = self.snapshot().await?;
+        let Some(target) = snapshot.redirects.target(raw_path) else {
+            return Ok(None);
+        };
+        let location =
+            HeaderValue::from_str(&format!("/{target}")).map_err(ServeError::internal)?;
+        let mut response = Response::new(Body::from(String::new()));
+        *response.status_mut() = StatusCode::MOVED_PERMANENTLY;
+        response.headers_mut().insert(header::LOCATION, location);
+        Ok(Some(response))
+    }
+
+    /// The workspace snapshot, rebuilt if any article file changed since it
+    /// was taken. Sources are checked at most once per [`SNAPSHOT_RECHECK`].
+    async fn snapshot(&self) -> Result<Arc<Snapshot>, ServeError> {
+        let mut guard = self.snapshot.lock().await;
+        if let Some((snapshot, checked)) = guard.as_ref()
+            && checked.elapsed() < SNAPSHOT_RECHECK
+        {
+            return Ok(snapshot.clone());
         }
-        let _guard = self.search_lock.lock().await;
-        if self.search_ready.load(Ordering::SeqCst) && self.search_files_exist().await? {
-            return Ok(());
+        let stamp = self.current_stamp().await?;
+        if let Some((snapshot, checked)) = guard.as_mut()
+            && snapshot.stamp == stamp
+        {
+            *checked = Instant::now();
+            return Ok(snapshot.clone());
         }
-        let output = self.workspace.build_dir();
-        search::emit_search_bundle(&self.workspace, &output, None)
+        let snapshot = Arc::new(Snapshot::build(&self.workspace, stamp).await?);
+        *guard = Some((snapshot.clone(), Instant::now()));
+        Ok(snapshot)
+    }
+
+    /// Fingerprint of the files under `articles/` and, when dates come from
+    /// git, of the commit the history was read at.
+    async fn current_stamp(&self) -> Result<String, ServeError> {
+        let workspace = self.workspace.clone();
+        let files = spawn_blocking(move || source_stamp(&workspace.articles_dir()))
             .await
-            .map_err(ServeError::internal)?;
-        self.search_ready.store(true, Ordering::SeqCst);
-        Ok(())
+            .map_err(ServeError::internal)??;
+        if !self.workspace.manifest().date_config().git {
+            return Ok(files);
+        }
+        // A commit changes git dates without touching the files.
+        let head = self.workspace.refresh_history().await.unwrap_or_default();
+        Ok(format!("{files}:{head}"))
+    }
+
+    /// The search index, reindexed and with its bundle written again
+    /// whenever the workspace changed since it was last built.
+    async fn searcher(&self) -> Result<Arc<Searcher>, ServeError> {
+        let stamp = self.snapshot().await?.stamp.clone();
+        let mut search = self.search.lock().await;
+        if let Some(state) = search.as_ref()
+            && state.stamp == stamp
+            && self.search_files_exist().await?
+        {
+            return Ok(state.searcher.clone());
+        }
+        let searcher = match search.take() {
+            Some(state) => state.searcher,
+            None => Arc::new(
+                Searcher::open(self.workspace.clone())
+                    .await
+                    .map_err(ServeError::Internal)?,
+            ),
+        };
+        searcher
+            .ensure_index(Some(&stamp))
+            .await
+            .map_err(ServeError::Internal)?;
+        searcher
+            .emit_bundle(&self.workspace.build_dir())
+            .await
+            .map_err(ServeError::Internal)?;
+        *search = Some(SearchState {
+            stamp,
+            searcher: searcher.clone(),
+        });
+        Ok(searcher)
     }
 
     async fn article_guard(&self, segments: &[String]) -> Arc<AsyncMutex<()>> {
@@ -347,16 +532,7 @@ impl ServeState {
     }
 
     async fn compute_index_fingerprint(&self) -> Result<String, ServeError> {
-        let mut hasher = Sha256::new();
-        let mut stream = self.workspace.articles();
-        while let Some(article) = stream.try_next().await.map_err(ServeError::internal)? {
-            if !article.is_default_locale() {
-                continue;
-            }
-            hasher.update(article.output_file().as_bytes());
-            hasher.update(article.sha256().as_bytes());
-        }
-        Ok(format!("{:x}", hasher.finalize()))
+        Ok(self.snapshot().await?.stamp.clone())
     }
 }
 
@@ -380,6 +556,8 @@ impl From<FailToOpenArticle> for ServeError {
                 ServeError::internal(eyre!("Workspace not found"))
             }
             FailToOpenArticle::FailToOpenMetadata(inner) => ServeError::internal(eyre!(inner)),
+            FailToOpenArticle::InvalidFrontMatter(inner) => ServeError::internal(eyre!(inner)),
+            FailToOpenArticle::InvalidSlug(inner) => ServeError::internal(eyre!(inner)),
         }
     }
 }
@@ -406,6 +584,36 @@ fn sanitize_relative_path(path: &str) -> Option<PathBuf> {
     Some(buf)
 }
 
+/// Hash of the path, size and modification time of every file under `root`:
+/// cheap enough to compute per request, and changed by any edit.
+fn source_stamp(root: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries.collect::<io::Result<Vec<_>>>()?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        entries.sort_by_key(std::fs::DirEntry::file_name);
+        for entry in entries {
+            let meta = entry.metadata()?;
+            hasher.update(entry.path().to_string_lossy().as_bytes());
+            if meta.is_dir() {
+                pending.push(entry.path());
+                continue;
+            }
+            hasher.update(meta.len().to_le_bytes());
+            if let Ok(modified) = meta.modified()
+                && let Ok(since) = modified.duration_since(std::time::UNIX_EPOCH)
+            {
+                hasher.update(since.as_nanos().to_le_bytes());
+            }
+        }
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
 fn guess_content_type(path: &Path) -> Option<HeaderValue> {
     mime_guess::from_path(path)
         .first_raw()
@@ -429,42 +637,21 @@ fn html_response(html: String) -> Response {
     response
 }
 
-fn path_segments(relative: &Path) -> Option<Vec<String>> {
-    let mut trimmed = relative.to_path_buf();
-    trimmed.set_extension("");
-    if trimmed.as_os_str().is_empty() {
-        return None;
-    }
-
+/// Build-relative output file for a request path, with `/` separators.
+fn route_key(relative: &Path) -> Option<String> {
     let mut segments = Vec::new();
-    for component in trimmed.components() {
+    for component in relative.components() {
         if let Component::Normal(segment) = component {
-            segments.push(segment.to_string_lossy().to_string());
+            segments.push(segment.to_str()?.to_string());
         } else {
             return None;
         }
     }
-    Some(segments)
-}
-
-fn split_locale_from_segments(mut segments: Vec<String>) -> Option<(Vec<String>, Option<String>)> {
     if segments.is_empty() {
-        return None;
-    }
-    let mut locale = None;
-    if let Some(last) = segments.pop() {
-        if let Some((slug, loc)) = last.split_once('.') {
-            if !slug.is_empty() && !loc.is_empty() {
-                segments.push(slug.to_string());
-                locale = Some(loc.to_string());
-            } else {
-                segments.push(last);
-            }
-        } else {
-            segments.push(last);
-        }
+        None
+    } else {
+        Some(segments.join("/"))
     }
-    Some((segments, locale))
 }
 
 fn select_port(host: &str, start: u16, allow_fallback: bool) -> eyre::Result<u16> {
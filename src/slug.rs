@@ -2,8 +2,13 @@ use slug::slugify;
 use std::{fmt, str::FromStr};
 
 #[derive(Debug, thiserror::Error)]
-#[error("generated article slug is empty")]
-pub struct EmptySlug;
+pub enum InvalidSlug {
+    #[error("article slug is empty")]
+    Empty,
+    /// The slug would escape its directory once used in a path.
+    #[error("article slug `{0}` may not contain `/`, `\\` or `..`, or start with `.`")]
+    Unsafe(String),
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ArticleSlug(String);
@@ -19,7 +24,7 @@ impl ArticleSlug {
         self.0
     }
 
-    pub fn from_title(title: &str) -> Result<Self, EmptySlug> {
+    pub fn from_title(title: &str) -> Result<Self, InvalidSlug> {
         let generated = slugify(title);
         ArticleSlug::from_str(&generated)
     }
@@ -38,12 +43,15 @@ impl fmt::Display for ArticleSlug {
 }
 
 impl FromStr for ArticleSlug {
-    type Err = EmptySlug;
+    type Err = InvalidSlug;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
         let trimmed = value.trim();
         if trimmed.is_empty() {
-            return Err(EmptySlug);
+            return Err(InvalidSlug::Empty);
+        }
+        if trimmed.contains(['/', '\\']) || trimmed.starts_with('.') || trimmed.contains("..") {
+            return Err(InvalidSlug::Unsafe(trimmed.to_string()));
         }
 
         Ok(Self(trimmed.to_string()))
@@ -0,0 +1,80 @@
+//! Word counts and reading time estimates.
+//!
+//! Text is split into words with Unicode word boundaries. Chinese and Japanese
+//! are not written with spaces, so each of their characters counts as one
+//! word and is read at a per-character rate instead.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Reading speed for space-separated scripts, in words per minute.
+const WORDS_PER_MINUTE: f64 = 230.0;
+/// Reading speed for Han and kana, in characters per minute.
+const CJK_CHARS_PER_MINUTE: f64 = 500.0;
+
+/// Running word count over the text of an article.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReadingStats {
+    words: usize,
+    cjk_chars: usize,
+}
+
+impl ReadingStats {
+    /// Count the words of `text`.
+    #[must_use]
+    pub fn measure(text: &str) -> Self {
+        let mut stats = Self::default();
+        stats.add(text);
+        stats
+    }
+
+    /// Count the words of another piece of text.
+    pub fn add(&mut self, text: &str) {
+        for word in text.unicode_words() {
+            let cjk = word.chars().filter(|&c| is_cjk(c)).count();
+            if cjk == 0 {
+                self.words += 1;
+            } else {
+                // Mixed tokens such as `Rust語` keep their Latin part as a word.
+                self.cjk_chars += cjk;
+                if word.chars().any(|c| !is_cjk(c)) {
+                    self.words += 1;
+                }
+            }
+        }
+    }
+
+    /// Words, counting each CJK character as one.
+    #[must_use]
+    pub const fn word_count(&self) -> usize {
+        self.words + self.cjk_chars
+    }
+
+    /// Estimated minutes to read the text; at least 1 for any non-empty text.
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn reading_minutes(&self) -> u32 {
+        if self.word_count() == 0 {
+            return 0;
+        }
+        let minutes =
+            self.words as f64 / WORDS_PER_MINUTE + self.cjk_chars as f64 / CJK_CHARS_PER_MINUTE;
+        (minutes.ceil() as u32).max(1)
+    }
+}
+
+/// Han ideographs and Japanese kana. Hangul is written with spaces and is
+/// counted like other scripts.
+const fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30ff}'     // Hiragana, Katakana
+        | '\u{3400}'..='\u{4dbf}'   // CJK Extension A
+        | '\u{4e00}'..='\u{9fff}'   // CJK Unified Ideographs
+        | '\u{f900}'..='\u{faff}'   // CJK Compatibility Ideographs
+        | '\u{ff66}'..='\u{ff9f}'   // Halfwidth Katakana
+        | '\u{20000}'..='\u{2fa1f}' // Extensions B and later
+    )
+}
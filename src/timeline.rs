@@ -0,0 +1,216 @@
+//! Chronological archive pages.
+//!
+//! Published default-locale articles are grouped by the year and month of
+//! their `created` date. `thought generate` renders `archive/index.html` with
+//! every year, `<year>/index.html` for each year and `<year>/<month>/index.html`
+//! for each month through the theme's `generate_archive`.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use color_eyre::eyre::{self, eyre};
+
+use crate::article::ArticlePreview;
+
+/// Output file of the page listing every year.
+const ARCHIVE_FILE: &str = "archive/index.html";
+
+/// Articles created in one month, newest first.
+#[derive(Debug, Clone)]
+pub struct ArchiveMonth {
+    pub(crate) month: u8,
+    pub(crate) articles: Vec<ArticlePreview>,
+}
+
+impl ArchiveMonth {
+    /// Month number, 1 to 12.
+    #[must_use]
+    pub const fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Articles created in the month, newest first.
+    #[must_use]
+    pub fn articles(&self) -> &[ArticlePreview] {
+        &self.articles
+    }
+}
+
+/// Articles created in one year, newest month first.
+#[derive(Debug, Clone)]
+pub struct ArchiveYear {
+    pub(crate) year: i32,
+    pub(crate) months: Vec<ArchiveMonth>,
+}
+
+impl ArchiveYear {
+    #[must_use]
+    pub const fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Months with at least one article, newest first.
+    #[must_use]
+    pub fn months(&self) -> &[ArchiveMonth] {
+        &self.months
+    }
+}
+
+/// One archive page: the whole archive, a year, or a month.
+#[derive(Debug, Clone)]
+pub struct ArchivePage {
+    pub(crate) year: Option<i32>,
+    pub(crate) month: Option<u8>,
+    pub(crate) output_file: String,
+    pub(crate) years: Vec<ArchiveYear>,
+}
+
+impl ArchivePage {
+    /// Year the page is limited to, if any.
+    #[must_use]
+    pub const fn year(&self) -> Option<i32> {
+        self.year
+    }
+
+    /// Month the page is limited to, if any.
+    #[must_use]
+    pub const fn month(&self) -> Option<u8> {
+        self.month
+    }
+
+    /// Output file of the page, relative to the build directory.
+    #[must_use]
+    pub fn output_file(&self) -> &str {
+        &self.output_file
+    }
+
+    /// Articles on the page, grouped by year and month, newest first.
+    #[must_use]
+    pub fn years(&self) -> &[ArchiveYear] {
+        &self.years
+    }
+}
+
+/// Published articles grouped by creation date.
+#[derive(Debug, Default, Clone)]
+pub struct Timeline {
+    /// The whole archive, then each year followed by its months.
+    pages: Vec<ArchivePage>,
+    /// Position in `pages` by output file.
+    by_output: HashMap<String, usize>,
+}
+
+impl Timeline {
+    /// Group the published default-locale articles among `previews`.
+    ///
+    /// # Errors
+    /// Returns an error if an archive page would overwrite an article.
+    pub fn new<'a>(previews: impl IntoIterator<Item = &'a ArticlePreview>) -> eyre::Result<Self> {
+        let previews = previews.into_iter().collect::<Vec<_>>();
+        let mut grouped: BTreeMap<i32, BTreeMap<u8, Vec<ArticlePreview>>> = BTreeMap::new();
+        for preview in previews
+            .iter()
+            .filter(|preview| preview.is_default_locale() && preview.is_published())
+        {
+            let created = preview.metadata().created();
+            grouped
+                .entry(created.year())
+                .or_default()
+                .entry(created.month().into())
+                .or_default()
+                .push((*preview).clone());
+        }
+
+        let years: Vec<ArchiveYear> = grouped
+            .into_iter()
+            .rev()
+            .map(|(year, months)| ArchiveYear {
+                year,
+                months: months
+                    .into_iter()
+                    .rev()
+                    .map(|(month, mut articles)| {
+                        sort_newest_first(&mut articles);
+                        ArchiveMonth { month, articles }
+                    })
+                    .collect(),
+            })
+            .collect();
+        let pages = archive_pages(&years);
+
+        let outputs = previews
+            .iter()
+            .map(|preview| preview.output_file())
+            .collect::<HashSet<_>>();
+        if let Some(page) = pages
+            .iter()
+            .find(|page| outputs.contains(&page.output_file))
+        {
+            return Err(eyre!(
+                "archive page `{}` collides with an article; adjust its slug or `permalink` in Thought.toml",
+                page.output_file
+            ));
+        }
+        let by_output = pages
+            .iter()
+            .enumerate()
+            .map(|(index, page)| (page.output_file.clone(), index))
+            .collect();
+        Ok(Self { pages, by_output })
+    }
+
+    /// Every archive page: the whole archive, then each year and its months.
+    #[must_use]
+    pub fn pages(&self) -> &[ArchivePage] {
+        &self.pages
+    }
+
+    /// The archive page emitted at `output_file`.
+    #[must_use]
+    pub fn page(&self, output_file: &str) -> Option<&ArchivePage> {
+        self.by_output
+            .get(output_file)
+            .map(|&index| &self.pages[index])
+    }
+}
+
+/// The page listing all of `years`, then a page per year and per month.
+fn archive_pages(years: &[ArchiveYear]) -> Vec<ArchivePage> {
+    let mut pages = vec![ArchivePage {
+        year: None,
+        month: None,
+        output_file: ARCHIVE_FILE.to_string(),
+        years: years.to_vec(),
+    }];
+    for year in years {
+        pages.push(ArchivePage {
+            year: Some(year.year),
+            month: None,
+            output_file: format!("{}/index.html", year.year),
+            years: vec![year.clone()],
+        });
+        for month in &year.months {
+            pages.push(ArchivePage {
+                year: Some(year.year),
+                month: Some(month.month),
+                output_file: format!("{}/{:02}/index.html", year.year, month.month),
+                years: vec![ArchiveYear {
+                    year: year.year,
+                    months: vec![month.clone()],
+                }],
+            });
+        }
+    }
+    pages
+}
+
+/// Order previews by creation date, newest first, then by title and output
+/// file so that articles created at the same time keep a stable order.
+fn sort_newest_first(previews: &mut [ArticlePreview]) {
+    previews.sort_by(|a, b| {
+        b.metadata()
+            .created()
+            .cmp(&a.metadata().created())
+            .then_with(|| a.title().cmp(b.title()))
+            .then_with(|| a.output_file().cmp(&b.output_file()))
+    });
+}
@@ -0,0 +1,111 @@
+//! Translation memory kept in `.thought/translation_memory.redb`.
+//!
+//! `thought translate` looks up every segment here before asking a model and
+//! stores the segments it had to request, so unchanged paragraphs are never
+//! sent twice. Output of the `dictionary` provider is not stored.
+
+use std::{path::PathBuf, sync::Arc};
+
+use color_eyre::eyre;
+use redb::{Database, ReadableDatabase, TableDefinition};
+use sha2::{Digest, Sha256};
+use tokio::task::spawn_blocking;
+
+const MEMORY_TABLE: TableDefinition<&str, &str> = TableDefinition::new("translation_memory");
+
+/// Earlier translations of Markdown segments, keyed by source locale, target
+/// locale and segment text.
+///
+/// Reusing them saves requests for paragraphs that did not change and keeps
+/// the wording of repeated segments consistent across articles.
+#[derive(Debug, Clone)]
+pub struct TranslationMemory {
+    db: Arc<Database>,
+}
+
+impl TranslationMemory {
+    /// Open the memory at `path`, creating the file and its parent
+    /// directories if needed.
+    ///
+    /// # Errors
+    /// Returns an error if the directories cannot be created or the database
+    /// cannot be opened.
+    pub async fn load(path: PathBuf) -> eyre::Result<Self> {
+        let db = spawn_blocking(move || -> eyre::Result<Database> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let db = Database::create(path.as_path())?;
+            let txn = db.begin_write()?;
+            txn.open_table(MEMORY_TABLE)?;
+            txn.commit()?;
+            Ok(db)
+        })
+        .await??;
+        Ok(Self { db: Arc::new(db) })
+    }
+
+    /// Look up the remembered translation of each of `segments` from `source`
+    /// into `target`.
+    pub async fn lookup(
+        &self,
+        source: &str,
+        target: &str,
+        segments: &[String],
+    ) -> eyre::Result<Vec<Option<String>>> {
+        let keys = segments
+            .iter()
+            .map(|segment| Self::key(source, target, segment))
+            .collect::<Vec<_>>();
+        let db = Arc::clone(&self.db);
+        spawn_blocking(move || -> eyre::Result<Vec<Option<String>>> {
+            let txn = db.begin_read()?;
+            let table = txn.open_table(MEMORY_TABLE)?;
+            keys.iter()
+                .map(|key| {
+                    Ok(table
+                        .get(key.as_str())?
+                        .map(|value| value.value().to_string()))
+                })
+                .collect()
+        })
+        .await?
+    }
+
+    /// Remember `translations`, pairs of source segment and its translation
+    /// from `source` into `target`.
+    pub async fn store(
+        &self,
+        source: &str,
+        target: &str,
+        translations: Vec<(String, String)>,
+    ) -> eyre::Result<()> {
+        let entries = translations
+            .into_iter()
+            .map(|(segment, translation)| (Self::key(source, target, &segment), translation))
+            .collect::<Vec<_>>();
+        let db = Arc::clone(&self.db);
+        spawn_blocking(move || -> eyre::Result<()> {
+            let txn = db.begin_write()?;
+            {
+                let mut table = txn.open_table(MEMORY_TABLE)?;
+                for (key, translation) in &entries {
+                    table.insert(key.as_str(), translation.as_str())?;
+                }
+            }
+            txn.commit()?;
+            Ok(())
+        })
+        .await?
+    }
+
+    fn key(source: &str, target: &str, segment: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(source.as_bytes());
+        hasher.update([0]);
+        hasher.update(target.as_bytes());
+        hasher.update([0]);
+        hasher.update(segment.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+}
@@ -25,3 +25,23 @@ pub async fn read_to_string(path: impl AsRef<Path>) -> Result<String, std::io::E
     reader.read_to_string(&mut buf).await?;
     Ok(buf)
 }
+
+/// Decode `%XX` escapes, replacing invalid UTF-8 in the result.
+pub fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%'
+            && let Some(hex) = input.get(index + 1..index + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            decoded.push(byte);
+            index += 3;
+            continue;
+        }
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -1,24 +1,27 @@
 use crate::{
-    article::{Article, FailToOpenArticle},
+    article::{Article, ArticleLayout, FailToOpenArticle, is_article_dir, is_article_file},
     category::{Category, FailToOpenCategory},
-    engine::Engine,
+    engine::{Engine, GenerateOptions},
+    history::{self, HistoryDates},
     metadata::{
         ArticleMetadata, CategoryMetadata, FailToOpenMetadata, MetadataExt, PluginEntry,
         PluginRegistry, WorkspaceManifest,
     },
-    slug::{ArticleSlug, EmptySlug},
+    slug::{ArticleSlug, InvalidSlug},
     utils::write,
 };
 use color_eyre::eyre::{self, eyre};
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
 use thiserror::Error;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
 use tokio::{
     fs::{self as async_fs, create_dir},
-    sync::mpsc,
+    sync::{Mutex, mpsc},
+    task,
 };
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
@@ -27,6 +30,7 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 /// /workspace-root
 /// ├── .thought
 /// ├── Thought.toml
+/// ├── Glossary.toml (optional)
 /// ├── articles
 /// │   ├── category1
 /// │   │   ├── Article.toml
@@ -44,10 +48,12 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 #[derive(Debug, Clone)]
 pub struct Workspace(Arc<WorkspaceInner>);
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 struct WorkspaceInner {
     path: PathBuf,
     manifest: WorkspaceManifest,
+    /// The last history walk; `None` until it is first needed.
+    history: Mutex<Option<Option<Arc<HistoryDates>>>>,
 }
 
 #[derive(Debug, Error)]
@@ -55,7 +61,7 @@ pub enum FailToCreateArticle {
     #[error("Invalid article path, must include at least a slug")]
     InvalidPath,
     #[error("{0}")]
-    EmptySlug(#[from] EmptySlug),
+    InvalidSlug(#[from] InvalidSlug),
     #[error("Fail to open category: {0}")]
     Category(#[from] FailToOpenCategory),
     #[error("IO error: {0}")]
@@ -74,6 +80,7 @@ impl Workspace {
             WorkspaceInner {
                 path: path.as_ref().to_path_buf(),
                 manifest,
+                history: Mutex::new(None),
             }
             .into(),
         )
@@ -100,6 +107,51 @@ impl Workspace {
         self.root().join(".thought")
     }
 
+    /// Terms with a fixed translation, see [`crate::glossary`].
+    pub fn glossary_path(&self) -> PathBuf {
+        self.root().join("Glossary.toml")
+    }
+
+    /// Commit dates of the files under `articles/` when `[dates] git = true`.
+    pub(crate) async fn history_dates(&self) -> Option<Arc<HistoryDates>> {
+        if !self.manifest().date_config().git {
+            return None;
+        }
+        self.history().await
+    }
+
+    /// Commit dates and authors of the files under `articles/`, walked once
+    /// per workspace handle until [`Self::refresh_history`] is called.
+    async fn history(&self) -> Option<Arc<HistoryDates>> {
+        let mut history = self.0.history.lock().await;
+        if let Some(history) = history.as_ref() {
+            return history.clone();
+        }
+        let dates = self.walk_history().await;
+        *history = Some(dates.clone());
+        dates
+    }
+
+    /// Pick up commits made since the history was last walked, returning the
+    /// commit it now reflects. Only new commits are visited.
+    pub(crate) async fn refresh_history(&self) -> Option<String> {
+        // Holding the lock also keeps two walks from opening the cache at once.
+        let mut history = self.0.history.lock().await;
+        let dates = self.walk_history().await;
+        *history = Some(dates.clone());
+        dates.map(|dates| dates.head().to_string())
+    }
+
+    async fn walk_history(&self) -> Option<Arc<HistoryDates>> {
+        let root = self.articles_dir();
+        let cache = self.cache_dir().join("history.redb");
+        task::spawn_blocking(move || history::history_dates(&root, &cache))
+            .await
+            .ok()
+            .flatten()
+            .map(Arc::new)
+    }
+
     pub async fn create(root: impl AsRef<Path>, name: String) -> color_eyre::eyre::Result<Self> {
         // create workspace directory
 
@@ -122,7 +174,9 @@ impl Workspace {
         let workspace = Self::new(&root, manifest);
         ensure_root_category(&workspace).await?;
 
-        let _ = workspace.create_article("Hello,world", None).await?;
+        let _ = workspace
+            .create_article("Hello,world", None, ArticleLayout::Directory)
+            .await?;
 
         Ok(workspace)
     }
@@ -157,6 +211,9 @@ impl Workspace {
             if segment == "assets" {
                 return Err(eyre!("Category name 'assets' is reserved"));
             }
+            if segment == "." || segment == ".." {
+                return Err(eyre!("Invalid category name '{segment}'"));
+            }
             current.push(segment);
             async_fs::create_dir_all(&current).await?;
 
@@ -171,41 +228,131 @@ impl Workspace {
         Ok(())
     }
 
+    /// Open the category at `segments`, e.g. `["guides", "rust"]`. An empty
+    /// path opens the root category.
+    ///
+    /// # Errors
+    /// Returns `FailToOpenCategory::NotFound` if there is no `Category.toml` at that path.
+    pub async fn category(&self, segments: &[String]) -> Result<Category, FailToOpenCategory> {
+        if segments.is_empty() {
+            ensure_root_category(self)
+                .await
+                .map_err(|_| FailToOpenCategory::WorkspaceNotFound)?;
+        }
+        let not_found = || FailToOpenCategory::NotFound(segments.join("/"));
+        if segments
+            .iter()
+            .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+        {
+            return Err(not_found());
+        }
+        let dir = segments
+            .iter()
+            .fold(self.articles_dir(), |dir, segment| dir.join(segment));
+        if async_fs::metadata(dir.join("Category.toml")).await.is_err() {
+            return Err(not_found());
+        }
+        Category::open(self.clone(), dir).await
+    }
+
+    /// Delete the category at `segments`.
+    ///
+    /// Unless `force` is set, only empty categories (no articles, no
+    /// subcategories) can be removed.
+    ///
+    /// # Errors
+    /// Returns an error for the root category, a missing category, or a
+    /// non-empty one without `force`.
+    pub async fn remove_category(&self, segments: &[String], force: bool) -> eyre::Result<()> {
+        if segments.is_empty() {
+            return Err(eyre!("The root category cannot be removed"));
+        }
+        let category = self.category(segments).await?;
+
+        if !force {
+            let articles = category.list_articles().count().await;
+            let children = category.list_categories().count().await;
+            if articles + children > 0 {
+                return Err(eyre!(
+                    "Category `{}` still contains {articles} article(s) and {children} subcategory(ies)",
+                    category.path()
+                ));
+            }
+            let mut entries = async_fs::read_dir(category.dir()).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                if entry.file_name() != "Category.toml" {
+                    return Err(eyre!(
+                        "Category `{}` still contains `{}`",
+                        category.path(),
+                        entry.file_name().to_string_lossy()
+                    ));
+                }
+            }
+        }
+
+        async_fs::remove_dir_all(category.dir()).await?;
+        Ok(())
+    }
+
     pub async fn save(&self) -> Result<(), std::io::Error> {
         let manifest_path = self.root().join("Thought.toml");
         self.0.manifest.save_to_file(manifest_path).await
     }
 
+    /// Create an article titled `title` in `category` (the root when `None`),
+    /// either as a `<slug>/` directory or as a single `<slug>.md` file.
+    /// Existing files are left untouched.
     pub async fn create_article(
         &self,
         title: impl Into<String>,
         category: Option<Category>,
+        layout: ArticleLayout,
     ) -> Result<Article, FailToCreateArticle> {
         let title = title.into();
         let slug = ArticleSlug::from_title(&title)?;
 
-        let mut article_dir = if let Some(ref category) = category {
+        let category_dir = if let Some(ref category) = category {
             category.dir()
         } else {
             self.articles_dir()
         };
-        article_dir.push(slug.as_str());
 
-        let metadata_path = article_dir.join("Article.toml");
-        if async_fs::metadata(&metadata_path).await.is_err() {
-            let metadata = ArticleMetadata::new(self.manifest().owner().to_string());
-            metadata
-                .save_to_file(&metadata_path)
-                .await
-                .map_err(FailToCreateArticle::Io)?;
-        }
+        match layout {
+            ArticleLayout::Directory => {
+                let article_dir = category_dir.join(slug.as_str());
+                let metadata_path = article_dir.join("Article.toml");
+                if async_fs::metadata(&metadata_path).await.is_err() {
+                    let metadata = ArticleMetadata::new(self.manifest().owner().to_string());
+                    metadata
+                        .save_to_file(&metadata_path)
+                        .await
+                        .map_err(FailToCreateArticle::Io)?;
+                }
 
-        let content_path = article_dir.join("article.md");
-        if async_fs::metadata(&content_path).await.is_err() {
-            let template = format!("# {title}\n\n");
-            write(&content_path, template.as_bytes())
-                .await
-                .map_err(FailToCreateArticle::Io)?;
+                let content_path = article_dir.join("article.md");
+                if async_fs::metadata(&content_path).await.is_err() {
+                    let template = format!("# {title}\n\n");
+                    write(&content_path, template.as_bytes())
+                        .await
+                        .map_err(FailToCreateArticle::Io)?;
+                }
+            }
+            ArticleLayout::File => {
+                let content_path = category_dir.join(format!("{slug}.md"));
+                if async_fs::metadata(&content_path).await.is_err() {
+                    // Pin the date in front matter, as `Article.toml` would;
+                    // otherwise it follows the file's modification time.
+                    let created = OffsetDateTime::now_utc()
+                        .replace_nanosecond(0)
+                        .ok()
+                        .and_then(|now| now.format(&Rfc3339).ok())
+                        .unwrap_or_default();
+                    let template = format!("---\ndate: {created}\n---\n\n# {title}\n\n");
+                    write(&content_path, template.as_bytes())
+                        .await
+                        .map_err(FailToCreateArticle::Io)?;
+                }
+            }
         }
 
         Article::open(
@@ -226,12 +373,20 @@ impl Workspace {
             FailToOpenArticle::FailToOpenMetadata(inner) => {
                 FailToCreateArticle::Io(std::io::Error::other(inner))
             }
+            FailToOpenArticle::InvalidFrontMatter(inner) => {
+                FailToCreateArticle::Io(std::io::Error::other(inner))
+            }
+            FailToOpenArticle::InvalidSlug(inner) => FailToCreateArticle::InvalidSlug(inner),
         })
     }
 
-    pub async fn generate(&self, output: impl AsRef<std::path::Path>) -> eyre::Result<()> {
+    pub async fn generate(
+        &self,
+        output: impl AsRef<std::path::Path>,
+        options: GenerateOptions,
+    ) -> eyre::Result<()> {
         let engine = Engine::new(self.clone()).await?;
-        engine.generate(output).await
+        engine.generate(output, options).await
     }
 
     /// List all categories recursively in the workspace
@@ -404,75 +559,76 @@ async fn walk_articles(
             .map_err(|_| FailToOpenArticle::WorkspaceNotFound)?
         {
             let path = entry.path();
-            if entry
+            let is_dir = entry
                 .file_type()
                 .await
                 .map_err(|_| FailToOpenArticle::WorkspaceNotFound)?
-                .is_dir()
-            {
-                if async_fs::metadata(path.join("Article.toml")).await.is_ok() {
-                    let relative = path
-                        .strip_prefix(&root)
-                        .map_err(|_| FailToOpenArticle::WorkspaceNotFound)?;
-                    let segments = relative
-                        .components()
-                        .map(|component| {
-                            component
-                                .as_os_str()
-                                .to_str()
-                                .map(|segment| segment.to_string())
-                                .ok_or(FailToOpenArticle::WorkspaceNotFound)
-                        })
-                        .collect::<Result<Vec<_>, _>>()?;
-
-                    let primary = Article::open(workspace.clone(), segments.clone()).await;
-                    match primary {
-                        Ok(article) => {
-                            let locales = article
-                                .translations()
-                                .iter()
-                                .map(|t| t.locale.clone())
-                                .collect::<Vec<_>>();
-
-                            if tx.send(Ok(article.clone())).is_err() {
-                                return Ok(());
-                            }
-
-                            for locale in locales {
-                                if locale == article.locale() {
-                                    continue;
-                                }
-                                match Article::open_with_locale(
-                                    workspace.clone(),
-                                    segments.clone(),
-                                    Some(locale),
-                                )
-                                .await
-                                {
-                                    Ok(variant) => {
-                                        if tx.send(Ok(variant)).is_err() {
-                                            return Ok(());
-                                        }
-                                    }
-                                    Err(err) => {
-                                        if tx.send(Err(err)).is_err() {
-                                            return Ok(());
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Err(err) => {
-                            if tx.send(Err(err)).is_err() {
-                                return Ok(());
-                            }
-                        }
-                    }
+                .is_dir();
+            let article_path = if is_dir {
+                if !is_article_dir(&path).await {
+                    stack.push(path);
                     continue;
                 }
-                stack.push(path);
+                path
+            } else if is_article_file(&path).await {
+                path.with_extension("")
+            } else {
+                continue;
+            };
+
+            let relative = article_path
+                .strip_prefix(&root)
+                .map_err(|_| FailToOpenArticle::WorkspaceNotFound)?;
+            let segments = relative
+                .components()
+                .map(|component| {
+                    component
+                        .as_os_str()
+                        .to_str()
+                        .map(|segment| segment.to_string())
+                        .ok_or(FailToOpenArticle::WorkspaceNotFound)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if !send_article_variants(&workspace, segments, &tx).await {
+                return Ok(());
             }
         }
     }
     Ok(())
 }
+
+/// Open the article at `segments` and send it followed by each of its locale
+/// variants. Returns `false` once the receiver has gone away.
+async fn send_article_variants(
+    workspace: &Workspace,
+    segments: Vec<String>,
+    tx: &mpsc::UnboundedSender<Result<Article, FailToOpenArticle>>,
+) -> bool {
+    let article = match Article::open(workspace.clone(), segments.clone()).await {
+        Ok(article) => article,
+        Err(err) => return tx.send(Err(err)).is_ok(),
+    };
+    let locales = article
+        .translations()
+        .iter()
+        .map(|t| t.locale.clone())
+        .collect::<Vec<_>>();
+    let primary_locale = article.locale().to_string();
+
+    if tx.send(Ok(article)).is_err() {
+        return false;
+    }
+
+    for locale in locales {
+        if locale == primary_locale {
+            continue;
+        }
+        let variant =
+            Article::open_with_locale(workspace.clone(), segments.clone(), Some(locale)).await;
+        if tx.send(variant).is_err() {
+            return false;
+        }
+    }
+    true
+}
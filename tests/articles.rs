@@ -0,0 +1,160 @@
+mod common;
+
+use std::{fs, path::Path};
+
+use common::write;
+use futures::TryStreamExt;
+use thought::{
+    article::{Article, ArticleLayout},
+    front_matter,
+    workspace::Workspace,
+};
+
+async fn paths(workspace: &Workspace) -> Vec<String> {
+    let mut paths = workspace
+        .articles()
+        .map_ok(|article| article.segments().join("/"))
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+#[tokio::test]
+async fn creates_single_file_articles() {
+    let parent = tempfile::tempdir().unwrap();
+    let workspace = Workspace::create(parent.path(), "blog".to_string())
+        .await
+        .unwrap();
+    workspace
+        .create_category(vec!["guides".to_string()], "")
+        .await
+        .unwrap();
+    let guides = workspace.category(&["guides".to_string()]).await.unwrap();
+
+    let article = workspace
+        .create_article("First Steps", Some(guides), ArticleLayout::File)
+        .await
+        .unwrap();
+    assert_eq!(article.layout(), ArticleLayout::File);
+    assert_eq!(article.title(), "First Steps");
+
+    let file = workspace.articles_dir().join("guides/first-steps.md");
+    let content = fs::read_to_string(&file).unwrap();
+    assert!(content.starts_with("---\ndate: "), "{content}");
+    assert!(content.ends_with("---\n\n# First Steps\n\n"), "{content}");
+    assert!(!workspace.articles_dir().join("guides/first-steps").exists());
+    let (front_matter, _) = front_matter::split(&content).unwrap();
+    assert_eq!(
+        front_matter.unwrap().created,
+        Some(article.metadata().created())
+    );
+}
+
+#[tokio::test]
+async fn skips_documentation_files_in_categories() {
+    let parent = tempfile::tempdir().unwrap();
+    let workspace = Workspace::create(parent.path(), "blog".to_string())
+        .await
+        .unwrap();
+    workspace
+        .create_category(vec!["guides".to_string()], "")
+        .await
+        .unwrap();
+    let root = workspace.root().to_path_buf();
+    for file in ["README.md", "Notes.md", "_index.md", "changelog.md"] {
+        write(
+            &root,
+            &format!("articles/guides/{file}"),
+            "# Not an article\n",
+        );
+    }
+    write(&root, "articles/guides/first-steps.md", "# First Steps\n");
+    write(&root, "articles/readme.md", "# Not an article either\n");
+
+    assert_eq!(
+        paths(&workspace).await,
+        ["guides/first-steps", "hello-world"]
+    );
+}
+
+#[tokio::test]
+async fn leaves_drafts_and_future_posts_unpublished() {
+    let parent = tempfile::tempdir().unwrap();
+    let workspace = Workspace::create(parent.path(), "blog".to_string())
+        .await
+        .unwrap();
+    let root = workspace.root().to_path_buf();
+    write(
+        &root,
+        "articles/draft.md",
+        "---\ndraft: true\n---\n\n# Draft\n",
+    );
+    write(
+        &root,
+        "articles/later.md",
+        "---\ndate: 2999-01-01\n---\n\n# Later\n",
+    );
+    write(
+        &root,
+        "articles/past.md",
+        "---\ndate: 2024-05-01\n---\n\n# Past\n",
+    );
+
+    let mut published = workspace
+        .articles()
+        .try_filter(|article| futures::future::ready(article.is_published()))
+        .map_ok(|article| article.slug().to_string())
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    published.sort();
+    assert_eq!(published, ["hello-world", "past"]);
+}
+
+#[tokio::test]
+async fn takes_authors_from_git_only_when_dates_follow_git() {
+    let parent = tempfile::tempdir().unwrap();
+    let workspace = Workspace::create(parent.path(), "blog".to_string())
+        .await
+        .unwrap();
+    let root = workspace.root().to_path_buf();
+    write(&root, "articles/committed.md", "# Committed\n");
+
+    let repo = git2::Repository::init(&root).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("articles/committed.md")).unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::new(
+        "Ada Committer",
+        "ada@example.com",
+        &git2::Time::new(1_700_000_000, 0),
+    )
+    .unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "Add", &tree, &[])
+        .unwrap();
+
+    let author = |workspace: Workspace| async move {
+        Article::open(workspace, vec!["committed".to_string()])
+            .await
+            .unwrap()
+            .metadata()
+            .author()
+            .to_string()
+    };
+    assert_eq!(
+        author(workspace.clone()).await,
+        workspace.manifest().owner()
+    );
+
+    let manifest = fs::read_to_string(workspace.manifest_path()).unwrap();
+    fs::write(
+        workspace.manifest_path(),
+        manifest.replace("git = false", "git = true"),
+    )
+    .unwrap();
+    let workspace = Workspace::open(&root).await.unwrap();
+    assert_eq!(author(workspace).await, "Ada Committer");
+}
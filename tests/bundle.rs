@@ -0,0 +1,145 @@
+mod common;
+
+use common::write;
+use futures::TryStreamExt;
+use thought::{
+    article::{Article, ArticlePreview},
+    bundle::{self, Bundle},
+    workspace::Workspace,
+};
+
+/// A workspace whose `guides/diagrams` article keeps a few files next to it.
+async fn workspace(permalink: Option<&str>) -> (tempfile::TempDir, Workspace) {
+    let parent = tempfile::tempdir().unwrap();
+    let workspace = Workspace::create(parent.path(), "blog".to_string())
+        .await
+        .unwrap();
+    workspace
+        .create_category(vec!["guides".to_string()], "")
+        .await
+        .unwrap();
+    let root = workspace.root().to_path_buf();
+    if let Some(permalink) = permalink {
+        let manifest = std::fs::read_to_string(root.join("Thought.toml")).unwrap();
+        let manifest = manifest.replace(
+            "permalink = \"/{category}/{slug}.html\"",
+            &format!("permalink = \"{permalink}\""),
+        );
+        write(&root, "Thought.toml", manifest);
+    }
+    write(
+        &root,
+        "articles/guides/diagrams/article.md",
+        "# Diagrams\n\n![A diagram](diagram.png \"diagram.png\") and \
+         [the paper](./files/my%20paper.pdf#page=2)\n\n\
+         [elsewhere](https://example.com/diagram.png) [missing](missing.png)\n",
+    );
+    write(
+        &root,
+        "articles/guides/diagrams/ja.md",
+        "# 図\n\n![図](diagram.png)\n",
+    );
+    write(
+        &root,
+        "articles/guides/diagrams/Article.toml",
+        "created = \"2024-05-01T00:00:00Z\"\nauthor = \"Ada\"\n",
+    );
+    write(
+        &root,
+        "articles/guides/diagrams/diagram.png",
+        [137_u8, 80, 78, 71],
+    );
+    write(&root, "articles/guides/diagrams/files/my paper.pdf", "%PDF");
+    write(&root, "articles/guides/diagrams/.DS_Store", "");
+    let workspace = Workspace::open(&root).await.unwrap();
+    (parent, workspace)
+}
+
+async fn open(workspace: &Workspace, locale: &str) -> Article {
+    Article::open_with_locale(
+        workspace.clone(),
+        ["guides", "diagrams"].map(String::from),
+        Some(locale.to_string()),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn publishes_files_next_to_the_output_file() {
+    let (_dir, workspace) = workspace(None).await;
+    let mut article = open(&workspace, "en").await;
+    let bundle = Bundle::of(article.preview()).await.unwrap();
+    assert_eq!(
+        bundle.outputs().collect::<Vec<_>>(),
+        [
+            "guides/diagrams/diagram.png",
+            "guides/diagrams/files/my paper.pdf"
+        ]
+    );
+
+    bundle.rewrite(&mut article);
+    assert_eq!(
+        article.content().trim(),
+        "# Diagrams\n\n![A diagram](../guides/diagrams/diagram.png \"diagram.png\") and \
+         [the paper](../guides/diagrams/files/my%20paper.pdf#page=2)\n\n\
+         [elsewhere](https://example.com/diagram.png) [missing](missing.png)"
+    );
+
+    // Variants share the files of the default locale.
+    let mut japanese = open(&workspace, "ja").await;
+    bundle.rewrite(&mut japanese);
+    assert_eq!(
+        japanese.content().trim(),
+        "# 図\n\n![図](../guides/diagrams/diagram.png)"
+    );
+
+    let output = tempfile::tempdir().unwrap();
+    bundle.emit(output.path()).await.unwrap();
+    assert!(
+        output
+            .path()
+            .join("guides/diagrams/files/my paper.pdf")
+            .is_file()
+    );
+}
+
+#[tokio::test]
+async fn keeps_files_beside_pretty_urls() {
+    let (_dir, workspace) = workspace(Some("/{slug}/")).await;
+    let mut article = open(&workspace, "en").await;
+    let bundle = Bundle::of(article.preview()).await.unwrap();
+    assert_eq!(
+        bundle.outputs().next().as_deref(),
+        Some("diagrams/diagram.png")
+    );
+    bundle.rewrite(&mut article);
+    assert!(
+        article
+            .content()
+            .contains("![A diagram](../diagrams/diagram.png \"diagram.png\")"),
+        "{}",
+        article.content()
+    );
+}
+
+#[tokio::test]
+async fn finds_the_source_of_a_published_file() {
+    let (_dir, workspace) = workspace(None).await;
+    let previews = workspace
+        .articles()
+        .map_ok(|article| article.preview().clone())
+        .try_collect::<Vec<ArticlePreview>>()
+        .await
+        .unwrap();
+    let source = bundle::source_for(&previews, "guides/diagrams/diagram.png").unwrap();
+    assert!(source.ends_with("articles/guides/diagrams/diagram.png"));
+    for hidden in [
+        "guides/diagrams/article.md",
+        "guides/diagrams/Article.toml",
+        "guides/diagrams/.DS_Store",
+        "guides/diagrams/missing.png",
+    ] {
+        assert_eq!(bundle::source_for(&previews, hidden), None, "{hidden}");
+    }
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::write;
+use thought::{article::Article, cache::RenderCache, workspace::Workspace};
+
+async fn workspace(front_matter: &str) -> (tempfile::TempDir, Workspace) {
+    let parent = tempfile::tempdir().unwrap();
+    let workspace = Workspace::create(parent.path(), "blog".to_string())
+        .await
+        .unwrap();
+    workspace
+        .create_category(vec!["posts".to_string()], "")
+        .await
+        .unwrap();
+    write(
+        workspace.root(),
+        "articles/posts/cached.md",
+        format!("---\n{front_matter}---\n\n# Cached\n\nBody.\n"),
+    );
+    (parent, workspace)
+}
+
+async fn round_trip(front_matter: &str) {
+    let (_dir, workspace) = workspace(front_matter).await;
+    let article = Article::open(workspace.clone(), ["posts", "cached"].map(String::from))
+        .await
+        .unwrap();
+    let cache = RenderCache::load(workspace.cache_dir().join("cache.redb"))
+        .await
+        .unwrap();
+
+    cache
+        .store(&article, "<p>Body.</p>", "theme")
+        .await
+        .unwrap();
+    assert_eq!(
+        cache.hit(&article, "theme").await.as_deref(),
+        Some("<p>Body.</p>"),
+        "{front_matter}"
+    );
+    assert!(cache.hit(&article, "other theme").await.is_none());
+}
+
+#[tokio::test]
+async fn reads_back_articles_with_default_metadata() {
+    round_trip("").await;
+}
+
+#[tokio::test]
+async fn reads_back_articles_with_every_optional_field_set() {
+    round_trip(
+        "updated: 2024-05-02\naliases: [/old/cached/]\ndraft: true\n\
+         series: { name: Caching, part: 2 }\npinned: true\nweight: -1\n",
+    )
+    .await;
+}
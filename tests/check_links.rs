@@ -0,0 +1,138 @@
+mod common;
+
+use common::{StandIn, write};
+use thought::check::{LinkCheckOptions, LinkProblem, check_links};
+
+fn problems(report: &thought::check::LinkReport, page: &str) -> Vec<(String, LinkProblem)> {
+    report
+        .broken
+        .get(page)
+        .map(|links| {
+            links
+                .iter()
+                .map(|link| (link.href.clone(), link.problem.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn reports_missing_pages_assets_and_anchors() {
+    let build = tempfile::tempdir().unwrap();
+    write(
+        build.path(),
+        "index.html",
+        r##"<a href="posts/hello.html">ok</a>
+<a href="posts/hello.html#intro">anchor</a>
+<a href="posts/hello.html#nowhere">bad anchor</a>
+<a href="posts/missing.html">missing</a>
+<img src="assets/logo.png"><img src="assets/missing%20logo.png">
+<a href="posts/">directory</a><a href="#top">top</a>"##,
+    );
+    write(
+        build.path(),
+        "posts/hello.html",
+        r#"<h2 id="intro">Intro</h2><a href="../index.html">home</a>"#,
+    );
+    write(build.path(), "posts/index.html", "<p>posts</p>");
+    write(build.path(), "assets/logo.png", [0_u8; 4]);
+
+    let report = check_links(build.path(), &LinkCheckOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(report.pages, 3);
+    assert_eq!(report.broken.len(), 1, "{report:#?}");
+    assert_eq!(
+        problems(&report, "index.html"),
+        vec![
+            (
+                "posts/hello.html#nowhere".to_string(),
+                LinkProblem::MissingAnchor {
+                    path: "posts/hello.html".to_string(),
+                    anchor: "nowhere".to_string(),
+                },
+            ),
+            (
+                "posts/missing.html".to_string(),
+                LinkProblem::MissingFile {
+                    path: "posts/missing.html".to_string(),
+                },
+            ),
+            (
+                "assets/missing%20logo.png".to_string(),
+                LinkProblem::MissingFile {
+                    path: "assets/missing logo.png".to_string(),
+                },
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn probes_only_allowlisted_external_links() {
+    let server = StandIn::start(&[("/ok", 200, b"fine"), ("/moved", 301, b"")]);
+    let build = tempfile::tempdir().unwrap();
+    write(
+        build.path(),
+        "index.html",
+        format!(
+            r#"<a href="{ok}">ok</a><a href="{moved}">moved</a><a href="{gone}">gone</a>
+<a href="http://unlisted.invalid/page">unlisted</a>"#,
+            ok = server.url("/ok"),
+            moved = server.url("/moved"),
+            gone = server.url("/gone"),
+        ),
+    );
+
+    let offline = check_links(build.path(), &LinkCheckOptions::default())
+        .await
+        .unwrap();
+    assert!(offline.is_clean(), "external links are skipped by default");
+
+    let options = LinkCheckOptions {
+        external: true,
+        allowlist: vec![server.host().to_string()],
+    };
+    let report = check_links(build.path(), &options).await.unwrap();
+    let broken = problems(&report, "index.html");
+    assert_eq!(broken.len(), 1, "{report:#?}");
+    assert_eq!(broken[0].0, server.url("/gone"));
+    assert!(matches!(&broken[0].1, LinkProblem::External { error } if error.contains("404")));
+}
+
+#[tokio::test]
+async fn checks_every_srcset_candidate() {
+    let build = tempfile::tempdir().unwrap();
+    write(
+        build.path(),
+        "index.html",
+        r#"<picture><source srcset="img/a.webp 1x, img/b.webp 2x">
+<img src="img/a.png" srcset='img/a.png 480w,img/missing.png 960w'></picture>"#,
+    );
+    for asset in ["img/a.webp", "img/b.webp", "img/a.png"] {
+        write(build.path(), asset, [0_u8; 4]);
+    }
+
+    let report = check_links(build.path(), &LinkCheckOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(report.links, 5);
+    assert_eq!(
+        problems(&report, "index.html"),
+        vec![(
+            "img/missing.png".to_string(),
+            LinkProblem::MissingFile {
+                path: "img/missing.png".to_string(),
+            },
+        )]
+    );
+}
+
+#[tokio::test]
+async fn missing_build_directory_is_an_error() {
+    let root = tempfile::tempdir().unwrap();
+    let result = check_links(root.path().join("build"), &LinkCheckOptions::default()).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,76 @@
+//! Helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    path::Path,
+    sync::Arc,
+    thread,
+};
+
+/// A local HTTP stand-in for external hosts, answering `GET` requests from a
+/// fixed table of paths so that network checks run without the network.
+///
+/// Unknown paths get a `404`. The server thread lives until the test process
+/// exits.
+pub struct StandIn {
+    address: String,
+}
+
+impl StandIn {
+    /// Serve `routes`, each a path with its status code and body.
+    pub fn start(routes: &[(&str, u16, &[u8])]) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind stand-in server");
+        let address = listener.local_addr().expect("local address").to_string();
+        let routes = Arc::new(
+            routes
+                .iter()
+                .map(|(path, status, body)| ((*path).to_string(), (*status, body.to_vec())))
+                .collect::<HashMap<_, _>>(),
+        );
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+                let mut request_line = String::new();
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+                // Drain the headers.
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                    line.clear();
+                }
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+                let (status, body) = routes.get(path).cloned().unwrap_or((404, Vec::new()));
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status} Stand-in\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = stream.write_all(&body);
+            }
+        });
+        Self { address }
+    }
+
+    /// `host:port` the server listens on.
+    pub fn host(&self) -> &str {
+        &self.address
+    }
+
+    /// Absolute URL of `path` on the server.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{path}", self.address)
+    }
+}
+
+/// Write `contents` to `root/relative`, creating parent directories.
+pub fn write(root: &Path, relative: &str, contents: impl AsRef<[u8]>) {
+    let path = root.join(relative);
+    std::fs::create_dir_all(path.parent().expect("file has a parent")).expect("create dirs");
+    std::fs::write(path, contents).expect("write file");
+}
@@ -0,0 +1,247 @@
+mod common;
+
+use std::fs;
+
+use common::write;
+use thought::{
+    archive::ExportFormat,
+    article::{Article, ArticleLayout},
+    import::{ImportFormat, ImportOptions},
+    workspace::Workspace,
+};
+
+fn segments(path: &str) -> Vec<String> {
+    path.split('/').map(str::to_string).collect()
+}
+
+#[tokio::test]
+async fn imports_hugo_sections_as_slugified_categories() {
+    let site = tempfile::tempdir().unwrap();
+    write(
+        site.path(),
+        "content/My Notes/Deep Dives/post/index.md",
+        "---\ntitle: A Post\ndate: 2024-05-01\n---\n![Cover](cover.png)\n",
+    );
+    write(
+        site.path(),
+        "content/My Notes/Deep Dives/post/cover.png",
+        b"png",
+    );
+
+    let root = tempfile::tempdir().unwrap();
+    let workspace = Workspace::create(root.path(), "blog".to_string())
+        .await
+        .unwrap();
+    let report = workspace
+        .import(ImportFormat::Hugo, site.path(), &ImportOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(report.imported, ["my-notes/deep-dives/post"]);
+    assert_eq!(report.assets, 1);
+    let dir = workspace.articles_dir().join("my-notes/deep-dives/post");
+    assert_eq!(fs::read(dir.join("cover.png")).unwrap(), b"png");
+}
+
+#[tokio::test]
+async fn imports_a_hexo_site() {
+    let site = tempfile::tempdir().unwrap();
+    write(
+        site.path(),
+        "source/_posts/Hello World.md",
+        "---\ntitle: Hello World\ndate: 2021-07-04 10:00:00\ncategories: [Notes, Rust]\ntags: [intro]\n---\n\
+         Some text.\n\n{% asset_img pic.png A picture %}\n",
+    );
+    write(site.path(), "source/_posts/Hello World/pic.png", b"png");
+    write(
+        site.path(),
+        "source/_posts/Hello World.zh_cn.md",
+        "---\ntitle: 你好\n---\n一些文字。\n",
+    );
+    write(
+        site.path(),
+        "source/_drafts/wip.md",
+        "---\ntitle: Work in progress\n---\nLater.\n",
+    );
+    write(site.path(), "source/_posts/!!!.md", "No slug here.\n");
+
+    let root = tempfile::tempdir().unwrap();
+    let workspace = Workspace::create(root.path(), "blog".to_string())
+        .await
+        .unwrap();
+    let report = workspace
+        .import(ImportFormat::Hexo, site.path(), &ImportOptions::default())
+        .await
+        .unwrap();
+
+    let mut imported = report.imported.clone();
+    imported.sort();
+    assert_eq!(imported, ["notes/rust/hello-world", "wip"]);
+    assert_eq!(report.assets, 1);
+    assert_eq!(report.skipped.len(), 1);
+    assert!(report.skipped[0].source.ends_with("!!!.md"));
+
+    let dir = workspace.articles_dir().join("notes/rust/hello-world");
+    let body = fs::read_to_string(dir.join("article.md")).unwrap();
+    assert!(body.starts_with("# Hello World\n"));
+    assert!(body.contains("![A picture](pic.png)"));
+    assert_eq!(fs::read(dir.join("pic.png")).unwrap(), b"png");
+    assert!(dir.join("zh-CN.md").is_file());
+
+    let article = Article::open(workspace.clone(), segments("notes/rust/hello-world"))
+        .await
+        .unwrap();
+    assert_eq!(article.metadata().tags(), ["intro"]);
+    assert!(article.is_published());
+    assert!(
+        article
+            .translations()
+            .iter()
+            .any(|translation| translation.locale() == "zh-CN")
+    );
+
+    let draft = Article::open(workspace.clone(), segments("wip"))
+        .await
+        .unwrap();
+    assert!(!draft.is_published());
+}
+
+#[tokio::test]
+async fn keeps_wordpress_upload_folders_apart() {
+    let site = tempfile::tempdir().unwrap();
+    write(
+        site.path(),
+        "export.xml",
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/" xmlns:wp="http://wordpress.org/export/1.2/">
+<channel>
+<item>
+<title>Two Images</title>
+<link>https://example.com/two-images/</link>
+<content:encoded><![CDATA[<p><img src="https://example.com/wp-content/uploads/2019/03/image.png"></p>
+<p><img src="https://example.com/wp-content/uploads/2021/07/image.png"></p>]]></content:encoded>
+<wp:post_id>1</wp:post_id>
+<wp:post_name>two-images</wp:post_name>
+<wp:status>publish</wp:status>
+<wp:post_type>post</wp:post_type>
+</item>
+</channel>
+</rss>"#,
+    );
+    write(site.path(), "uploads/2019/03/image.png", b"old");
+    write(site.path(), "uploads/2021/07/image.png", b"new");
+
+    let root = tempfile::tempdir().unwrap();
+    let workspace = Workspace::create(root.path(), "blog".to_string())
+        .await
+        .unwrap();
+    let options = ImportOptions {
+        uploads: Some(site.path().join("uploads")),
+    };
+    let report = workspace
+        .import(
+            ImportFormat::WordPress,
+            &site.path().join("export.xml"),
+            &options,
+        )
+        .await
+        .unwrap();
+    assert_eq!(report.imported, ["two-images"]);
+    assert_eq!(report.assets, 2);
+    assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+
+    let dir = workspace.articles_dir().join("two-images");
+    assert_eq!(fs::read(dir.join("2019/03/image.png")).unwrap(), b"old");
+    assert_eq!(fs::read(dir.join("2021/07/image.png")).unwrap(), b"new");
+    let body = fs::read_to_string(dir.join("article.md")).unwrap();
+    assert!(body.contains("(2019/03/image.png)"));
+    assert!(body.contains("(2021/07/image.png)"));
+    assert!(!body.contains("wp-content"));
+}
+
+#[tokio::test]
+async fn restores_an_export() {
+    let root = tempfile::tempdir().unwrap();
+    let source = Workspace::create(root.path(), "source".to_string())
+        .await
+        .unwrap();
+    let guide = source
+        .create_article("A Helpful Guide", None, ArticleLayout::Directory)
+        .await
+        .unwrap();
+    let dir = guide.dir();
+    fs::write(dir.join("ja.md"), "# ガイド\n\n本文。\n").unwrap();
+    fs::write(dir.join("diagram.png"), [0_u8, 159, 146, 150]).unwrap();
+    write(
+        &source.articles_dir(),
+        "guides/Category.toml",
+        fs::read(source.articles_dir().join("Category.toml")).unwrap(),
+    );
+    write(
+        &source.articles_dir(),
+        "guides/first-steps.md",
+        "# First Steps\n\nHello.\n",
+    );
+    let glossary = "[Thought]\nja = \"Thought\"\n";
+    fs::write(source.glossary_path(), glossary).unwrap();
+
+    for format in [ExportFormat::Json, ExportFormat::Ndjson, ExportFormat::Zip] {
+        let archive = root.path().join(format!("export-{format}"));
+        fs::write(&archive, source.export(format).await.unwrap()).unwrap();
+
+        let target = Workspace::create(root.path(), format!("target-{format}"))
+            .await
+            .unwrap();
+        // Start from an empty root category.
+        for entry in fs::read_dir(target.articles_dir()).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                fs::remove_dir_all(path).unwrap();
+            }
+        }
+        let report = target
+            .import(
+                ImportFormat::ThoughtArchive,
+                &archive,
+                &ImportOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        let mut imported = report.imported.clone();
+        imported.sort();
+        assert_eq!(
+            imported,
+            ["a-helpful-guide", "guides/first-steps", "hello-world"],
+            "{format}"
+        );
+        assert_eq!(report.assets, 1, "{format}");
+        assert!(report.skipped.is_empty(), "{format}: {:?}", report.skipped);
+        assert_eq!(
+            fs::read_to_string(target.glossary_path()).unwrap(),
+            glossary,
+            "{format}"
+        );
+        assert!(
+            report.warnings.contains(
+                &"kept existing `Thought.toml`, which differs from the archive".to_string()
+            ),
+            "{format}: {:?}",
+            report.warnings
+        );
+        for file in [
+            "a-helpful-guide/Article.toml",
+            "a-helpful-guide/article.md",
+            "a-helpful-guide/ja.md",
+            "a-helpful-guide/diagram.png",
+            "guides/Category.toml",
+            "guides/first-steps.md",
+        ] {
+            assert_eq!(
+                fs::read(target.articles_dir().join(file)).unwrap(),
+                fs::read(source.articles_dir().join(file)).unwrap(),
+                "{format}: {file}"
+            );
+        }
+    }
+}
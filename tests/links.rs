@@ -0,0 +1,152 @@
+mod common;
+
+use common::write;
+use futures::TryStreamExt;
+use thought::{article::Article, link::LinkResolver, workspace::Workspace};
+
+fn segments(path: &str) -> Vec<String> {
+    path.split('/').map(str::to_string).collect()
+}
+
+/// A workspace with `guides/a-helpful-guide` (and its Japanese variant),
+/// `posts/linking` and `posts/2024/deep`, where the two posts have `linking`
+/// and `deep` as their bodies.
+async fn workspace(linking: &str, deep: &str) -> (tempfile::TempDir, Workspace) {
+    let parent = tempfile::tempdir().unwrap();
+    let workspace = Workspace::create(parent.path(), "blog".to_string())
+        .await
+        .unwrap();
+    for category in ["guides", "posts", "posts/2024"] {
+        workspace
+            .create_category(segments(category), "")
+            .await
+            .unwrap();
+    }
+    let root = workspace.root().to_path_buf();
+    write(
+        &root,
+        "articles/guides/a-helpful-guide.md",
+        "---\ntitle: A Helpful Guide\n---\n\n## Installation\n",
+    );
+    write(
+        &root,
+        "articles/guides/a-helpful-guide.ja.md",
+        "---\ntitle: 役に立つガイド\n---\n\n本文。\n",
+    );
+    write(
+        &root,
+        "articles/posts/linking.md",
+        format!("---\ntitle: Linking\n---\n\n{linking}"),
+    );
+    write(
+        &root,
+        "articles/posts/2024/deep.md",
+        format!("---\ntitle: Deep\n---\n\n{deep}"),
+    );
+    (parent, workspace)
+}
+
+async fn resolver(workspace: &Workspace) -> LinkResolver {
+    let articles = workspace.articles().try_collect::<Vec<_>>().await.unwrap();
+    LinkResolver::new(articles.iter().map(Article::preview))
+}
+
+/// The body of `path` after rewriting its references, with the references
+/// left unresolved.
+async fn rewrite(workspace: &Workspace, path: &str) -> (String, Vec<String>) {
+    let resolver = resolver(workspace).await;
+    let mut article = Article::open(workspace.clone(), segments(path))
+        .await
+        .unwrap();
+    let unresolved = resolver.rewrite(&mut article);
+    (
+        article.content().trim().to_string(),
+        unresolved.into_iter().map(|link| link.target).collect(),
+    )
+}
+
+#[tokio::test]
+async fn resolves_paths_and_slugs_relative_to_the_linking_article() {
+    let (_dir, workspace) = workspace(
+        "[path](@/guides/a-helpful-guide) and [slug](@a-helpful-guide#installation)\n",
+        "[up](@/posts/linking) and [guide](@a-helpful-guide)\n",
+    )
+    .await;
+
+    let (linking, unresolved) = rewrite(&workspace, "posts/linking").await;
+    assert!(unresolved.is_empty(), "{unresolved:?}");
+    assert_eq!(
+        linking,
+        "[path](../guides/a-helpful-guide.html) and \
+         [slug](../guides/a-helpful-guide.html#installation)"
+    );
+
+    // One more directory level means one more `../`.
+    let (deep, _) = rewrite(&workspace, "posts/2024/deep").await;
+    assert_eq!(
+        deep,
+        "[up](../../posts/linking.html) and [guide](../../guides/a-helpful-guide.html)"
+    );
+}
+
+#[tokio::test]
+async fn prefers_the_variant_in_the_linking_locale() {
+    let (_dir, workspace) = workspace("", "").await;
+    let resolver = resolver(&workspace).await;
+    let japanese = resolver.resolve("/guides/a-helpful-guide", "ja").unwrap();
+    assert_ne!(japanese, "guides/a-helpful-guide.html");
+    assert_eq!(resolver.resolve("a-helpful-guide", "ja"), Some(japanese));
+    // Locales without a variant fall back to the default one.
+    assert_eq!(
+        resolver.resolve("a-helpful-guide", "fr"),
+        Some("guides/a-helpful-guide.html")
+    );
+}
+
+#[tokio::test]
+async fn rewrites_reference_definitions() {
+    let (_dir, workspace) = workspace(
+        "[see @a-helpful-guide][guide] and [guide]\n\n\
+         [guide]: @a-helpful-guide \"@a-helpful-guide\"\n",
+        "",
+    )
+    .await;
+
+    let (linking, unresolved) = rewrite(&workspace, "posts/linking").await;
+    assert!(unresolved.is_empty(), "{unresolved:?}");
+    assert_eq!(
+        linking,
+        "[see @a-helpful-guide][guide] and [guide]\n\n\
+         [guide]: ../guides/a-helpful-guide.html \"@a-helpful-guide\""
+    );
+}
+
+#[tokio::test]
+async fn only_rewrites_the_destination() {
+    let (_dir, workspace) = workspace(
+        "[@a-helpful-guide](@a-helpful-guide \"@a-helpful-guide\")\n\n\
+         [`](@x)`](<@/guides/a-helpful-guide>)\n\n\
+         [escaped](@a\\-helpful\\-guide) [encoded](@a%2Dhelpful-guide)\n",
+        "",
+    )
+    .await;
+
+    let (linking, unresolved) = rewrite(&workspace, "posts/linking").await;
+    assert!(unresolved.is_empty(), "{unresolved:?}");
+    assert_eq!(
+        linking,
+        "[@a-helpful-guide](../guides/a-helpful-guide.html \"@a-helpful-guide\")\n\n\
+         [`](@x)`](<../guides/a-helpful-guide.html>)\n\n\
+         [escaped](../guides/a-helpful-guide.html) [encoded](../guides/a-helpful-guide.html)"
+    );
+}
+
+#[tokio::test]
+async fn leaves_missing_targets_untouched() {
+    let body = "[gone](@/guides/missing) [nobody](@nowhere#top)";
+    let (_dir, workspace) = workspace(body, "").await;
+
+    let (linking, unresolved) = rewrite(&workspace, "posts/linking").await;
+    assert_eq!(linking, body);
+    assert_eq!(unresolved, ["/guides/missing", "nowhere#top"]);
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::write;
+use futures::TryStreamExt;
+use thought::{article::Article, redirect::RedirectTable, workspace::Workspace};
+
+/// The redirect table of a workspace whose only post has `aliases`.
+async fn table(aliases: &str) -> Result<RedirectTable, String> {
+    let parent = tempfile::tempdir().unwrap();
+    let workspace = Workspace::create(parent.path(), "blog".to_string())
+        .await
+        .unwrap();
+    workspace
+        .create_category(vec!["posts".to_string()], "")
+        .await
+        .unwrap();
+    write(
+        workspace.root(),
+        "articles/posts/moved.md",
+        format!("---\naliases: {aliases}\n---\n\n# Moved\n"),
+    );
+    let articles = workspace.articles().try_collect::<Vec<_>>().await.unwrap();
+    RedirectTable::new(articles.iter().map(Article::preview)).map_err(|err| err.to_string())
+}
+
+#[tokio::test]
+async fn redirects_aliases_to_the_article() {
+    let table = table("[/old/moved/, /old/moved.html]").await.unwrap();
+    assert_eq!(table.target("/old/moved"), Some("posts/moved.html"));
+    assert_eq!(table.target("/old/moved.html"), Some("posts/moved.html"));
+    assert_eq!(table.target("/posts/moved.html"), None);
+}
+
+#[tokio::test]
+async fn rejects_aliases_over_reserved_files() {
+    for alias in [
+        "/assets/style.css",
+        "/assets/thought-search/thought-search.js",
+        "/redirects.nginx.conf",
+        "/hello-world.html",
+    ] {
+        let error = table(alias).await.unwrap_err();
+        assert!(error.contains(alias), "{error}");
+    }
+}
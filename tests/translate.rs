@@ -0,0 +1,108 @@
+#![cfg(feature = "cli")]
+
+mod common;
+
+use std::{fs, path::Path, process::Command};
+
+use common::write;
+use thought::{article::Article, workspace::Workspace};
+
+fn thought(root: &Path, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_thought"))
+        .args(args)
+        .current_dir(root)
+        .output()
+        .expect("run thought")
+}
+
+#[tokio::test]
+async fn translates_with_the_dictionary_provider() {
+    let parent = tempfile::tempdir().unwrap();
+    let workspace = Workspace::create(parent.path(), "blog".to_string())
+        .await
+        .unwrap();
+    let root = workspace.root().to_path_buf();
+    let manifest = fs::read_to_string(root.join("Thought.toml")).unwrap();
+    write(
+        &root,
+        "Thought.toml",
+        format!(
+            "{manifest}\n[translation]\nprovider = \"dictionary\"\n\n\
+             [translation.dictionary]\nhello = \"hola\"\nworld = \"mundo\"\n\
+             thought = \"pensamiento\"\nopen = \"abrir\"\n"
+        ),
+    );
+    write(
+        &root,
+        "Glossary.toml",
+        "[Thought]\ntranslate = false\n\n[\"pull request\"]\nes = \"solicitud de cambios\"\n",
+    );
+    write(
+        &root,
+        "articles/greeting.md",
+        "---\ntitle: Hello World\ndescription: Hello from Thought\n---\n\n\
+         Hello world, open a pull request in [Thought](https://example.com/world).\n\n\
+         ```rust\nlet hello = \"world\";\n```\n",
+    );
+
+    let output = thought(&root, &["translate", "es"]);
+    assert!(output.status.success(), "{output:?}");
+
+    let written = fs::read_to_string(root.join("articles/greeting.es.md")).unwrap();
+    let greeting = Article::open(workspace.clone(), vec!["greeting".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(
+        written,
+        format!(
+            "+++\ntitle = \"hola mundo\"\ndescription = \"hola from Thought\"\n\
+             source_hash = \"{}\"\n+++\n\n\
+             hola mundo, abrir a solicitud de cambios in [Thought](https://example.com/world).\n\n\
+             ```rust\nlet hello = \"world\";\n```\n",
+            greeting.preview().source_hash()
+        )
+    );
+    let translation = greeting
+        .translations()
+        .iter()
+        .find(|translation| translation.locale() == "es")
+        .expect("es translation");
+    assert_eq!(translation.title(), "hola mundo");
+    assert!(!translation.is_stale());
+    assert!(
+        thought(&root, &["translate", "--check", "es"])
+            .status
+            .success()
+    );
+
+    // Editing the source makes the translation outdated.
+    write(
+        &root,
+        "articles/greeting.md",
+        "---\ntitle: Hello World\n---\n\nHello again.\n",
+    );
+    let greeting = Article::open(workspace.clone(), vec!["greeting".to_string()])
+        .await
+        .unwrap();
+    assert!(
+        greeting
+            .translations()
+            .iter()
+            .any(|translation| translation.locale() == "es" && translation.is_stale())
+    );
+    let check = thought(&root, &["translate", "--check", "es"]);
+    assert!(!check.status.success());
+    assert!(
+        String::from_utf8_lossy(&check.stdout).contains("greeting.es.md: outdated es translation")
+    );
+
+    // Translating again brings it up to date.
+    assert!(thought(&root, &["translate", "es"]).status.success());
+    let written = fs::read_to_string(root.join("articles/greeting.es.md")).unwrap();
+    assert!(written.ends_with("hola again.\n"), "{written}");
+    assert!(
+        thought(&root, &["translate", "--check", "es"])
+            .status
+            .success()
+    );
+}